@@ -0,0 +1,15 @@
+//! Fuzz target for message parsing.
+//!
+//! Feeds arbitrary data into `Message` and parses everything there is to
+//! parse. Parse errors are fine, panics are not. Run with
+//! `cargo fuzz run parse_message` from the crate’s root directory.
+
+#![no_main]
+#[macro_use] extern crate libfuzzer_sys;
+extern crate domain;
+
+use domain::bits::message::parse_everything;
+
+fuzz_target!(|data: &[u8]| {
+    parse_everything(data);
+});
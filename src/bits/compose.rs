@@ -100,6 +100,12 @@ pub struct Composer {
     /// Has the vector been truncated yet?
     truncated: bool,
 
+    /// The number of octets reserved at the end of the message.
+    ///
+    /// These octets are not available for regular composing but only
+    /// through `use_reserved()`.
+    reserved: usize,
+
     /// A hashmap storing the indexes of domain names for compression.
     ///
     /// If this is `None`, we don’t do compression at all.
@@ -141,6 +147,7 @@ impl Composer {
             start: start,
             checkpoint: None,
             truncated: false,
+            reserved: 0,
             compress: if compress { Some(HashMap::new()) }
                       else { None }
         }
@@ -211,6 +218,11 @@ impl Composer {
         self.vec.len()
     }
 
+    /// Returns the composition mode.
+    pub fn mode(&self) -> &ComposeMode {
+        &self.mode
+    }
+
     /// Returns the position where the message starts.
     ///
     /// This is identical to having called `self.pos()` right after `self`
@@ -242,6 +254,66 @@ impl Composer {
     pub fn is_truncated(&self) -> bool {
         self.truncated
     }
+
+    /// Reserves `len` octets at the end of the message.
+    ///
+    /// Reserved octets are not available for regular composing, so data
+    /// that would go into them leads to truncation. They can later be
+    /// used through [`use_reserved()`] even if the composer has been
+    /// truncated in the meantime, or given back via [`release()`].
+    ///
+    /// Returns an error if the message doesn’t have enough space left. In
+    /// this case, nothing is reserved and the composer isn’t marked as
+    /// truncated.
+    ///
+    /// [`use_reserved()`]: #method.use_reserved
+    /// [`release()`]: #method.release
+    pub fn reserve(&mut self, len: usize) -> ComposeResult<()> {
+        if let Some(maxlen) = self.maxlen() {
+            if maxlen < self.vec.len() + self.start + self.reserved + len {
+                return Err(ComposeError::SizeExceeded)
+            }
+        }
+        self.reserved += len;
+        Ok(())
+    }
+
+    /// Gives back `len` previously reserved octets.
+    ///
+    /// Returns an error if less than `len` octets are currently reserved.
+    /// In this case, nothing is released.
+    pub fn release(&mut self, len: usize) -> ComposeResult<()> {
+        match self.reserved.checked_sub(len) {
+            Some(reserved) => {
+                self.reserved = reserved;
+                Ok(())
+            }
+            None => Err(ComposeError::Overflow)
+        }
+    }
+
+    /// Composes data into `len` previously reserved octets.
+    ///
+    /// The reserved octets are released and `op` is run even if the
+    /// composer has been truncated. If `op` composes more than `len`
+    /// octets and this exceeds the size limit, everything `op` composed
+    /// is removed again and an error is returned. The truncation state of
+    /// the composer is not changed by `op`.
+    ///
+    /// Returns an error without running `op` if less than `len` octets are
+    /// currently reserved.
+    pub fn use_reserved<F>(&mut self, len: usize, op: F) -> ComposeResult<()>
+                        where F: FnOnce(&mut Self) -> ComposeResult<()> {
+        try!(self.release(len));
+        let checkpoint = self.checkpoint;
+        let truncated = self.truncated;
+        self.checkpoint = Some(self.pos());
+        self.truncated = false;
+        let res = op(self);
+        self.checkpoint = checkpoint;
+        self.truncated = truncated;
+        res
+    }
 }
 
 
@@ -353,6 +425,18 @@ impl Composer {
             self.compose_dname_simple(name)
         }
     }
+
+    /// Pushes a domain name in canonical form to the end of the message.
+    ///
+    /// The canonical form of a domain name is defined in section 6.2 of
+    /// [RFC 4034]. It is never compressed and all ASCII letters are
+    /// converted to lowercase.
+    ///
+    /// [RFC 4034]: https://tools.ietf.org/html/rfc4034
+    pub fn compose_dname_canonical<N: DName>(&mut self, name: &N)
+                                             -> ComposeResult<()> {
+        self.compose_dname_simple(&name.to_cow().to_lowercase())
+    }
 }
 
 
@@ -364,12 +448,11 @@ impl Composer {
     /// This method can be used with `try!` for convenience.
     fn can_push(&mut self, len: usize) -> ComposeResult<()> {
         if self.truncated { return Err(ComposeError::SizeExceeded) }
-        let maxlen = match self.mode {
-            ComposeMode::Unlimited => return Ok(()),
-            ComposeMode::Limited(len) => len,
-            ComposeMode::Stream => 0xFFFF,
+        let maxlen = match self.maxlen() {
+            Some(maxlen) => maxlen,
+            None => return Ok(())
         };
-        if maxlen < self.vec.len() + self.start + len {
+        if maxlen < self.vec.len() + self.start + self.reserved + len {
             self.checkpoint.map(|len| self.vec.truncate(len));
             self.truncated = true;
             return Err(ComposeError::SizeExceeded)
@@ -377,6 +460,15 @@ impl Composer {
         Ok(())
     }
 
+    /// Returns the maximum size of the message or `None` if unlimited.
+    fn maxlen(&self) -> Option<usize> {
+        match self.mode {
+            ComposeMode::Unlimited => None,
+            ComposeMode::Limited(len) => Some(len),
+            ComposeMode::Stream => Some(0xFFFF),
+        }
+    }
+
     /// Pushes a domain name ignoring compression entirely.
     ///
     /// The name will be pushed uncompressed and no entries will be made
@@ -848,6 +940,39 @@ mod test {
                    b"\x12\x34");
     }
 
+    #[test]
+    fn reserved() {
+        let mut c = Composer::new(ComposeMode::Limited(6), false);
+        c.compose_u16(0x1234).unwrap();
+        c.reserve(2).unwrap();
+        assert_eq!(c.reserve(3), Err(ComposeError::SizeExceeded));
+        assert!(!c.is_truncated());
+        c.mark_checkpoint();
+        assert_eq!(c.compose_u32(0xdeadbeef),
+                   Err(ComposeError::SizeExceeded));
+        assert!(c.is_truncated());
+        c.use_reserved(2, |c| c.compose_u16(0x5678)).unwrap();
+        assert!(c.is_truncated());
+        assert_eq!(c.release(2), Err(ComposeError::Overflow));
+        assert_eq!(c.use_reserved(2, |c| c.compose_u16(0x9abc)),
+                   Err(ComposeError::Overflow));
+        assert_eq!(c.finish(), b"\x12\x34\x56\x78");
+    }
+
+    #[test]
+    fn reserved_after_rollback() {
+        let mut c = Composer::new(ComposeMode::Limited(6), false);
+        c.compose_u16(0x1234).unwrap();
+        let mut c = c.snapshot();
+        c.reserve(2).unwrap();
+        c.compose_u16(0x5678).unwrap();
+        let mut c = c.rollback();
+        assert_eq!(c.release(4), Err(ComposeError::Overflow));
+        c.release(2).unwrap();
+        assert_eq!(c.release(2), Err(ComposeError::Overflow));
+        assert_eq!(c.finish(), b"\x12\x34");
+    }
+
     #[test]
     fn stream_mode() {
         let mut c = Composer::new(ComposeMode::Stream, false);
@@ -18,10 +18,11 @@
 //! [`HeaderSection`]: struct.HeaderSection.html
 //! [RFC 1035]: https://tools.ietf.org/html/rfc1035
 
-use std::mem;
+use std::{fmt, mem, ops};
 use byteorder::{BigEndian, ByteOrder};
 use ::iana::{Opcode, Rcode};
 use super::compose::{ComposeError, ComposeResult};
+use super::parse::{ParseError, ParseResult};
 
 
 //------------ Header --------------------------------------------------
@@ -97,6 +98,22 @@ impl Header {
     pub fn as_bytes(&self) -> &[u8] {
         &self.inner
     }
+
+    /// Parses a copy of the header from the beginning of a message.
+    ///
+    /// Only the first four octets of `bytes` are looked at, so this works
+    /// for messages whose remainder is missing or broken. If you need the
+    /// section counts, too, use [`HeaderSection::parse()`] instead.
+    ///
+    /// Fails if `bytes` is too short to contain the header.
+    ///
+    /// [`HeaderSection::parse()`]: struct.HeaderSection.html#method.parse
+    pub fn parse(bytes: &[u8]) -> ParseResult<Header> {
+        if bytes.len() < mem::size_of::<Header>() {
+            return Err(ParseError::UnexpectedEnd)
+        }
+        Ok(Header::from_message(bytes).clone())
+    }
 }
 
 
@@ -220,6 +237,24 @@ impl Header {
     /// Sets the value of the CD bit.
     pub fn set_cd(&mut self, set: bool) { self.set_bit(3, 4, set) }
 
+    /// Returns all flags at once.
+    ///
+    /// See [`HeaderFlags`] for more information.
+    ///
+    /// [`HeaderFlags`]: struct.HeaderFlags.html
+    pub fn flags(&self) -> HeaderFlags {
+        HeaderFlags::from_bits(BigEndian::read_u16(&self.inner[2..]))
+    }
+
+    /// Sets all flags at once.
+    ///
+    /// The opcode and rcode fields are left unchanged.
+    pub fn set_flags(&mut self, flags: HeaderFlags) {
+        let value = BigEndian::read_u16(&self.inner[2..]) & !HeaderFlags::MASK
+                  | flags.bits();
+        BigEndian::write_u16(&mut self.inner[2..], value)
+    }
+
     /// Returns the value of the RCODE field.
     ///
     /// The *response code* is used in a response to indicate what happened
@@ -256,6 +291,136 @@ impl Header {
 }
 
 
+//------------ HeaderFlags ---------------------------------------------------
+
+/// The flags of a DNS message header.
+///
+/// This type is a set of the single bit flags of the [`Header`] for code
+/// that deals with several of them at once. Each flag is available as an
+/// associated constant. Flags can be combined using the `|` operator and
+/// checked, added, or removed through [`contains()`], [`insert()`], and
+/// [`remove()`].
+///
+/// Internally, the flags are kept at their positions within the second 16
+/// bit word of the header with the opcode and rcode bits always cleared.
+///
+/// The `Display` implementation renders the set flags as lowercase
+/// mnemonics separated by spaces in the way `dig` does, e.g., `qr aa rd`.
+///
+/// [`Header`]: struct.Header.html
+/// [`contains()`]: #method.contains
+/// [`insert()`]: #method.insert
+/// [`remove()`]: #method.remove
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct HeaderFlags(u16);
+
+impl HeaderFlags {
+    /// The QR bit marking a message as a response.
+    pub const QR: HeaderFlags = HeaderFlags(0x8000);
+
+    /// The AA bit marking an authoritative answer.
+    pub const AA: HeaderFlags = HeaderFlags(0x0400);
+
+    /// The TC bit marking a truncated message.
+    pub const TC: HeaderFlags = HeaderFlags(0x0200);
+
+    /// The RD bit asking for recursion.
+    pub const RD: HeaderFlags = HeaderFlags(0x0100);
+
+    /// The RA bit stating that recursion is available.
+    pub const RA: HeaderFlags = HeaderFlags(0x0080);
+
+    /// The reserved Z bit.
+    pub const Z: HeaderFlags = HeaderFlags(0x0040);
+
+    /// The AD bit marking authentic data.
+    pub const AD: HeaderFlags = HeaderFlags(0x0020);
+
+    /// The CD bit disabling DNSSEC checking.
+    pub const CD: HeaderFlags = HeaderFlags(0x0010);
+
+    /// All the flag bits within the 16 bit word.
+    const MASK: u16 = 0x87F0;
+
+    /// Creates an empty set of flags.
+    pub fn empty() -> Self {
+        HeaderFlags(0)
+    }
+
+    /// Creates the flags from the second 16 bit word of a header.
+    ///
+    /// The bits of the opcode and rcode fields are ignored.
+    pub fn from_bits(bits: u16) -> Self {
+        HeaderFlags(bits & HeaderFlags::MASK)
+    }
+
+    /// Returns the flags as bits of the second 16 bit word of a header.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns whether no flag is set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether all flags in `other` are set.
+    pub fn contains(self, other: HeaderFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Sets all flags in `other`.
+    pub fn insert(&mut self, other: HeaderFlags) {
+        self.0 |= other.0
+    }
+
+    /// Clears all flags in `other`.
+    pub fn remove(&mut self, other: HeaderFlags) {
+        self.0 &= !other.0
+    }
+}
+
+
+//--- BitOr and BitOrAssign
+
+impl ops::BitOr for HeaderFlags {
+    type Output = HeaderFlags;
+
+    fn bitor(self, other: HeaderFlags) -> HeaderFlags {
+        HeaderFlags(self.0 | other.0)
+    }
+}
+
+impl ops::BitOrAssign for HeaderFlags {
+    fn bitor_assign(&mut self, other: HeaderFlags) {
+        self.insert(other)
+    }
+}
+
+
+//--- Display
+
+impl fmt::Display for HeaderFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = [
+            (HeaderFlags::QR, "qr"), (HeaderFlags::AA, "aa"),
+            (HeaderFlags::TC, "tc"), (HeaderFlags::RD, "rd"),
+            (HeaderFlags::RA, "ra"), (HeaderFlags::Z, "z"),
+            (HeaderFlags::AD, "ad"), (HeaderFlags::CD, "cd"),
+        ];
+        let mut first = true;
+        for &(flag, name) in &names {
+            if self.contains(flag) {
+                if first { first = false }
+                else { f.write_str(" ")? }
+                f.write_str(name)?
+            }
+        }
+        Ok(())
+    }
+}
+
+
 //------------ HeaderCounts -------------------------------------------------
 
 /// The section count part of the header section of a DNS message.
@@ -549,6 +714,20 @@ impl HeaderSection {
     pub fn as_bytes(&self) -> &[u8] {
         &self.inner
     }
+
+    /// Parses a copy of the header section from the beginning of a message.
+    ///
+    /// Only the first twelve octets of `bytes` are looked at, so this is a
+    /// cheap way to learn the ID, opcode, rcode, and section counts of a
+    /// message without touching the possibly malformed rest of it.
+    ///
+    /// Fails if `bytes` is too short to contain the header section.
+    pub fn parse(bytes: &[u8]) -> ParseResult<HeaderSection> {
+        if bytes.len() < mem::size_of::<HeaderSection>() {
+            return Err(ParseError::UnexpectedEnd)
+        }
+        Ok(HeaderSection::from_message(bytes).clone())
+    }
 }
 
 
@@ -581,6 +760,7 @@ impl HeaderSection {
 
 #[cfg(test)]
 mod test {
+    use byteorder::{BigEndian, ByteOrder};
     use super::*;
     use iana::{Opcode, Rcode};
 
@@ -613,6 +793,23 @@ mod test {
         HeaderSection::from_message(b"1234");
     }
 
+    #[test]
+    fn parse() {
+        let bytes = b"\x12\x34\x81\x83\x00\x01\x00\x02\x00\x03\x00\x04";
+        let header = Header::parse(bytes).unwrap();
+        assert_eq!(header.id(), 0x1234);
+        assert!(header.qr());
+        assert_eq!(header.opcode(), Opcode::Query);
+        assert_eq!(header.rcode(), Rcode::NXDomain);
+        assert!(Header::parse(b"\x12\x34\x81").is_err());
+
+        let section = HeaderSection::parse(bytes).unwrap();
+        assert_eq!(section.header(), &header);
+        assert_eq!(section.counts().qdcount(), 1);
+        assert_eq!(section.counts().arcount(), 4);
+        assert!(HeaderSection::parse(&bytes[..11]).is_err());
+    }
+
     #[test]
     fn header() {
         test_field!(id, set_id, 0, 0x1234);
@@ -628,6 +825,45 @@ mod test {
         test_field!(rcode, set_rcode, Rcode::NoError, Rcode::Refused);
     }
 
+    #[test]
+    fn flags() {
+        let mut flags = HeaderFlags::QR | HeaderFlags::AA;
+        flags |= HeaderFlags::RD;
+        assert!(flags.contains(HeaderFlags::QR | HeaderFlags::RD));
+        assert!(!flags.contains(HeaderFlags::TC));
+        assert_eq!(flags.to_string(), "qr aa rd");
+        flags.remove(HeaderFlags::AA);
+        flags.insert(HeaderFlags::Z | HeaderFlags::CD);
+        assert_eq!(flags.to_string(), "qr rd z cd");
+        assert_eq!(HeaderFlags::empty().to_string(), "");
+
+        let mut h = Header::new();
+        h.set_opcode(Opcode::Notify);
+        h.set_rcode(Rcode::Refused);
+        h.set_flags(flags);
+        assert!(h.qr() && h.rd() && h.z() && h.cd());
+        assert!(!h.aa() && !h.tc() && !h.ra() && !h.ad());
+        assert_eq!(h.opcode(), Opcode::Notify);
+        assert_eq!(h.rcode(), Rcode::Refused);
+        assert_eq!(h.flags(), flags);
+
+        for bits in 0..0x1_0000u32 {
+            let bits = bits as u16;
+            let mut h = Header::new();
+            h.inner[2] = (bits >> 8) as u8;
+            h.inner[3] = bits as u8;
+            let flags = h.flags();
+            assert_eq!(flags.bits(), bits & 0x87F0);
+            let mut copy = Header::new();
+            copy.set_flags(flags);
+            assert_eq!(copy.flags(), flags);
+            h.set_flags(HeaderFlags::empty());
+            assert_eq!(h.flags(), HeaderFlags::empty());
+            h.set_flags(flags);
+            assert_eq!(BigEndian::read_u16(&h.inner[2..]), bits);
+        }
+    }
+
     #[test]
     fn counts() {
         let mut c = HeaderCounts { inner: [ 1, 2, 3, 4, 5, 6, 7, 8 ] };
@@ -14,13 +14,21 @@
 
 
 use std::collections::HashMap;
-use std::{borrow, mem, ops};
+use std::{borrow, cmp, fmt, io, mem, ops};
 use std::marker::PhantomData;
-use ::iana::{Rcode, Rtype};
-use ::rdata::Cname;
-use super::{HeaderSection, GenericRecord, Header, HeaderCounts, ParsedDName,
-            ParsedRecordData, Parser, ParseError, ParseResult, Question,
-            Record};
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use ::iana::{OptRcode, Rtype};
+use ::rdata::{AllRecordData, Cname};
+use ::rdata::parsed::Ns;
+use super::message_builder::{TruncationPolicy, TruncationReport,
+                             truncate_message};
+use super::opt::{Opt, OptRecord};
+use super::opt::rfc8914::{Ede, ExtendedError};
+use super::{Composer, ComposeMode, ComposeResult, DName, DNameBuf,
+            HeaderSection, GenericRecord, Header, HeaderCounts,
+            MessageBuilder, ParsedDName, ParsedRecordData, Parser,
+            ParseError, ParseResult, Question, RdataRegistry, Record,
+            RecordData, RegistryRecord};
 
 //------------ Message -------------------------------------------------------
 
@@ -118,6 +126,10 @@ use super::{HeaderSection, GenericRecord, Header, HeaderCounts, ParsedDName,
 /// }
 /// ```
 ///
+/// Parsing is meant to never panic, no matter how broken the message
+/// is. Any inconsistency results in an error being returned instead. The
+/// `fuzz` directory contains a [cargo-fuzz] target to check this.
+///
 /// [`additional()`]: #method.additional
 /// [`answer()`]: #method.answer
 /// [`authority()`]: #method.authority
@@ -137,6 +149,7 @@ use super::{HeaderSection, GenericRecord, Header, HeaderCounts, ParsedDName,
 /// [domain::rdata::parsed]: ../../rdata/parsed/index.html
 /// [Header Section]: #header-section
 /// [RFC 1035]: https://tools.ietf.org/html/rfc1035
+/// [cargo-fuzz]: https://github.com/rust-fuzz/cargo-fuzz
 pub struct Message {
     inner: [u8]
 }
@@ -211,12 +224,12 @@ impl Message {
 
     /// Returns whether the rcode is NoError.
     pub fn no_error(&self) -> bool {
-        self.header().rcode() == Rcode::NoError
+        self.header().rcode().is_success()
     }
 
     /// Returns whether the rcode is one of the error values.
     pub fn is_error(&self) -> bool {
-        self.header().rcode() != Rcode::NoError
+        self.header().rcode().is_error()
     }
 }
 
@@ -276,6 +289,38 @@ impl Message {
                                                .map(Option::unwrap));
         Ok((question, answer, authority, additional))
     }
+
+    /// Parses the records of a message using a registry of parsers.
+    ///
+    /// Returns the records of the answer, authority, and additional
+    /// sections in this order. The data of each record is parsed by the
+    /// parser registered for its record type in `registry` or left as
+    /// generic data if there isn’t one. Fails if any of the records or the
+    /// data of a record with a registered parser cannot be parsed.
+    pub fn parse_with_registry<'a>(&'a self, registry: &RdataRegistry)
+                                   -> ParseResult<(Vec<RegistryRecord<'a>>,
+                                                   Vec<RegistryRecord<'a>>,
+                                                   Vec<RegistryRecord<'a>>)> {
+        let (_, answer, authority, additional) = self.sections()?;
+        Ok((parse_section(answer, registry)?,
+            parse_section(authority, registry)?,
+            parse_section(additional, registry)?))
+    }
+}
+
+/// Parses all records of a section with a registry.
+///
+/// This is a helper for `Message::parse_with_registry()`.
+fn parse_section<'a>(section: RecordSection<'a>, registry: &RdataRegistry)
+                     -> ParseResult<Vec<RegistryRecord<'a>>> {
+    let mut res = Vec::new();
+    for record in section {
+        let record = record?;
+        let data = registry.parse(record.data().clone())?;
+        res.push(Record::new(record.name().clone(), record.class(),
+                             record.ttl(), data));
+    }
+    Ok(res)
 }
 
 /// # Helpers for Common Tasks
@@ -351,6 +396,308 @@ impl Message {
             }
         }
     }
+
+    /// Returns the first extended DNS error of the message, if any.
+    ///
+    /// The error is taken from the first EDE option of the first OPT
+    /// record in the additional section and is combined with the full
+    /// extended rcode of the message.
+    ///
+    /// Returns `None` if there is no such option or there was a parse
+    /// error.
+    pub fn extended_error(&self) -> Option<ExtendedError> {
+        let additional = match self.additional() {
+            Ok(additional) => additional,
+            Err(..) => return None
+        };
+        let record = match additional.limit_to::<Opt>().next() {
+            None | Some(Err(..)) => return None,
+            Some(Ok(record)) => record
+        };
+        let rcode = OptRcode::from_parts(self.header().rcode(),
+                                         (record.ttl() >> 24) as u8);
+        match record.data().iter::<Ede<&[u8]>>().next() {
+            None | Some(Err(..)) => None,
+            Some(Ok(ede)) => Some(ExtendedError::new(rcode, ede))
+        }
+    }
+
+    /// Returns the codes of the EDNS options present in the message.
+    ///
+    /// The codes are taken from the first OPT record in the additional
+    /// section in the order they appear. The option data isn’t parsed.
+    ///
+    /// Returns an empty vec if there is no OPT record or there was a parse
+    /// error.
+    pub fn edns_option_codes(&self) -> Vec<u16> {
+        let additional = match self.additional() {
+            Ok(additional) => additional,
+            Err(..) => return Vec::new()
+        };
+        match additional.limit_to::<Opt>().next() {
+            Some(Ok(record)) => record.data().option_codes(),
+            None | Some(Err(..)) => Vec::new()
+        }
+    }
+}
+
+
+/// # Validation
+///
+impl Message {
+    /// Checks that records only appear in the sections they belong in.
+    ///
+    /// The following rules are applied:
+    ///
+    /// * OPT records must only appear in the additional section and there
+    ///   must be at most one of them,
+    /// * a TSIG record must be the last record of the additional section,
+    ///   and
+    /// * the answer section of a referral, i.e., a non-authoritative
+    ///   response with NS records in the authority section, must not
+    ///   contain an SOA record.
+    ///
+    /// Returns all violations of these rules in the order they appear in
+    /// the message. Checking stops silently at the first record that
+    /// cannot be parsed.
+    pub fn validate_sections(&self) -> Vec<SectionViolation> {
+        let mut res = Vec::new();
+        let referral = self.is_referral();
+        let mut section = match self.answer() {
+            Ok(section) => section,
+            Err(..) => return res
+        };
+        let mut opt_seen = false;
+        loop {
+            let which = section.section;
+            let count = which.count(self.counts()) as usize;
+            for (index, record) in (&mut section).enumerate() {
+                let rtype = match record {
+                    Ok(record) => record.rtype(),
+                    Err(..) => return res
+                };
+                match rtype {
+                    Rtype::Opt => {
+                        if which != Section::Additional {
+                            res.push(SectionViolation::MisplacedOpt(which))
+                        }
+                        else if opt_seen {
+                            res.push(SectionViolation::DuplicateOpt)
+                        }
+                        else {
+                            opt_seen = true
+                        }
+                    }
+                    Rtype::Tsig => {
+                        if which != Section::Additional || index + 1 != count {
+                            res.push(SectionViolation::MisplacedTsig(which))
+                        }
+                    }
+                    Rtype::Soa if which == Section::Answer && referral => {
+                        res.push(SectionViolation::SoaInReferral)
+                    }
+                    _ => { }
+                }
+            }
+            section = match section.next_section() {
+                Ok(Some(section)) => section,
+                _ => return res
+            }
+        }
+    }
+
+    /// Returns the delegation if the message is a referral.
+    ///
+    /// A message is considered a referral if it is a non-authoritative
+    /// response with NS records in its authority section. The zone of the
+    /// delegation is the owner of the first of these records. NS records
+    /// for other owners are ignored.
+    ///
+    /// Returns `None` if the message isn’t a referral or cannot be parsed.
+    pub fn delegation<'a>(&'a self) -> Option<Delegation<'a>> {
+        if !self.is_referral() {
+            return None
+        }
+        let mut zone = None;
+        let mut nameservers = Vec::new();
+        for record in self.authority().ok()?.limit_to::<Ns>() {
+            let record = record.ok()?;
+            if zone.is_none() {
+                zone = Some(record.name().clone())
+            }
+            if zone.as_ref() == Some(record.name()) {
+                nameservers.push(record.data().nsdname().clone())
+            }
+        }
+        Some(Delegation {
+            zone: zone?,
+            nameservers: nameservers,
+            additional: self.additional().ok()?
+        })
+    }
+
+    /// Returns whether the message looks like a referral.
+    fn is_referral(&self) -> bool {
+        if !self.header().qr() || self.header().aa() {
+            return false
+        }
+        match self.authority() {
+            Ok(mut authority) => authority.any(|record| {
+                record.map(|record| record.rtype() == Rtype::Ns)
+                      .unwrap_or(false)
+            }),
+            Err(..) => false
+        }
+    }
+}
+
+
+/// # Canonical Form
+///
+impl Message {
+    /// Returns a copy of the message in a deterministic, canonical form.
+    ///
+    /// All domain names, both owner names and the names embedded in the
+    /// data of those record types known to contain them, are converted to
+    /// lowercase and composed without name compression. The records of
+    /// each RRset are sorted into the canonical order defined in section
+    /// 6.3 of [RFC 4034]. The RRsets themselves stay in the order of their
+    /// first appearance within their section.
+    ///
+    /// Two messages with the same content regardless of case and name
+    /// compression will thus result in identical bytes. This is mostly
+    /// useful for comparing messages in tests.
+    ///
+    /// [RFC 4034]: https://tools.ietf.org/html/rfc4034
+    pub fn canonicalize(&self) -> ComposeResult<MessageBuf> {
+        let (question, answer, authority, additional) = try!(self.sections());
+        let mut msg = try!(MessageBuilder::new(ComposeMode::Unlimited,
+                                               false));
+        *msg.header_mut() = self.header().clone();
+        for item in question {
+            let item = try!(item);
+            try!(msg.push((item.qname().to_cow().to_lowercase(),
+                           item.qtype(), item.qclass())));
+        }
+        let mut msg = msg.answer();
+        for record in try!(canonical_rrsets(answer)) {
+            try!(msg.push(record));
+        }
+        let mut msg = msg.authority();
+        for record in try!(canonical_rrsets(authority)) {
+            try!(msg.push(record));
+        }
+        let mut msg = msg.additional();
+        for record in try!(canonical_rrsets(additional)) {
+            try!(msg.push(record));
+        }
+        Ok(try!(MessageBuf::from_vec(try!(msg.finish()))))
+    }
+}
+
+/// Collects the records of a section in canonical form.
+///
+/// Records are grouped into RRsets in the order of the first appearance of
+/// each RRset and sorted canonically within each RRset.
+fn canonical_rrsets(section: RecordSection)
+                    -> ComposeResult<Vec<Record<DNameBuf, CanonicalData>>> {
+    let mut rrsets: Vec<Vec<Record<DNameBuf, CanonicalData>>> = Vec::new();
+    let mut index = HashMap::new();
+    for record in section {
+        let record = try!(record);
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        try!(record.data().compose_canonical(&mut composer));
+        let data = CanonicalData {
+            rtype: record.rtype(),
+            data: composer.finish()
+        };
+        let record = Record::new(record.name().to_cow().to_lowercase(),
+                                 record.class(), record.ttl(), data);
+        let key = (record.name().clone(), record.class(), record.rtype());
+        let idx: Option<usize> = index.get(&key).cloned();
+        match idx {
+            Some(idx) => rrsets[idx].push(record),
+            None => {
+                index.insert(key, rrsets.len());
+                rrsets.push(vec![record]);
+            }
+        }
+    }
+    Ok(rrsets.into_iter().flat_map(|mut rrset| {
+        rrset.sort_by(|left, right| left.data().data.cmp(&right.data().data));
+        rrset
+    }).collect())
+}
+
+
+/// # Compression
+///
+impl Message {
+    /// Returns a copy of the message using name compression.
+    ///
+    /// The message is parsed and composed again with the compression map
+    /// enabled so that every domain name that can be compressed is
+    /// compressed as much as possible. The header, the questions, and the
+    /// records are kept unchanged and in order. This is useful for shrinking
+    /// a message assembled from fragments composed without compression
+    /// before sending it over UDP.
+    ///
+    /// Only the domain names in record types defined in [RFC 1035] are
+    /// compressed. All other record data is copied verbatim.
+    ///
+    /// [RFC 1035]: https://tools.ietf.org/html/rfc1035
+    pub fn recompress(&self) -> ComposeResult<MessageBuf> {
+        let (question, answer, authority, additional) = self.sections()?;
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited, true)?;
+        *msg.header_mut() = self.header().clone();
+        for item in question {
+            msg.push(item?)?;
+        }
+        let mut msg = msg.answer();
+        for record in answer {
+            msg.push(record?)?;
+        }
+        let mut msg = msg.authority();
+        for record in authority {
+            msg.push(record?)?;
+        }
+        let mut msg = msg.additional();
+        for record in additional {
+            msg.push(record?)?;
+        }
+        Ok(MessageBuf::from_vec(msg.finish()?)?)
+    }
+}
+
+
+/// # Sending
+///
+impl Message {
+    /// Sends the message as a UDP datagram.
+    ///
+    /// The message is sent via `socket` to `to`. If it is larger than
+    /// `edns_size` octets, it is truncated first by dropping records from
+    /// the end of the message as described for
+    /// [`TruncationPolicy::DropAdditionalFirst`], setting the TC bit if
+    /// answer records had to be dropped. The question section and the OPT
+    /// record are always kept. Since every DNS client can receive 512
+    /// octets over UDP, smaller values of `edns_size` are increased to 512.
+    ///
+    /// Fails with an error of kind `InvalidData` if the message is broken
+    /// or if its header, question, and OPT record alone are too large.
+    ///
+    /// [`TruncationPolicy::DropAdditionalFirst`]: ../message_builder/enum.TruncationPolicy.html#variant.DropAdditionalFirst
+    pub fn send_udp(&self, socket: &UdpSocket, to: SocketAddr,
+                    edns_size: u16) -> io::Result<()> {
+        let size = cmp::max(edns_size, 512) as usize;
+        let msg = truncate_message(self, size,
+                                   TruncationPolicy::DropAdditionalFirst,
+                                   &mut TruncationReport::default())
+                      .map_err(|err| {
+                          io::Error::new(io::ErrorKind::InvalidData, err)
+                      })?;
+        socket.send_to(&msg, to).map(|_| ())
+    }
 }
 
 
@@ -442,6 +789,61 @@ impl MessageBuf {
 }
 
 
+/// # Modification
+///
+impl MessageBuf {
+    /// Sets the extended rcode of the message in place.
+    ///
+    /// The lower four bits of `rcode` go into the header while the upper
+    /// eight bits go into the first octet of the TTL of the first OPT
+    /// record in the additional section. If there is no such record and
+    /// the upper bits are not zero, an OPT record without options and an
+    /// UDP payload size of 512 is appended to the message.
+    ///
+    /// Fails if the message cannot be parsed. In this case, the message is
+    /// left unchanged.
+    pub fn set_rcode(&mut self, rcode: OptRcode) -> ParseResult<()> {
+        let (rcode, ext) = rcode.to_parts();
+        match self.opt_ttl_pos()? {
+            Some(pos) => self.inner[pos] = ext,
+            None if ext != 0 => {
+                let record: Record<_, Opt> = OptRecord::new(512, ext, 0,
+                                                            false).into();
+                let mut composer = Composer::new(ComposeMode::Unlimited,
+                                                 false);
+                if record.compose(&mut composer).is_err() ||
+                        self.counts().arcount() == ::std::u16::MAX {
+                    return Err(ParseError::FormErr)
+                }
+                self.inner.extend_from_slice(&composer.finish());
+                let counts = HeaderCounts::from_message_mut(&mut self.inner);
+                let arcount = counts.arcount();
+                counts.set_arcount(arcount + 1);
+            }
+            None => { }
+        }
+        self.header_mut().set_rcode(rcode);
+        Ok(())
+    }
+
+    /// Returns the position of the TTL of the first OPT record.
+    fn opt_ttl_pos(&self) -> ParseResult<Option<usize>> {
+        let mut section = self.additional()?;
+        loop {
+            let record = match section.next() {
+                Some(record) => record?,
+                None => return Ok(None)
+            };
+            if record.rtype() == Rtype::Opt {
+                // The TTL is followed by the two octet rdlen and the data.
+                let len = record.data().as_bytes().len();
+                return Ok(Some(section.parser.pos() - len - 6))
+            }
+        }
+    }
+}
+
+
 //--- Deref, DerefMut, Borrow, AsRef, AsMut
 
 impl ops::Deref for MessageBuf {
@@ -574,9 +976,14 @@ impl<'a> Iterator for QuestionSection<'a> {
 
 /// A helper type enumerating which section a `RecordSection` is currently in.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Section {
+pub enum Section {
+    /// The answer section.
     Answer,
+
+    /// The authority section.
     Authority,
+
+    /// The additional section.
     Additional
 }
 
@@ -605,6 +1012,123 @@ impl Section {
 }
 
 
+//--- Display
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Section::Answer => f.write_str("answer"),
+            Section::Authority => f.write_str("authority"),
+            Section::Additional => f.write_str("additional"),
+        }
+    }
+}
+
+
+//------------ SectionViolation ----------------------------------------------
+
+/// A record appearing in a section where it doesn’t belong.
+///
+/// Values of this type are returned by [`Message::validate_sections()`].
+///
+/// [`Message::validate_sections()`]: struct.Message.html#method.validate_sections
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SectionViolation {
+    /// An OPT record appears outside the additional section.
+    MisplacedOpt(Section),
+
+    /// There is more than one OPT record in the additional section.
+    DuplicateOpt,
+
+    /// A TSIG record is not the last record of the additional section.
+    MisplacedTsig(Section),
+
+    /// The answer section of a referral contains an SOA record.
+    SoaInReferral,
+}
+
+impl fmt::Display for SectionViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SectionViolation::MisplacedOpt(section) => {
+                write!(f, "OPT record in {} section", section)
+            }
+            SectionViolation::DuplicateOpt => {
+                f.write_str("more than one OPT record")
+            }
+            SectionViolation::MisplacedTsig(section) => {
+                write!(f, "TSIG record not last in {} section", section)
+            }
+            SectionViolation::SoaInReferral => {
+                f.write_str("SOA record in answer section of referral")
+            }
+        }
+    }
+}
+
+
+//------------ Delegation ----------------------------------------------------
+
+/// The delegation contained in a referral.
+///
+/// A referral is a response by a name server that isn’t authoritative for
+/// the queried name but knows the name servers of a zone closer to it.
+/// Values of this type are returned by [`Message::delegation()`]. They
+/// provide the name of this zone, its name servers, and access to any glue
+/// records for the name servers from the additional section.
+///
+/// [`Message::delegation()`]: struct.Message.html#method.delegation
+#[derive(Clone, Debug)]
+pub struct Delegation<'a> {
+    /// The zone delegated to.
+    zone: ParsedDName<'a>,
+
+    /// The names of the name servers of the zone.
+    nameservers: Vec<ParsedDName<'a>>,
+
+    /// The additional section of the referral.
+    additional: RecordSection<'a>,
+}
+
+impl<'a> Delegation<'a> {
+    /// Returns the name of the zone delegated to.
+    pub fn zone(&self) -> &ParsedDName<'a> {
+        &self.zone
+    }
+
+    /// Returns the names of the name servers of the zone.
+    pub fn nameservers(&self) -> &[ParsedDName<'a>] {
+        &self.nameservers
+    }
+
+    /// Returns the glue addresses for the name server `ns_name`.
+    ///
+    /// These are the addresses of all A and AAAA records in the additional
+    /// section whose owner is `ns_name`, ignoring case, in the order they
+    /// appear. Records that cannot be parsed are skipped.
+    pub fn glue_for<N: DName>(&self, ns_name: &N) -> Vec<IpAddr> {
+        let mut res = Vec::new();
+        for record in self.additional.clone().limit_to::<AllRecordData>() {
+            let record = match record {
+                Ok(record) => record,
+                Err(..) => continue
+            };
+            if *record.name() != *ns_name {
+                continue
+            }
+            match *record.data() {
+                AllRecordData::A(ref a) => res.push(IpAddr::V4(a.addr())),
+                AllRecordData::Aaaa(ref aaaa) => {
+                    res.push(IpAddr::V6(aaaa.addr()))
+                }
+                _ => { }
+            }
+        }
+        res
+    }
+}
+
+
 //------------ RecordSection -----------------------------------------------
 
 /// An iterator over one of the three record sections of a DNS message.
@@ -776,6 +1300,88 @@ impl<'a, D: ParsedRecordData<'a>> Iterator for RecordIter<'a, D> {
 }
 
 
+//------------ CanonicalData -------------------------------------------------
+
+/// Record data already composed into its canonical form.
+///
+/// This is a helper type for `Message::canonicalize()`.
+#[derive(Clone, Debug)]
+struct CanonicalData {
+    rtype: Rtype,
+    data: Vec<u8>,
+}
+
+impl RecordData for CanonicalData {
+    fn rtype(&self) -> Rtype {
+        self.rtype
+    }
+
+    fn compose<C: AsMut<Composer>>(&self, mut target: C)
+                                   -> ComposeResult<()> {
+        target.as_mut().compose_bytes(&self.data)
+    }
+}
+
+
+//------------ parse_everything ----------------------------------------------
+
+/// Runs a message through all the parsing it may go through.
+///
+/// Errors are fine, panics and endless loops are not. This is shared by
+/// the `parse_garbage` test and the `parse_message` fuzz target and is not
+/// part of the public API.
+#[doc(hidden)]
+pub fn parse_everything(bytes: &[u8]) {
+    use ::bits::opt::rfc5001::Nsid;
+    use ::bits::opt::rfc7901::Chain;
+    use ::rdata::parsed;
+
+    let msg = match Message::from_bytes(bytes) {
+        Ok(msg) => msg,
+        Err(_) => return
+    };
+    let _ = msg.first_question();
+    let _ = msg.canonical_name().map(|name| name.to_string());
+    let _ = msg.canonicalize();
+    let _ = msg.recompress();
+    let _ = msg.extended_error().map(|err| err.to_string());
+    for question in msg.question() {
+        let _ = question.map(|question| question.to_string());
+    }
+    let (_, answer, authority, additional) = match msg.sections() {
+        Ok(sections) => sections,
+        Err(_) => return
+    };
+    for section in vec![answer.clone(), authority, additional.clone()] {
+        for record in section {
+            let _ = record.map(|record| record.to_string());
+        }
+    }
+    for record in additional.limit_to::<Opt>() {
+        if let Ok(record) = record {
+            for option in record.data().iter::<Nsid<&[u8]>>() {
+                let _ = option.map(|option| option.to_string());
+            }
+            for option in record.data().iter::<Chain<ParsedDName>>() {
+                let _ = option.map(|option| option.name().to_string());
+            }
+        }
+    }
+    macro_rules! parse_as {
+        ( $( $rtype:ident ),* ) => {
+            $(
+                for record in answer.clone()
+                                    .limit_to::<parsed::$rtype>() {
+                    let _ = record.map(|record| record.to_string());
+                }
+            )*
+        }
+    }
+    parse_as!(A, Aaaa, Cert, Cname, Dnskey, Hinfo, Minfo, Mx, Ns, Ptr,
+              Soa, Srv, Txt, Wks);
+}
+
+
 //============ Testing ======================================================
 
 #[cfg(test)]
@@ -784,10 +1390,118 @@ mod test {
     use bits::compose::ComposeMode;
     use bits::message_builder::MessageBuilder;
     use bits::name::DNameBuf;
-    use iana::Rtype;
+    use iana::{Rcode, Rtype};
     use rdata::owned::Cname;
     use super::*;
 
+    #[test]
+    fn delegation() {
+        use std::net::IpAddr;
+        use std::str::FromStr;
+        use rdata::owned::{A, Aaaa, Ns};
+
+        let name = |s: &str| DNameBuf::from_str(s).unwrap();
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.push((name("www.example.com."), Rtype::A)).unwrap();
+        let mut msg = msg.authority();
+        msg.push((name("example.com."), 172800,
+                  Ns::new(name("ns1.example.com.")))).unwrap();
+        msg.push((name("example.com."), 172800,
+                  Ns::new(name("ns.example.net.")))).unwrap();
+        let mut msg = msg.additional();
+        msg.push((name("NS1.example.com."), 172800,
+                  A::from_octets(192, 0, 2, 53))).unwrap();
+        msg.push((name("ns1.example.com."), 172800,
+                  Aaaa::new(FromStr::from_str("2001:db8::53").unwrap())))
+           .unwrap();
+        let msg = MessageBuf::from_vec(msg.finish().unwrap()).unwrap();
+
+        let delegation = msg.delegation().unwrap();
+        assert_eq!(*delegation.zone(), name("example.com."));
+        assert_eq!(delegation.nameservers().len(), 2);
+        assert_eq!(delegation.glue_for(&name("ns1.Example.com.")),
+                   [IpAddr::from_str("192.0.2.53").unwrap(),
+                    IpAddr::from_str("2001:db8::53").unwrap()]);
+        assert!(delegation.glue_for(&name("ns.example.net.")).is_empty());
+
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.header_mut().set_aa(true);
+        let mut msg = msg.authority();
+        msg.push((name("example.com."), 172800,
+                  Ns::new(name("ns1.example.com.")))).unwrap();
+        let msg = MessageBuf::from_vec(msg.finish()).unwrap();
+        assert!(msg.delegation().is_none());
+    }
+
+    #[test]
+    fn validate_sections() {
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.push((DNameBuf::from_str("example.com.").unwrap(),
+                  Rtype::A)).unwrap();
+        let mut msg = msg.answer();
+        msg.push(OptRecord::for_query(1232, false)).unwrap();
+        let mut msg = msg.additional();
+        msg.push(OptRecord::for_query(1232, false)).unwrap();
+        let msg = MessageBuf::from_vec(msg.finish().unwrap()).unwrap();
+        assert_eq!(msg.validate_sections(),
+                   [SectionViolation::MisplacedOpt(Section::Answer)]);
+        assert_eq!(msg.validate_sections()[0].to_string(),
+                   "OPT record in answer section");
+
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        let mut msg = msg.additional();
+        msg.push(OptRecord::for_query(1232, false)).unwrap();
+        let msg = MessageBuf::from_vec(msg.finish().unwrap()).unwrap();
+        assert!(msg.validate_sections().is_empty());
+    }
+
+    #[test]
+    fn set_rcode() {
+        fn read_back(msg: &Message) -> OptRcode {
+            let record = msg.additional().unwrap().limit_to::<Opt>()
+                            .next().unwrap().unwrap();
+            OptRcode::from_parts(msg.header().rcode(),
+                                 (record.ttl() >> 24) as u8)
+        }
+
+        // Message with an OPT record.
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.push((DNameBuf::from_str("example.com.").unwrap(),
+                  Rtype::A)).unwrap();
+        let mut msg = msg.additional();
+        let opt = msg.build_opt(1232, OptRcode::NoError, true).unwrap();
+        let msg = opt.complete().unwrap().finish().unwrap();
+        let mut msg = MessageBuf::from_vec(msg).unwrap();
+        msg.set_rcode(OptRcode::BadVers).unwrap();
+        assert_eq!(msg.header().rcode(), Rcode::NoError);
+        assert_eq!(read_back(&msg).to_int(), 16);
+        assert_eq!(msg.counts().arcount(), 1);
+        msg.set_rcode(OptRcode::Refused).unwrap();
+        assert_eq!(read_back(&msg).to_int(), 5);
+
+        // Message without an OPT record.
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.push((DNameBuf::from_str("example.com.").unwrap(),
+                  Rtype::A)).unwrap();
+        let mut msg = MessageBuf::from_vec(msg.finish()).unwrap();
+        msg.set_rcode(OptRcode::ServFail).unwrap();
+        assert_eq!(msg.header().rcode(), Rcode::ServFail);
+        assert_eq!(msg.counts().arcount(), 0);
+        msg.set_rcode(OptRcode::BadVers).unwrap();
+        assert_eq!(msg.counts().arcount(), 1);
+        assert_eq!(read_back(&msg).to_int(), 16);
+    }
+
     #[test]
     fn short_message() {
         assert!(Message::from_bytes(&[0u8; 11]).is_err());
@@ -827,4 +1541,345 @@ mod test {
         assert_eq!(DNameBuf::from_str("baz.example.com.").unwrap(),
                    msg.canonical_name().unwrap());
     }
+
+    #[test]
+    fn canonicalize() {
+        use rdata::owned::{A, Mx};
+
+        fn build(compress: bool, names: &[&str]) -> MessageBuf {
+            let name = |idx: usize| DNameBuf::from_str(names[idx]).unwrap();
+            let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                              compress).unwrap();
+            msg.header_mut().set_id(12);
+            msg.push((name(0), Rtype::Mx)).unwrap();
+            let mut answer = msg.answer();
+            if compress {
+                answer.push((name(0), 3600, Mx::new(20, name(2)))).unwrap();
+                answer.push((name(0), 3600, Mx::new(10, name(1)))).unwrap();
+            }
+            else {
+                answer.push((name(0), 3600, Mx::new(10, name(1)))).unwrap();
+                answer.push((name(0), 3600, Mx::new(20, name(2)))).unwrap();
+            }
+            let mut additional = answer.additional();
+            additional.push((name(1), 3600, A::new("192.0.2.1".parse()
+                                                              .unwrap())))
+                      .unwrap();
+            MessageBuf::from_vec(additional.finish().unwrap()).unwrap()
+        }
+
+        let one = build(true, &["Example.COM.", "mx1.EXAMPLE.com.",
+                                "MX2.example.com."]);
+        let two = build(false, &["example.com.", "mx1.example.com.",
+                                 "mx2.example.com."]);
+        assert!(one.as_bytes() != two.as_bytes());
+        let one = one.canonicalize().unwrap();
+        let two = two.canonicalize().unwrap();
+        assert_eq!(one.as_bytes(), two.as_bytes());
+        assert_eq!(one.header().id(), 12);
+        assert_eq!(one.counts().ancount(), 2);
+        assert_eq!(one.counts().arcount(), 1);
+        assert_eq!(one.first_question().unwrap().qname(),
+                   &DNameBuf::from_str("example.com.").unwrap());
+    }
+
+    #[test]
+    fn recompress() {
+        use rdata::owned::{Mx, Ns};
+
+        let name = |s: &str| DNameBuf::from_str(s).unwrap();
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          false).unwrap();
+        msg.header_mut().set_id(12);
+        msg.push((name("example.com."), Rtype::Mx)).unwrap();
+        let mut answer = msg.answer();
+        answer.push((name("example.com."), 3600,
+                     Mx::new(10, name("mx1.example.com.")))).unwrap();
+        answer.push((name("example.com."), 3600,
+                     Mx::new(20, name("mx2.example.com.")))).unwrap();
+        let mut authority = answer.authority();
+        authority.push((name("example.com."), 3600,
+                        Ns::new(name("ns.example.com.")))).unwrap();
+        let msg = MessageBuf::from_vec(authority.finish()).unwrap();
+
+        let small = msg.recompress().unwrap();
+        assert!(small.as_bytes().len() < msg.as_bytes().len());
+        assert_eq!(small.header().id(), 12);
+        assert_eq!(small.counts().ancount(), 2);
+        assert_eq!(small.counts().nscount(), 1);
+        assert_eq!(small.canonicalize().unwrap().as_bytes(),
+                   msg.canonicalize().unwrap().as_bytes());
+        assert_eq!(small.recompress().unwrap().as_bytes(),
+                   small.as_bytes());
+    }
+
+    #[test]
+    fn extended_error() {
+        use bits::opt::rfc8914::Ede;
+        use iana::{ExtendedErrorCode, OptRcode, Rcode};
+
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.header_mut().set_rcode(Rcode::ServFail);
+        msg.push((DNameBuf::from_str("example.com.").unwrap(), Rtype::A))
+           .unwrap();
+        let msg = MessageBuf::from_vec(msg.finish()).unwrap();
+        assert!(msg.extended_error().is_none());
+
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.header_mut().set_rcode(Rcode::ServFail);
+        msg.push((DNameBuf::from_str("example.com.").unwrap(), Rtype::A))
+           .unwrap();
+        let mut opt = msg.additional()
+                         .build_opt(1232, OptRcode::ServFail, true).unwrap();
+        opt.push(Ede::new(ExtendedErrorCode::DnssecBogus,
+                          b"signature expired")).unwrap();
+        opt.push(Ede::new(ExtendedErrorCode::Other, b"")).unwrap();
+        let msg = opt.complete().unwrap().finish().unwrap();
+        let msg = MessageBuf::from_vec(msg).unwrap();
+        let err = msg.extended_error().unwrap();
+        assert_eq!(err.rcode().to_int(), OptRcode::ServFail.to_int());
+        assert_eq!(err.ede().code(), ExtendedErrorCode::DnssecBogus);
+        assert_eq!(err.ede().extra_text(), b"signature expired");
+        assert_eq!(err.to_string(),
+                   "SERVFAIL / EDE 6 (DNSSEC Bogus): signature expired");
+    }
+
+    #[test]
+    fn edns_option_codes() {
+        use bits::opt::rfc5001::Nsid;
+        use bits::opt::rfc7873::Cookie;
+        use iana::OptRcode;
+
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.push((DNameBuf::from_str("example.com.").unwrap(), Rtype::A))
+           .unwrap();
+        let plain = MessageBuf::from_vec(msg.clone().finish()).unwrap();
+        assert!(plain.edns_option_codes().is_empty());
+
+        let mut opt = msg.additional()
+                         .build_opt(1232, OptRcode::NoError, false).unwrap();
+        opt.push(Cookie::new(*b"\x01\x02\x03\x04\x05\x06\x07\x08"))
+           .unwrap();
+        opt.push(Nsid::new(b"ns1")).unwrap();
+        let msg = opt.complete().unwrap().finish().unwrap();
+        let msg = MessageBuf::from_vec(msg).unwrap();
+        assert_eq!(msg.edns_option_codes(), vec![10, 3]);
+    }
+
+    #[test]
+    fn parse_with_registry() {
+        use bits::{ParseResult, Parser, RdataRegistry};
+        use rdata::MasterRecordData;
+        use rdata::owned::A;
+
+        #[derive(Debug, PartialEq)]
+        struct Private(u16, Vec<u8>);
+
+        fn parse_private(parser: &mut Parser) -> ParseResult<Private> {
+            let value = parser.parse_u16()?;
+            let len = parser.remaining();
+            Ok(Private(value, parser.parse_bytes(len)?.to_vec()))
+        }
+
+        fn message(private: &[u8]) -> MessageBuf {
+            let name = DNameBuf::from_str("example.com.").unwrap();
+            let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                              true).unwrap();
+            msg.push((name.clone(), Rtype::A)).unwrap();
+            let mut msg = msg.answer();
+            msg.push((name.clone(), 3600,
+                      MasterRecordData::Generic(Rtype::Int(65280),
+                                                private.to_vec())))
+               .unwrap();
+            msg.push((name.clone(), 3600, A::from_octets(192, 0, 2, 1)))
+               .unwrap();
+            MessageBuf::from_vec(msg.finish()).unwrap()
+        }
+
+        let msg = message(b"\x01\x02abc");
+        let mut registry = RdataRegistry::new();
+        let (answer, authority, additional)
+            = msg.parse_with_registry(&registry).unwrap();
+        assert_eq!(answer.len(), 2);
+        assert!(authority.is_empty() && additional.is_empty());
+        assert!(answer[0].data().custom::<Private>().is_none());
+
+        registry.register(Rtype::Int(65280), parse_private);
+        assert!(registry.is_registered(Rtype::Int(65280)));
+        let (answer, _, _) = msg.parse_with_registry(&registry).unwrap();
+        assert_eq!(answer[0].rtype(), Rtype::Int(65280));
+        assert_eq!(answer[0].data().custom::<Private>(),
+                   Some(&Private(0x0102, b"abc".to_vec())));
+        assert_eq!(answer[1].rtype(), Rtype::A);
+        assert!(answer[1].data().custom::<Private>().is_none());
+        assert_eq!(answer[1].data().generic().as_bytes(),
+                   b"\xc0\x00\x02\x01");
+
+        assert!(message(b"\x01").parse_with_registry(&registry).is_err());
+    }
+
+    #[test]
+    fn send_udp() {
+        use std::net::UdpSocket;
+        use rdata::owned::A;
+
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.push((DNameBuf::from_str("example.com.").unwrap(), Rtype::A))
+           .unwrap();
+        let mut msg = msg.answer();
+        for i in 0..100 {
+            msg.push((DNameBuf::from_str("example.com.").unwrap(), 3600,
+                      A::from_octets(192, 0, 2, i))).unwrap();
+        }
+        let msg = MessageBuf::from_vec(msg.finish()).unwrap();
+        assert!(msg.as_bytes().len() > 512);
+
+        let recv = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let send = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let to = recv.local_addr().unwrap();
+        let mut buf = [0u8; 4096];
+
+        msg.send_udp(&send, to, 4096).unwrap();
+        let len = recv.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], msg.as_bytes());
+
+        msg.send_udp(&send, to, 0).unwrap();
+        let len = recv.recv(&mut buf).unwrap();
+        assert!(len <= 512);
+        let sent = Message::from_bytes(&buf[..len]).unwrap();
+        assert!(sent.header().tc());
+        assert_eq!(sent.counts().qdcount(), 1);
+        assert!(sent.counts().ancount() < 100);
+        assert_eq!(sent.answer().unwrap().count(),
+                   sent.counts().ancount() as usize);
+    }
+
+    #[test]
+    fn send_udp_opt_first() {
+        use std::net::UdpSocket;
+        use bits::opt::OptRecord;
+        use rdata::owned::{A, Aaaa};
+
+        let name = |i: u8| {
+            DNameBuf::from_str(&format!("host{}.example.com.", i)).unwrap()
+        };
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.push((DNameBuf::from_str("example.com.").unwrap(), Rtype::A))
+           .unwrap();
+        let mut msg = msg.additional();
+        msg.push(OptRecord::for_query(1232, false)).unwrap();
+        for i in 0..40 {
+            msg.push((name(i), 3600, A::from_octets(192, 0, 2, i)))
+               .unwrap();
+            msg.push((name(i), 3600,
+                      Aaaa::new(format!("2001:db8::{}", i).parse()
+                                                          .unwrap())))
+               .unwrap();
+        }
+        let msg = MessageBuf::from_vec(msg.finish().unwrap()).unwrap();
+        assert!(msg.as_bytes().len() > 512);
+
+        let recv = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let send = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut buf = [0u8; 4096];
+        msg.send_udp(&send, recv.local_addr().unwrap(), 0).unwrap();
+        let len = recv.recv(&mut buf).unwrap();
+        assert!(len <= 512);
+        let sent = Message::from_bytes(&buf[..len]).unwrap();
+        let mut records = sent.additional().unwrap();
+        let mut count = 0;
+        for i in 0.. {
+            let a = match records.next() {
+                Some(record) => record.unwrap(),
+                None => break
+            };
+            if a.rtype() == Rtype::Opt {
+                break
+            }
+            let aaaa = records.next().unwrap().unwrap();
+            assert_eq!(*a.name(), name(i));
+            assert_eq!(*aaaa.name(), name(i));
+            count += 2;
+        }
+        assert!(count > 0);
+        assert_eq!(count + 1, sent.counts().arcount());
+        let opt = sent.additional().unwrap().last().unwrap().unwrap();
+        assert_eq!(opt.rtype(), Rtype::Opt);
+    }
+
+    #[test]
+    fn parse_garbage() {
+        use rand::{Rng, SeedableRng, XorShiftRng};
+        use bits::opt::rfc5001::Nsid;
+        use bits::opt::rfc7901::Chain;
+        use iana::{CertType, OptRcode, SecAlg};
+        use rdata::owned::{A, Aaaa, Cert, Dnskey, Mx, Soa, Srv};
+        use rdata::Txt;
+
+        // Start with a valid message containing a bit of everything.
+        let name = DNameBuf::from_str("www.example.com.").unwrap();
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.push((&name, Rtype::A)).unwrap();
+        let mut answer = msg.answer();
+        answer.push((&name, 3600, A::from_octets(192, 0, 2, 1))).unwrap();
+        answer.push((&name, 3600, Aaaa::new("2001:db8::1".parse().unwrap())))
+              .unwrap();
+        answer.push((&name, 3600, Mx::new(10, name.clone()))).unwrap();
+        answer.push((&name, 3600, Cname::new(name.clone()))).unwrap();
+        answer.push((&name, 3600, Soa::new(name.clone(), name.clone(),
+                                           1, 2, 3, 4, 5))).unwrap();
+        answer.push((&name, 3600, Srv::new(1, 2, 3, name.clone()))).unwrap();
+        answer.push((&name, 3600, Txt::new(b"\x03foo".to_vec()))).unwrap();
+        answer.push((&name, 3600, Cert::new(CertType::Pkix, 1,
+                                            SecAlg::RsaSha256,
+                                            vec![1, 2, 3])))
+              .unwrap();
+        answer.push((&name, 3600, Dnskey::new(256, 3, SecAlg::RsaSha256,
+                                              vec![1, 2, 3])))
+              .unwrap();
+        let mut opt = answer.additional().build_opt(4096, OptRcode::NoError,
+                                                    false).unwrap();
+        opt.push(Nsid::new(b"ns1")).unwrap();
+        opt.push(Chain::new(name.clone())).unwrap();
+        let base = opt.complete().unwrap().finish().unwrap();
+        parse_everything(&base);
+
+        // Now derive a corpus of broken messages of up to 4 KiB from it.
+        let mut rng = XorShiftRng::from_seed([0x5eed, 0xd0, 0x3a1, 0x1035]);
+        for _ in 0..10000 {
+            let mut data = base.clone();
+            match rng.gen_range(0, 4) {
+                0 => {
+                    let len = rng.gen_range(0, 4097);
+                    data = (0..len).map(|_| rng.gen()).collect();
+                }
+                kind => {
+                    for _ in 0..rng.gen_range(1, 9) {
+                        let pos = rng.gen_range(0, data.len());
+                        data[pos] = rng.gen();
+                    }
+                    if kind == 2 {
+                        let len = rng.gen_range(0, data.len() + 1);
+                        data.truncate(len);
+                    }
+                    else if kind == 3 {
+                        for _ in 0..rng.gen_range(0, 64) {
+                            data.push(rng.gen());
+                        }
+                    }
+                }
+            }
+            parse_everything(&data);
+        }
+    }
 }
@@ -84,10 +84,13 @@
 //! [`from_vec()`]: struct.MessageBuilder.html#method.from_vec
 
 use std::mem;
-use ::iana::{Class, OptRcode, Rtype};
+use byteorder::{BigEndian, ByteOrder};
+use ::iana::{Class, Opcode, OptRcode, Rcode, Rtype};
 use super::{Composer, ComposeError, ComposeMode, ComposeResult,
-            ComposeSnapshot, DName, DNameSlice, HeaderSection, Header,
-            HeaderCounts, Message, Question, Record, RecordData};
+            ComposeSnapshot, DName, DNameSlice, GenericRecord, HeaderSection,
+            Header, HeaderCounts, Message, ParsedDName, ParseError,
+            ParseResult, Parser, Question, Record, RecordData};
+use super::message::RecordSection;
 use super::record::RecordBuilder;
 use super::opt::OptData;
 
@@ -145,6 +148,90 @@ impl MessageBuilder {
         try!(composer.compose_empty(mem::size_of::<HeaderSection>()));
         Ok(MessageBuilder{target: MessageTarget::new(composer)})
     }
+
+    /// Creates a new NOTIFY message for a zone.
+    ///
+    /// NOTIFY messages are sent by a primary server to inform its
+    /// secondaries that a zone has changed as described in [RFC 1996]. The
+    /// message will have the opcode NOTIFY, the AA bit set, a random ID,
+    /// and a single question for the SOA record of `zone` in class IN.
+    ///
+    /// Since a NOTIFY message may contain the new SOA record of the zone
+    /// in its answer section, the builder is returned rather than the
+    /// finished message.
+    ///
+    /// [RFC 1996]: https://tools.ietf.org/html/rfc1996
+    pub fn notify<N: DName>(zone: N, mode: ComposeMode)
+                            -> ComposeResult<Self> {
+        let mut res = Self::new(mode, true)?;
+        res.header_mut().set_random_id();
+        res.header_mut().set_opcode(Opcode::Notify);
+        res.header_mut().set_aa(true);
+        res.push((zone, Rtype::Soa))?;
+        Ok(res)
+    }
+
+    /// Creates a new builder for a response to `query`.
+    ///
+    /// The response has the ID, opcode, and RD bit of `query`, the QR bit
+    /// set, and the questions of `query` in its question section. All
+    /// other header fields are left at zero so that the builder can be
+    /// used for any kind of response.
+    ///
+    /// Fails if the questions of `query` cannot be parsed.
+    pub fn respond_to(query: &Message, mode: ComposeMode)
+                      -> ComposeResult<Self> {
+        let mut res = Self::new(mode, true)?;
+        res.header_mut().set_id(query.header().id());
+        res.header_mut().set_opcode(query.header().opcode());
+        res.header_mut().set_rd(query.header().rd());
+        res.header_mut().set_qr(true);
+        for question in query.question() {
+            res.push(question?)?;
+        }
+        Ok(res)
+    }
+
+    /// Creates a positive authoritative response with a single record.
+    ///
+    /// This is a shortcut for the common case of a stub or mock server
+    /// answering a query. The response is based on [`respond_to()`] with
+    /// the AA bit set, the rcode NoError, and `record` as the only record
+    /// in the answer section. The finished message is returned.
+    ///
+    /// [`respond_to()`]: #method.respond_to
+    pub fn single_answer<N, D, R>(query: &Message, record: R,
+                                  mode: ComposeMode)
+                                  -> ComposeResult<Vec<u8>>
+                         where N: DName,
+                               D: RecordData,
+                               R: Into<Record<N, D>> {
+        let mut res = Self::respond_to(query, mode)?;
+        res.header_mut().set_aa(true);
+        res.header_mut().set_rcode(Rcode::NoError);
+        let mut res = res.answer();
+        res.push(record)?;
+        Ok(res.finish())
+    }
+
+    /// Creates a DSOTYPENI error response to a DSO request.
+    ///
+    /// A server receiving a DNS stateful operations request with a primary
+    /// TLV whose DSO-TYPE it doesn’t implement has to respond with the
+    /// rcode DSOTYPENI as described in section 5.1.1 of [RFC 8490]. The
+    /// response has the ID and opcode of `request`, the QR bit set, and no
+    /// TLVs.
+    ///
+    /// [RFC 8490]: https://tools.ietf.org/html/rfc8490
+    pub fn dso_type_ni(request: &Message, mode: ComposeMode)
+                       -> ComposeResult<Self> {
+        let mut res = Self::new(mode, false)?;
+        res.header_mut().set_id(request.header().id());
+        res.header_mut().set_opcode(request.header().opcode());
+        res.header_mut().set_qr(true);
+        res.header_mut().set_rcode(Rcode::DsoTypeNi);
+        Ok(res)
+    }
 }
 
 
@@ -173,7 +260,7 @@ impl MessageBuilder {
     pub fn push<N: DName, Q: Into<Question<N>>>(&mut self, question: Q)
                           -> ComposeResult<()> {
         self.target.push(|target| question.into().compose(target),
-                         |counts| counts.inc_qdcount(1))
+                         |counts| counts.inc_qdcount(1)).map(|_| ())
     }
 
     /// Rewinds to the beginning of the question section.
@@ -185,7 +272,7 @@ impl MessageBuilder {
 
     /// Proceeds to building the answer section.
     pub fn answer(self) -> AnswerBuilder {
-        AnswerBuilder::new(self.target.commit())
+        AnswerBuilder::new(self.target.next_section())
     }
 
     /// Proceeds to building the authority section, skipping the answer.
@@ -217,6 +304,16 @@ impl MessageBuilder {
     pub fn finish(self) -> Vec<u8> {
         self.target.finish()
     }
+
+    /// Finishes the message and reports what was lost to truncation.
+    ///
+    /// This is the same as [`finish()`] but also returns how many records
+    /// had to be dropped because the message size was exceeded.
+    ///
+    /// [`finish()`]: #method.finish
+    pub fn finish_with_report(self) -> (Vec<u8>, TruncationReport) {
+        self.target.finish_with_report()
+    }
 }
 
 
@@ -237,11 +334,9 @@ pub struct AnswerBuilder {
 
 
 impl AnswerBuilder {
-    /// Creates a new answer builder from a compser.
-    fn new(composer: Composer) -> Self {
-        AnswerBuilder { 
-            target: MessageTarget::new(composer)
-        }
+    /// Creates a new answer builder from a message target.
+    fn new(target: MessageTarget) -> Self {
+        AnswerBuilder { target: target }
     }
 
     /// Returns a reference to the messages header.
@@ -266,20 +361,36 @@ impl AnswerBuilder {
                 where N: DName,
                       D: RecordData,
                       R: Into<Record<N, D>> {
-        self.target.push(|target| record.into().compose(target),
-                         |counts| counts.inc_ancount(1))
+        if !self.target.push(|target| record.into().compose(target),
+                             |counts| counts.inc_ancount(1))? {
+            self.target.report.dropped_answer += 1
+        }
+        Ok(())
+    }
+
+    /// Removes duplicate records from the answer section.
+    ///
+    /// Records are duplicates if they have the same owner name, class,
+    /// record type, and record data. Of each set of duplicates, only the
+    /// first record is kept, receiving the lowest TTL of the set. The
+    /// order of the remaining records is not changed.
+    pub fn dedup(&mut self) -> ComposeResult<()> {
+        MessageTarget::dedup(self, |builder| &builder.target,
+                             Message::answer, Self::rewind,
+                             |builder, record| builder.push(record))
     }
 
     /// Rewinds to the beginning of the answer section.
     ///
     /// This drops all previously assembled answer records.
     pub fn rewind(&mut self) {
-        self.target.rewind(|counts| counts.set_ancount(0))
+        self.target.rewind(|counts| counts.set_ancount(0));
+        self.target.report.dropped_answer = 0;
     }
 
     /// Proceeds to building the authority section.
     pub fn authority(self) -> AuthorityBuilder {
-        AuthorityBuilder::new(self.target.commit())
+        AuthorityBuilder::new(self.target.next_section())
     }
 
     /// Proceeds to building the additional section, skipping authority.
@@ -305,6 +416,16 @@ impl AnswerBuilder {
     pub fn finish(self) -> Vec<u8> {
         self.target.finish()
     }
+
+    /// Finishes the message and reports what was lost to truncation.
+    ///
+    /// This is the same as [`finish()`] but also returns how many records
+    /// had to be dropped because the message size was exceeded.
+    ///
+    /// [`finish()`]: #method.finish
+    pub fn finish_with_report(self) -> (Vec<u8>, TruncationReport) {
+        self.target.finish_with_report()
+    }
 }
 
 
@@ -326,11 +447,9 @@ pub struct AuthorityBuilder {
 
 
 impl AuthorityBuilder {
-    /// Creates a new authority builder from a compser.
-    fn new(composer: Composer) -> Self {
-        AuthorityBuilder { 
-            target: MessageTarget::new(composer)
-        }
+    /// Creates a new authority builder from a message target.
+    fn new(target: MessageTarget) -> Self {
+        AuthorityBuilder { target: target }
     }
 
     /// Returns a reference to the messages header.
@@ -355,20 +474,36 @@ impl AuthorityBuilder {
                 where N: DName,
                       D: RecordData,
                       R: Into<Record<N, D>> {
-        self.target.push(|target| record.into().compose(target),
-                         |counts| counts.inc_nscount(1))
+        if !self.target.push(|target| record.into().compose(target),
+                             |counts| counts.inc_nscount(1))? {
+            self.target.report.dropped_authority += 1
+        }
+        Ok(())
+    }
+
+    /// Removes duplicate records from the authority section.
+    ///
+    /// Records are duplicates if they have the same owner name, class,
+    /// record type, and record data. Of each set of duplicates, only the
+    /// first record is kept, receiving the lowest TTL of the set. The
+    /// order of the remaining records is not changed.
+    pub fn dedup(&mut self) -> ComposeResult<()> {
+        MessageTarget::dedup(self, |builder| &builder.target,
+                             Message::authority, Self::rewind,
+                             |builder, record| builder.push(record))
     }
 
     /// Rewinds to the beginning of the authority section.
     ///
     /// This drops all previously assembled authority records.
     pub fn rewind(&mut self) {
-        self.target.rewind(|counts| counts.set_nscount(0))
+        self.target.rewind(|counts| counts.set_nscount(0));
+        self.target.report.dropped_authority = 0;
     }
 
     /// Proceeds to building the additional section.
     pub fn additional(self) -> AdditionalBuilder {
-        AdditionalBuilder::new(self.target.commit())
+        AdditionalBuilder::new(self.target.next_section())
     }
 
     /// Returns a reference to the message assembled so far.
@@ -388,6 +523,16 @@ impl AuthorityBuilder {
     pub fn finish(self) -> Vec<u8> {
         self.target.finish()
     }
+
+    /// Finishes the message and reports what was lost to truncation.
+    ///
+    /// This is the same as [`finish()`] but also returns how many records
+    /// had to be dropped because the message size was exceeded.
+    ///
+    /// [`finish()`]: #method.finish
+    pub fn finish_with_report(self) -> (Vec<u8>, TruncationReport) {
+        self.target.finish_with_report()
+    }
 }
 
 
@@ -406,14 +551,31 @@ impl AuthorityBuilder {
 #[derive(Clone, Debug)]
 pub struct AdditionalBuilder {
     target: MessageTarget,
+
+    /// The position of the TTL field of the OPT record if there is one.
+    ///
+    /// The position is relative to the start of the message.
+    opt_ttl: Option<usize>,
+
+    /// The upper eight bits of the extended rcode.
+    ext_rcode: u8,
+
+    /// Whether space for an automatically added OPT record is reserved.
+    opt_reserved: bool,
 }
 
 
+/// The length of an OPT record without any options.
+const OPT_LEN: usize = 11;
+
 impl AdditionalBuilder {
-    /// Creates a new additional builder from a compser.
-    fn new(composer: Composer) -> Self {
+    /// Creates a new additional builder from a message target.
+    fn new(target: MessageTarget) -> Self {
         AdditionalBuilder { 
-            target: MessageTarget::new(composer)
+            target: target,
+            opt_ttl: None,
+            ext_rcode: 0,
+            opt_reserved: false
         }
     }
 
@@ -434,13 +596,79 @@ impl AdditionalBuilder {
     /// a domain name, class, TTL, and record data or triples leaving out
     /// the class which will then be assumed to be `Class::In`.
     ///
+    /// If the record is an OPT record, it will receive the upper bits of
+    /// an extended rcode set via [`set_extended_rcode()`].
+    ///
     /// [`Record`]: ../record/struct.Record.html
+    /// [`set_extended_rcode()`]: #method.set_extended_rcode
     pub fn push<N, D, R>(&mut self, record: R) -> ComposeResult<()>
                 where N: DName,
                       D: RecordData,
                       R: Into<Record<N, D>> {
-        self.target.push(|target| record.into().compose(target),
-                         |counts| counts.inc_arcount(1))
+        let record = record.into();
+        let start = self.target.len();
+        let release = record.rtype() == Rtype::Opt && self.opt_reserved;
+        if release {
+            try!(self.target.composer.release(OPT_LEN));
+            self.opt_reserved = false;
+        }
+        if !self.target.push(|target| record.compose(target),
+                             |counts| counts.inc_arcount(1))? {
+            self.target.report.dropped_additional += 1;
+            if release {
+                // The record has been cut back, so the space is there.
+                self.opt_reserved =
+                    self.target.composer.reserve(OPT_LEN).is_ok();
+            }
+        }
+        if record.rtype() == Rtype::Opt && self.target.len() > start {
+            let mut parser = Parser::new(self.target.so_far());
+            parser.seek(start)?;
+            ParsedDName::parse(&mut parser)?;
+            let pos = parser.pos() + 4;
+            self.opt_ttl = Some(pos);
+            if self.ext_rcode != 0 {
+                self.target.so_far_mut()[pos] = self.ext_rcode;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the extended rcode of the message.
+    ///
+    /// The lower four bits of the extended rcode are placed in the
+    /// message header while the upper eight bits are part of the OPT
+    /// record. If there already is an OPT record, it is updated right
+    /// away. Otherwise the bits are remembered and placed into an OPT
+    /// record added later. If there still is no OPT record when the
+    /// message is finished, one is added by [`finish()`].
+    ///
+    /// In the latter case, space for this OPT record is reserved right
+    /// away so that it will be present even if records added later are
+    /// dropped due to truncation. If the message doesn’t have enough
+    /// space left for the record, an error is returned and nothing is
+    /// changed.
+    ///
+    /// [`finish()`]: #method.finish
+    pub fn set_extended_rcode(&mut self, rcode: OptRcode)
+                              -> ComposeResult<()> {
+        let ext_rcode = rcode.ext();
+        if self.opt_ttl.is_none() {
+            if ext_rcode != 0 && !self.opt_reserved {
+                self.target.composer.reserve(OPT_LEN)?;
+                self.opt_reserved = true;
+            }
+            else if ext_rcode == 0 && self.opt_reserved {
+                try!(self.target.composer.release(OPT_LEN));
+                self.opt_reserved = false;
+            }
+        }
+        self.header_mut().set_rcode(rcode.rcode());
+        self.ext_rcode = ext_rcode;
+        if let Some(pos) = self.opt_ttl {
+            self.target.so_far_mut()[pos] = self.ext_rcode;
+        }
+        Ok(())
     }
 
     /// Starts appending an OPT record to the section.
@@ -454,7 +682,9 @@ impl AdditionalBuilder {
     /// The `rcode` argument should contain the Rcode used for a response
     /// or `OptRcode::NoError` for a message. Only the upper eight bits are
     /// used here, the lower for bits go into the message header’s rcode
-    /// field.
+    /// field. If `rcode` is `OptRcode::NoError`, the upper bits of an
+    /// extended rcode set earlier via [`set_extended_rcode()`] are used
+    /// instead.
     ///
     /// The `dnssec_ok` flag indicates whether a sender is prepared to
     /// receive and process DNSSEC-related resource records in a response.
@@ -463,16 +693,42 @@ impl AdditionalBuilder {
     /// This method trades in the additional section builder for an OPT
     /// record builder. Once the record is finished, it can be traded back
     /// to continue building the additional section.
-    pub fn build_opt(self, payload_size: u16, rcode: OptRcode,
+    ///
+    /// [`set_extended_rcode()`]: #method.set_extended_rcode
+    pub fn build_opt(mut self, payload_size: u16, rcode: OptRcode,
                      dnssec_ok: bool) -> ComposeResult<OptBuilder> {
-        OptBuilder::new(self, payload_size, rcode, dnssec_ok)
+        if rcode.is_error() {
+            self.ext_rcode = rcode.ext();
+        }
+        if self.opt_reserved {
+            try!(self.target.composer.release(OPT_LEN));
+            self.opt_reserved = false;
+        }
+        OptBuilder::new(self, payload_size, dnssec_ok)
+    }
+
+    /// Removes duplicate records from the additional section.
+    ///
+    /// Records are duplicates if they have the same owner name, class,
+    /// record type, and record data. Of each set of duplicates, only the
+    /// first record is kept, receiving the lowest TTL of the set. The
+    /// order of the remaining records is not changed.
+    pub fn dedup(&mut self) -> ComposeResult<()> {
+        MessageTarget::dedup(self, |builder| &builder.target,
+                             Message::additional, Self::rewind,
+                             |builder, record| builder.push(record))
     }
 
     /// Rewinds to the beginning of the additional section.
     ///
     /// This drops all previously assembled additonal records.
     pub fn rewind(&mut self) {
-        self.target.rewind(|counts| counts.set_arcount(0))
+        self.target.rewind(|counts| counts.set_arcount(0));
+        self.target.report.dropped_additional = 0;
+        self.opt_ttl = None;
+        if self.ext_rcode != 0 && !self.opt_reserved {
+            self.opt_reserved = self.target.composer.reserve(OPT_LEN).is_ok();
+        }
     }
 
     /// Returns a reference to the message assembled so far.
@@ -487,8 +743,71 @@ impl AdditionalBuilder {
     }
 
     /// Finishes the message.
-    pub fn finish(self) -> Vec<u8> {
-        self.target.finish()
+    ///
+    /// If an extended rcode that doesn’t fit into the message header has
+    /// been set via [`set_extended_rcode()`] but no OPT record has been
+    /// added, an OPT record with a payload size of 512 is added to carry
+    /// its upper bits. The space for this record has been reserved when
+    /// the extended rcode was set, so the record is added even if the
+    /// message has been truncated.
+    ///
+    /// The method fails if the OPT record cannot be added.
+    ///
+    /// [`set_extended_rcode()`]: #method.set_extended_rcode
+    pub fn finish(self) -> ComposeResult<Vec<u8>> {
+        self.finish_with_report().map(|(res, _)| res)
+    }
+
+    /// Finishes the message and reports what was lost to truncation.
+    ///
+    /// This is the same as [`finish()`] but also returns how many records
+    /// had to be dropped because the message size was exceeded.
+    ///
+    /// [`finish()`]: #method.finish
+    pub fn finish_with_report(mut self)
+                              -> ComposeResult<(Vec<u8>, TruncationReport)> {
+        if self.ext_rcode != 0 && self.opt_ttl.is_none()
+                && self.opt_reserved {
+            let ext_rcode = self.ext_rcode;
+            try!(self.target.composer.use_reserved(OPT_LEN, |target| {
+                let record = try!(RecordBuilder::new(
+                    target, &DNameSlice::root(), Class::Int(512),
+                    Rtype::Opt, (ext_rcode as u32) << 24
+                ));
+                record.finish().map(|_| ())
+            }));
+            try!(self.target.counts_mut().inc_arcount(1));
+        }
+        Ok(self.target.finish_with_report())
+    }
+
+    /// Finishes the message and truncates it to fit into a UDP datagram.
+    ///
+    /// If the finished message is larger than `size` octets, records are
+    /// dropped from the end of the message according to `policy` until it
+    /// fits. The question section and the OPT record are always kept. The
+    /// TC bit is only set if records had to be dropped from the answer
+    /// section.
+    ///
+    /// If the builder is in stream mode, the length prefix is updated to
+    /// the truncated length. The method fails if the message cannot be
+    /// parsed or if the header, question, and OPT record alone are larger
+    /// than `size`.
+    pub fn finish_for_udp(self, size: usize, policy: TruncationPolicy)
+                          -> ComposeResult<Vec<u8>> {
+        let start = self.target.composer.start();
+        let stream = match *self.target.composer.mode() {
+            ComposeMode::Stream => true,
+            _ => false
+        };
+        let (mut res, mut report) = try!(self.finish_with_report());
+        let msg = res.split_off(start);
+        let msg = try!(truncate_message(&msg, size, policy, &mut report));
+        if stream {
+            BigEndian::write_u16(&mut res[start - 2..], msg.len() as u16);
+        }
+        res.extend_from_slice(&msg);
+        Ok(res)
     }
 }
 
@@ -513,21 +832,40 @@ impl AsRef<Message> for AdditionalBuilder {
 #[derive(Clone, Debug)]
 pub struct OptBuilder {
     builder: RecordBuilder<ComposeSnapshot>,
+
+    /// The position of the record’s TTL field relative to message start.
+    ttl_pos: usize,
+
+    /// The upper eight bits of the extended rcode.
+    ext_rcode: u8,
+
+    /// The records dropped so far.
+    report: TruncationReport,
 }
 
 impl OptBuilder {
     /// Creates a new OPT builder from an additional builder
-    fn new(builder: AdditionalBuilder, payload_size: u16, rcode: OptRcode,
-           dnssec_ok: bool) -> ComposeResult<Self> {
-        let mut ttl = (rcode.ext() as u32) << 24;
+    fn new(builder: AdditionalBuilder, payload_size: u16, dnssec_ok: bool)
+           -> ComposeResult<Self> {
+        let mut ttl = (builder.ext_rcode as u32) << 24;
         if dnssec_ok {
             ttl |= 0x8000
         }
-        let builder = RecordBuilder::new(builder.target.composer,
-                                         &DNameSlice::root(),
-                                         Class::Int(payload_size),
-                                         Rtype::Opt, ttl)?;
-        Ok(OptBuilder { builder })
+        let mut record = RecordBuilder::new(builder.target.composer,
+                                            &DNameSlice::root(),
+                                            Class::Int(payload_size),
+                                            Rtype::Opt, ttl)?;
+        // The TTL is followed by the two octets of the data length.
+        let ttl_pos = {
+            let composer = record.as_mut();
+            composer.pos() - composer.start() - 6
+        };
+        Ok(OptBuilder {
+            builder: record,
+            ttl_pos: ttl_pos,
+            ext_rcode: builder.ext_rcode,
+            report: builder.target.report
+        })
     }
 
     /// Pushes an option to the OPT record.
@@ -537,13 +875,69 @@ impl OptBuilder {
 
     /// Completes the OPT record and returns the additional section builder.
     pub fn complete(self) -> ComposeResult<AdditionalBuilder> {
-        let mut target = MessageTarget { composer: self.builder.finish()? };
+        let mut target = MessageTarget {
+            composer: self.builder.finish()?,
+            report: self.report
+        };
         target.counts_mut().inc_arcount(1)?;
-        Ok(AdditionalBuilder { target })
+        Ok(AdditionalBuilder {
+            target: target,
+            opt_ttl: Some(self.ttl_pos),
+            ext_rcode: self.ext_rcode,
+            opt_reserved: false
+        })
     }
 }
 
 
+//------------ TruncationReport ----------------------------------------------
+
+/// Information on what was lost when a message was truncated.
+///
+/// If a message is built with a size limit, records that don’t fit are
+/// silently dropped and the TC bit is set in the header of the finished
+/// message. A value of this type, returned by the `finish_with_report()`
+/// method of the builders, counts the dropped records in each section.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TruncationReport {
+    /// The number of records dropped from the answer section.
+    pub dropped_answer: usize,
+
+    /// The number of records dropped from the authority section.
+    pub dropped_authority: usize,
+
+    /// The number of records dropped from the additional section.
+    pub dropped_additional: usize,
+
+    /// Whether the TC bit has been set in the message.
+    pub tc_set: bool,
+}
+
+
+//------------ TruncationPolicy ----------------------------------------------
+
+/// The order in which records are dropped when truncating a message.
+///
+/// This is used by [`AdditionalBuilder::finish_for_udp()`]. With either
+/// policy, the question section and the OPT record are always kept.
+///
+/// [`AdditionalBuilder::finish_for_udp()`]: struct.AdditionalBuilder.html#method.finish_for_udp
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TruncationPolicy {
+    /// Drops records one by one from the end of the message.
+    ///
+    /// This drops additional records first, then authority records, and
+    /// only then answer records.
+    DropAdditionalFirst,
+
+    /// Drops all authority and additional records at once.
+    ///
+    /// If the message still doesn’t fit, answer records are dropped from
+    /// the end.
+    DropAllButAnswer,
+}
+
+
 //------------ MessageTarget -------------------------------------------------
 
 /// Underlying data for constructing a DNS message.
@@ -552,13 +946,19 @@ impl OptBuilder {
 #[derive(Clone, Debug)]
 struct MessageTarget {
     composer: ComposeSnapshot,
+
+    /// The records dropped so far.
+    report: TruncationReport,
 }
 
 
 impl MessageTarget {
     /// Creates a new message target atop a given composer.
     fn new(composer: Composer) -> Self {
-        MessageTarget{composer: composer.snapshot()}
+        MessageTarget {
+            composer: composer.snapshot(),
+            report: TruncationReport::default()
+        }
     }
 
     /// Returns a reference to the message’s header.
@@ -576,12 +976,30 @@ impl MessageTarget {
         HeaderCounts::from_message_mut(self.composer.so_far_mut())
     }
 
+    /// Returns the message assembled so far.
+    fn so_far(&self) -> &[u8] {
+        self.composer.so_far()
+    }
+
+    /// Returns the message assembled so far for manipulation.
+    fn so_far_mut(&mut self) -> &mut [u8] {
+        self.composer.so_far_mut()
+    }
+
+    /// Returns the length of the message assembled so far.
+    fn len(&self) -> usize {
+        self.composer.so_far().len()
+    }
+
     /// Pushes something to the end of the message.
     ///
     /// There’s two closures here. The first one, `composeop` actually
     /// writes the data. The second, `incop` increments the counter in the
     /// messages header to reflect the new element.
-    fn push<O, I>(&mut self, composeop: O, incop: I) -> ComposeResult<()>
+    ///
+    /// Returns whether the element was added or dropped because the
+    /// message is truncated.
+    fn push<O, I>(&mut self, composeop: O, incop: I) -> ComposeResult<bool>
             where O: FnOnce(&mut Composer) -> ComposeResult<()>,
                   I: FnOnce(&mut HeaderCounts) -> ComposeResult<()> {
         if !self.composer.is_truncated() {
@@ -589,13 +1007,13 @@ impl MessageTarget {
             match composeop(&mut self.composer) {
                 Ok(()) => {
                     try!(incop(self.counts_mut()));
-                    Ok(())
+                    Ok(true)
                 }
-                Err(ComposeError::SizeExceeded) => Ok(()),
+                Err(ComposeError::SizeExceeded) => Ok(false),
                 Err(error) => Err(error)
             }
         }
-        else { Ok(()) }
+        else { Ok(false) }
     }
 
     /// Returns a reference to the message assembled so far.
@@ -604,10 +1022,16 @@ impl MessageTarget {
     }
 
     /// Finishes the message building and extracts the underlying vector.
-    fn finish(mut self) -> Vec<u8> {
+    fn finish(self) -> Vec<u8> {
+        self.finish_with_report().0
+    }
+
+    /// Finishes the message building and returns the truncation report.
+    fn finish_with_report(mut self) -> (Vec<u8>, TruncationReport) {
         let tc = self.composer.is_truncated();
         self.header_mut().set_tc(tc);
-        self.composer.commit().finish()
+        self.report.tc_set = tc;
+        (self.composer.commit().finish(), self.report)
     }
 
     /// Rewinds the compose snapshots and allows updating the header counts.
@@ -617,9 +1041,34 @@ impl MessageTarget {
         self.composer.rewind()
     }
 
-    /// Commit the compose snapshot.
-    fn commit(self) -> Composer {
-        self.composer.commit()
+    /// Removes duplicate records from the section currently being built.
+    ///
+    /// This is the implementation of the `dedup()` methods of the section
+    /// builders. The closure `target` provides access to the builder’s
+    /// message target and `section` picks the section from the message.
+    /// The deduplicated records are added back by `push` after the
+    /// section has been cleared by `rewind`.
+    fn dedup<B, T, S, R, P>(builder: &mut B, target: T, section: S,
+                            rewind: R, mut push: P) -> ComposeResult<()>
+             where T: FnOnce(&B) -> &MessageTarget,
+                   S: FnOnce(&Message) -> ParseResult<RecordSection>,
+                   R: FnOnce(&mut B),
+                   P: FnMut(&mut B, GenericRecord) -> ComposeResult<()> {
+        let msg = target(builder).so_far().to_vec();
+        let records = dedup_records(section(Message::from_bytes(&msg)?)?)?;
+        rewind(builder);
+        for record in records {
+            push(builder, record)?;
+        }
+        Ok(())
+    }
+
+    /// Commits the compose snapshot and starts the next section.
+    fn next_section(self) -> Self {
+        MessageTarget {
+            composer: self.composer.commit().snapshot(),
+            report: self.report
+        }
     }
 }
 
@@ -629,3 +1078,517 @@ impl AsRef<Message> for MessageTarget {
         unsafe { Message::from_bytes_unsafe(self.composer.so_far()) }
     }
 }
+
+
+//------------ Helper Functions ----------------------------------------------
+
+/// Truncates a complete message to at most `size` octets.
+///
+/// This is a helper for `AdditionalBuilder::finish_for_udp()` and
+/// `Message::send_udp()`. If `msg` is too long, the message is assembled
+/// anew from its header, question, and as many records as fit, so that
+/// compressed names are valid in the result. The first OPT record is
+/// always kept and placed at the end of the message.
+///
+/// Returns an error if `msg` is broken or if the header, question, and
+/// OPT record alone are larger than `size`.
+pub(crate) fn truncate_message(msg: &[u8], size: usize,
+                               policy: TruncationPolicy,
+                               report: &mut TruncationReport)
+                               -> ComposeResult<Vec<u8>> {
+    if msg.len() <= size {
+        return Ok(msg.to_vec())
+    }
+    let message = try!(Message::from_bytes(msg));
+    let counts = message.counts().clone();
+    let mut target = Composer::new(ComposeMode::Limited(size), true);
+    try!(target.compose_bytes(&msg[..mem::size_of::<HeaderSection>()]));
+    for question in message.question() {
+        try!(try!(question).compose(&mut target));
+    }
+
+    // The OPT record doesn’t contain any compressed names, so we can
+    // compose it right away and add it at the very end.
+    let mut opt = None;
+    for record in try!(message.additional()) {
+        let record = try!(record);
+        if record.rtype() == Rtype::Opt {
+            let mut composer = Composer::new(ComposeMode::Unlimited, false);
+            try!(record.compose(&mut composer));
+            let opt_bytes = composer.finish();
+            try!(target.reserve(opt_bytes.len()));
+            opt = Some(opt_bytes);
+            break
+        }
+    }
+
+    let sections = vec![try!(message.answer()), try!(message.authority()),
+                        try!(message.additional())];
+    let mut kept = [0u16; 3];
+    let mut opt_seen = false;
+    'sections: for (section, records) in sections.into_iter().enumerate() {
+        if section > 0 && policy == TruncationPolicy::DropAllButAnswer {
+            break
+        }
+        for record in records {
+            let record = try!(record);
+            if section == 2 && record.rtype() == Rtype::Opt && !opt_seen {
+                opt_seen = true;
+                continue
+            }
+            target.mark_checkpoint();
+            match record.compose(&mut target) {
+                Ok(()) => kept[section] += 1,
+                Err(ComposeError::SizeExceeded) => break 'sections,
+                Err(err) => return Err(err)
+            }
+        }
+    }
+    if let Some(opt) = opt {
+        try!(target.use_reserved(opt.len(),
+                                 |target| target.compose_bytes(&opt)));
+        kept[2] += 1;
+    }
+
+    let mut res = target.finish();
+    report.dropped_answer += (counts.ancount() - kept[0]) as usize;
+    report.dropped_authority += (counts.nscount() - kept[1]) as usize;
+    report.dropped_additional += (counts.arcount() - kept[2]) as usize;
+    {
+        let new_counts = HeaderCounts::from_message_mut(&mut res);
+        new_counts.set_ancount(kept[0]);
+        new_counts.set_nscount(kept[1]);
+        new_counts.set_arcount(kept[2]);
+    }
+    if kept[0] < counts.ancount() {
+        Header::from_message_mut(&mut res).set_tc(true);
+        report.tc_set = true;
+    }
+    Ok(res)
+}
+
+/// Returns the records of a section with duplicates removed.
+///
+/// This is a helper for `MessageTarget::dedup()`.
+fn dedup_records(section: RecordSection)
+                 -> ParseResult<Vec<GenericRecord>> {
+    let mut res: Vec<(GenericRecord, Vec<u8>)> = Vec::new();
+    for record in section {
+        let record = record?;
+        // Compose without compression so that record data with compressed
+        // names compares correctly.
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        if record.data().compose(&mut composer).is_err() {
+            return Err(ParseError::FormErr)
+        }
+        let data = composer.finish();
+        let dup = res.iter_mut().find(|&&mut (ref other, ref other_data)| {
+            other.name() == record.name() && other.class() == record.class()
+                && other.rtype() == record.rtype() && *other_data == data
+        });
+        match dup {
+            Some(&mut (ref mut other, _)) => {
+                if record.ttl() < other.ttl() {
+                    other.set_ttl(record.ttl())
+                }
+            }
+            None => res.push((record, data))
+        }
+    }
+    Ok(res.into_iter().map(|(record, _)| record).collect())
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use bits::{DNameBuf, Message};
+    use bits::opt::{Opt, OptRecord};
+    use iana::{OptRcode, Rcode, Rtype};
+    use super::*;
+
+    fn additional(mode: ComposeMode) -> AdditionalBuilder {
+        let mut msg = MessageBuilder::new(mode, true).unwrap();
+        msg.push((DNameBuf::from_str("example.com.").unwrap(), Rtype::A))
+           .unwrap();
+        msg.additional()
+    }
+
+    /// Returns the number of OPT records and the extended rcode bits.
+    fn ext_rcode(msg: &[u8]) -> (usize, u8) {
+        let msg = Message::from_bytes(msg).unwrap();
+        let mut count = 0;
+        let mut ext = 0;
+        for record in msg.additional().unwrap().limit_to::<Opt>() {
+            count += 1;
+            ext = (record.unwrap().ttl() >> 24) as u8;
+        }
+        (count, ext)
+    }
+
+    #[test]
+    fn extended_rcode_without_opt() {
+        let mut msg = additional(ComposeMode::Unlimited);
+        msg.set_extended_rcode(OptRcode::BadVers).unwrap();
+        let msg = msg.finish().unwrap();
+        assert_eq!(ext_rcode(&msg), (1, 1));
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(msg.header().rcode(), Rcode::NoError);
+        assert!(!msg.header().tc());
+    }
+
+    #[test]
+    fn extended_rcode_with_opt() {
+        // OPT record first.
+        let mut msg = additional(ComposeMode::Unlimited)
+                            .build_opt(1232, OptRcode::NoError, false)
+                            .unwrap().complete().unwrap();
+        msg.set_extended_rcode(OptRcode::BadVers).unwrap();
+        assert_eq!(ext_rcode(&msg.finish().unwrap()), (1, 1));
+
+        // Extended rcode first.
+        let mut msg = additional(ComposeMode::Unlimited);
+        msg.set_extended_rcode(OptRcode::BadVers).unwrap();
+        let msg = msg.build_opt(1232, OptRcode::NoError, false).unwrap()
+                     .complete().unwrap();
+        assert_eq!(ext_rcode(&msg.finish().unwrap()), (1, 1));
+
+        // Plain rcodes don’t need an OPT record.
+        let mut msg = additional(ComposeMode::Unlimited);
+        msg.set_extended_rcode(OptRcode::Refused).unwrap();
+        assert_eq!(ext_rcode(&msg.finish().unwrap()), (0, 0));
+    }
+
+    #[test]
+    fn extended_rcode_after_rewind() {
+        use rdata::owned::A;
+
+        let mut msg = additional(ComposeMode::Limited(512));
+        msg.set_extended_rcode(OptRcode::BadVers).unwrap();
+        let mut msg = msg.build_opt(1232, OptRcode::NoError, false).unwrap()
+                         .complete().unwrap();
+        msg.push((DNameBuf::from_str("example.com.").unwrap(), 3600,
+                  A::from_octets(192, 0, 2, 1))).unwrap();
+        msg.rewind();
+        msg.set_extended_rcode(OptRcode::NoError).unwrap();
+        msg.set_extended_rcode(OptRcode::BadVers).unwrap();
+        let msg = msg.finish().unwrap();
+        assert_eq!(ext_rcode(&msg), (1, 1));
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(msg.counts().arcount(), 1);
+    }
+
+    #[test]
+    fn truncation_report() {
+        use rdata::owned::A;
+
+        let name = DNameBuf::from_str("example.com.").unwrap();
+        let mut msg = MessageBuilder::new(ComposeMode::Limited(100),
+                                          true).unwrap();
+        msg.push((&name, Rtype::A)).unwrap();
+        let mut msg = msg.answer();
+        // The header and question take 29 octets, each record 27. So only
+        // two records fit.
+        for i in 0..4 {
+            msg.push((&name, 3600, A::from_octets(192, 0, 2, i))).unwrap();
+        }
+        let mut msg = msg.additional();
+        msg.push((&name, 3600, A::from_octets(192, 0, 2, 10))).unwrap();
+        let (msg, report) = msg.finish_with_report().unwrap();
+        assert_eq!(report, TruncationReport {
+            dropped_answer: 2,
+            dropped_authority: 0,
+            dropped_additional: 1,
+            tc_set: true
+        });
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(msg.counts().ancount(), 2);
+        assert_eq!(msg.counts().arcount(), 0);
+        assert!(msg.header().tc());
+
+        let (_, report) = additional(ComposeMode::Unlimited)
+                                    .finish_with_report().unwrap();
+        assert_eq!(report, TruncationReport::default());
+    }
+
+    #[test]
+    fn notify() {
+        use iana::{Class, Opcode};
+
+        let zone = DNameBuf::from_str("example.com.").unwrap();
+        let msg = MessageBuilder::notify(&zone, ComposeMode::Limited(512))
+                                 .unwrap().finish();
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(msg.header().opcode(), Opcode::Notify);
+        assert!(msg.header().aa());
+        assert!(!msg.header().qr());
+        assert_eq!(msg.counts().qdcount(), 1);
+        let question = msg.first_question().unwrap();
+        assert_eq!(question.qname(), &zone);
+        assert_eq!(question.qtype(), Rtype::Soa);
+        assert_eq!(question.qclass(), Class::In);
+    }
+
+    /// Returns an overflowing response with three records in each section
+    /// plus an OPT record.
+    fn overflowing() -> AdditionalBuilder {
+        overflowing_with(ComposeMode::Unlimited, false)
+    }
+
+    /// Returns an overflowing response built in the given mode.
+    ///
+    /// If `opt_first` is `true`, the OPT record is the first record of the
+    /// additional section and is followed by an additional AAAA record for
+    /// ns1.example.com. whose name is compressed into the preceding A
+    /// record.
+    fn overflowing_with(mode: ComposeMode, opt_first: bool)
+                        -> AdditionalBuilder {
+        use rdata::owned::{A, Aaaa, Ns};
+
+        let name = |s: &str| DNameBuf::from_str(s).unwrap();
+        let mut msg = MessageBuilder::new(mode, true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.push((name("www.example.com."), Rtype::A)).unwrap();
+        let mut msg = msg.answer();
+        for i in 1..4 {
+            msg.push((name("www.example.com."), 3600,
+                      A::from_octets(192, 0, 2, i))).unwrap();
+        }
+        let mut msg = msg.authority();
+        for ns in &["ns1", "ns2", "ns3"] {
+            msg.push((name("example.com."), 3600,
+                      Ns::new(name(&format!("{}.example.com.", ns)))))
+               .unwrap();
+        }
+        let mut msg = msg.additional();
+        if opt_first {
+            msg.push(OptRecord::for_query(1232, false)).unwrap();
+        }
+        for i in 1..4 {
+            msg.push((name(&format!("ns{}.example.com.", i)), 3600,
+                      A::from_octets(192, 0, 2, 52 + i))).unwrap();
+            if opt_first && i == 1 {
+                msg.push((name("ns1.example.com."), 3600,
+                          Aaaa::new("2001:db8::53".parse().unwrap())))
+                   .unwrap();
+            }
+        }
+        if !opt_first {
+            msg.push(OptRecord::for_query(1232, false)).unwrap();
+        }
+        msg
+    }
+
+    fn section_counts(msg: &[u8]) -> (u16, u16, u16, bool) {
+        let msg = Message::from_bytes(msg).unwrap();
+        let counts = msg.counts();
+        (counts.ancount(), counts.nscount(), counts.arcount(),
+         msg.header().tc())
+    }
+
+    #[test]
+    fn finish_for_udp() {
+        let full = overflowing().finish().unwrap();
+        assert_eq!(section_counts(&full), (3, 3, 4, false));
+        let msg = overflowing()
+                      .finish_for_udp(full.len(),
+                                      TruncationPolicy::DropAllButAnswer)
+                      .unwrap();
+        assert_eq!(msg, full);
+
+        // Dropping two glue records makes the message fit.
+        let size = full.len() - 40;
+        let msg = overflowing()
+                      .finish_for_udp(size,
+                                      TruncationPolicy::DropAdditionalFirst)
+                      .unwrap();
+        assert!(msg.len() <= size);
+        assert_eq!(section_counts(&msg), (3, 3, 2, false));
+        let msg = Message::from_bytes(&msg).unwrap();
+        let opt = msg.additional().unwrap().last().unwrap().unwrap();
+        assert_eq!(opt.rtype(), Rtype::Opt);
+        assert!(msg.is_answer(Message::from_bytes(&full).unwrap()));
+
+        let msg = overflowing()
+                      .finish_for_udp(size,
+                                      TruncationPolicy::DropAllButAnswer)
+                      .unwrap();
+        assert_eq!(section_counts(&msg), (3, 0, 1, false));
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert!(msg.additional().unwrap().limit_to::<Opt>().next()
+                   .is_some());
+
+        // If the answer doesn’t fit, TC is set.
+        let msg = overflowing()
+                      .finish_for_udp(80,
+                                      TruncationPolicy::DropAdditionalFirst)
+                      .unwrap();
+        assert!(msg.len() <= 80);
+        assert_eq!(section_counts(&msg), (1, 0, 1, true));
+
+        // If not even the question and OPT record fit, it’s an error.
+        assert_eq!(overflowing()
+                       .finish_for_udp(40,
+                                       TruncationPolicy::DropAdditionalFirst),
+                   Err(ComposeError::SizeExceeded));
+    }
+
+    #[test]
+    fn finish_for_udp_opt_first() {
+        use rdata::owned::{A, Aaaa};
+
+        let full = overflowing_with(ComposeMode::Unlimited, true).finish()
+                                                         .unwrap();
+        assert_eq!(section_counts(&full), (3, 3, 5, false));
+
+        // Drop the last glue record only. The AAAA record following the
+        // OPT record uses a compressed name which must still be correct.
+        let size = full.len() - 1;
+        let msg = overflowing_with(ComposeMode::Unlimited, true)
+                      .finish_for_udp(size,
+                                      TruncationPolicy::DropAdditionalFirst)
+                      .unwrap();
+        assert!(msg.len() <= size);
+        assert_eq!(section_counts(&msg), (3, 3, 4, false));
+        let msg = Message::from_bytes(&msg).unwrap();
+        let ns1 = DNameBuf::from_str("ns1.example.com.").unwrap();
+        let glue = msg.additional().unwrap().limit_to::<A>().next().unwrap()
+                      .unwrap();
+        assert_eq!(*glue.name(), ns1);
+        let glue = msg.additional().unwrap().limit_to::<Aaaa>().next()
+                      .unwrap().unwrap();
+        assert_eq!(*glue.name(), ns1);
+        let opt = msg.additional().unwrap().last().unwrap().unwrap();
+        assert_eq!(opt.rtype(), Rtype::Opt);
+    }
+
+    #[test]
+    fn finish_for_udp_stream() {
+        let full = overflowing_with(ComposeMode::Stream, false).finish()
+                                                         .unwrap();
+        let size = full.len() - 2 - 40;
+        let msg = overflowing_with(ComposeMode::Stream, false)
+                      .finish_for_udp(size,
+                                      TruncationPolicy::DropAdditionalFirst)
+                      .unwrap();
+        assert!(msg.len() - 2 <= size);
+        assert_eq!(BigEndian::read_u16(&msg) as usize, msg.len() - 2);
+        assert_eq!(section_counts(&msg[2..]), (3, 3, 2, false));
+    }
+
+    #[test]
+    fn single_answer() {
+        use rdata::owned::A;
+
+        let name = DNameBuf::from_str("www.example.com.").unwrap();
+        let mut query = MessageBuilder::new(ComposeMode::Unlimited,
+                                            true).unwrap();
+        query.header_mut().set_id(0x4711);
+        query.header_mut().set_rd(true);
+        query.push((&name, Rtype::A)).unwrap();
+        let query = query.finish();
+        let query = Message::from_bytes(&query).unwrap();
+
+        let msg = MessageBuilder::single_answer(
+            query, (&name, 3600, A::from_octets(192, 0, 2, 1)),
+            ComposeMode::Unlimited
+        ).unwrap();
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert!(msg.is_answer(query));
+        assert_eq!(msg.header().id(), 0x4711);
+        assert!(msg.header().qr());
+        assert!(msg.header().aa());
+        assert!(msg.header().rd());
+        assert_eq!(msg.header().rcode(), Rcode::NoError);
+        assert_eq!(msg.counts().ancount(), 1);
+        let record = msg.answer().unwrap().limit_to::<A>().next().unwrap()
+                        .unwrap();
+        assert_eq!(*record.name(), name);
+        assert_eq!(record.ttl(), 3600);
+        assert_eq!(*record.data(), A::from_octets(192, 0, 2, 1));
+    }
+
+    #[test]
+    fn dso_type_ni() {
+        use iana::{Opcode, Rcode};
+
+        let mut request = MessageBuilder::new(ComposeMode::Stream,
+                                              false).unwrap();
+        request.header_mut().set_id(0x1234);
+        request.header_mut().set_opcode(Opcode::Int(6));
+        let mut request = request.finish();
+        request.extend_from_slice(b"\xff\xff\x00\x00");
+        let request = Message::from_bytes(&request[2..]).unwrap();
+        let msg = MessageBuilder::dso_type_ni(request, ComposeMode::Unlimited)
+                                 .unwrap().finish();
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(msg.header().id(), 0x1234);
+        assert!(msg.header().qr());
+        assert_eq!(msg.header().opcode().to_int(), 6);
+        assert_eq!(msg.header().rcode(), Rcode::DsoTypeNi);
+        assert_eq!(msg.header().rcode().to_int(), 11);
+        assert_eq!(msg.counts().qdcount(), 0);
+    }
+
+    #[test]
+    fn dedup() {
+        use rdata::owned::{A, Mx};
+
+        let name = |s: &str| DNameBuf::from_str(s).unwrap();
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.push((name("example.com."), Rtype::A)).unwrap();
+        let mut msg = msg.answer();
+        msg.push((name("example.com."), 3600, A::from_octets(192, 0, 2, 1)))
+           .unwrap();
+        msg.push((name("example.com."), 3600, A::from_octets(192, 0, 2, 2)))
+           .unwrap();
+        msg.push((name("EXAMPLE.com."), 60, A::from_octets(192, 0, 2, 1)))
+           .unwrap();
+        msg.push((name("example.com."), 3600,
+                  Mx::new(10, name("mail.example.com.")))).unwrap();
+        msg.push((name("example.com."), 3600,
+                  Mx::new(10, name("mail.example.com.")))).unwrap();
+        msg.dedup().unwrap();
+        let msg = msg.finish();
+
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(msg.counts().ancount(), 3);
+        let records: Vec<_> = msg.answer().unwrap().map(|record| {
+            let record = record.unwrap();
+            (record.rtype(), record.ttl())
+        }).collect();
+        assert_eq!(records, vec![(Rtype::A, 60), (Rtype::A, 3600),
+                                 (Rtype::Mx, 3600)]);
+    }
+
+    #[test]
+    fn extended_rcode_truncated() {
+        use rdata::owned::A;
+
+        // Header plus question leave no space for the OPT record.
+        let mut msg = additional(ComposeMode::Limited(39));
+        assert_eq!(msg.set_extended_rcode(OptRcode::BadVers),
+                   Err(ComposeError::SizeExceeded));
+        let msg = msg.finish().unwrap();
+        assert_eq!(ext_rcode(&msg), (0, 0));
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(msg.header().rcode(), Rcode::NoError);
+        assert!(!msg.header().tc());
+
+        // With space for the OPT record, it stays even if later records
+        // are dropped.
+        let name = DNameBuf::from_str("example.com.").unwrap();
+        let mut msg = additional(ComposeMode::Limited(40));
+        msg.set_extended_rcode(OptRcode::BadVers).unwrap();
+        msg.push((&name, 3600, A::from_octets(192, 0, 2, 1))).unwrap();
+        let msg = msg.finish().unwrap();
+        assert_eq!(ext_rcode(&msg), (1, 1));
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(msg.header().rcode(), Rcode::NoError);
+        assert!(msg.header().tc());
+        assert_eq!(msg.counts().arcount(), 1);
+    }
+}
@@ -79,15 +79,17 @@
 pub use self::charstr::{CharStr, CharStrBuf};
 pub use self::compose::{Composable, Composer, ComposeError, ComposeMode,
                         ComposeResult, ComposeSnapshot};
-pub use self::header::{Header, HeaderCounts, HeaderSection};
+pub use self::header::{Header, HeaderCounts, HeaderFlags, HeaderSection};
 pub use self::message::{Message, MessageBuf};
 pub use self::message_builder::{MessageBuilder, AnswerBuilder,
-                                AuthorityBuilder, AdditionalBuilder};
+                                AuthorityBuilder, AdditionalBuilder,
+                                TruncationPolicy, TruncationReport};
 pub use self::name::{DName, DNameBuf, DNameSlice, ParsedDName};
 pub use self::parse::{Parser, ParseError, ParseResult};
 pub use self::question::Question;
-pub use self::rdata::{GenericRecordData, ParsedRecordData, RecordData};
-pub use self::record::{GenericRecord, Record};
+pub use self::rdata::{GenericRecordData, ParsedRecordData, RdataRegistry,
+                      RecordData, RegistryRecordData};
+pub use self::record::{GenericRecord, Record, RegistryRecord};
 
 
 //--- Modules
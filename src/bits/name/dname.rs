@@ -42,6 +42,15 @@ pub trait DName: Sized {
         NameLabelettes::new(self.labels())
     }
 
+    /// Returns the length of the uncompressed wire format of the name.
+    ///
+    /// This is the sum of the lengths of all labels including their
+    /// length octets and, for absolute names, the root label. The method
+    /// does not allocate, not even for parsed names.
+    fn compose_len(&self) -> usize {
+        self.labels().map(|label| label.len()).sum()
+    }
+
     /// Appends the name to the end of a composition.
     fn compose<C: AsMut<Composer>>(&self, mut composer: C)
                                    -> ComposeResult<()> {
@@ -53,5 +62,14 @@ pub trait DName: Sized {
                                               -> ComposeResult<()> {
         composer.as_mut().compose_dname_compressed(self)
     }
+
+    /// Appends the name in its canonical form to the end of a composition.
+    ///
+    /// The canonical form is uncompressed and has all letters converted to
+    /// lowercase.
+    fn compose_canonical<C: AsMut<Composer>>(&self, mut composer: C)
+                                             -> ComposeResult<()> {
+        composer.as_mut().compose_dname_canonical(self)
+    }
 }
 
@@ -0,0 +1,128 @@
+//! Escaping and unescaping of normal labels.
+//!
+//! The presentation format of domain names uses escape sequences for
+//! octets that cannot or must not appear literally in a label. An escape
+//! sequence is a backslash followed either by three decimal digits giving
+//! the value of the octet or by a single printable ASCII character that is
+//! to be taken literally.
+//!
+//! The two functions in this module implement these rules for both
+//! directions. They are used by the `Display` implementations of labels
+//! and by the `FromStr` implementation of `DNameBuf`.
+
+use std::fmt;
+use super::FromStrError;
+
+
+/// Writes the presentation format of a normal label’s content.
+///
+/// Spaces, dots, and backslashes are escaped with a backslash. All octets
+/// outside the printable ASCII range are written as a backslash followed
+/// by three decimal digits. Everything else is written as is.
+pub fn escape_label<W: fmt::Write>(label: &[u8], out: &mut W)
+                                   -> fmt::Result {
+    for &ch in label {
+        if ch == b' ' || ch == b'.' || ch == b'\\' {
+            write!(out, "\\{}", ch as char)?;
+        }
+        else if ch < b' '  || ch >= 0x7F {
+            write!(out, "\\{:03}", ch)?;
+        }
+        else {
+            out.write_char(ch as char)?;
+        }
+    }
+    Ok(())
+}
+
+
+/// Takes the content of a normal label from the beginning of a string.
+///
+/// The label ends at the first dot that is not escaped or at the end of
+/// the string. The function returns the unescaped label content and the
+/// remainder of the string which starts with the dot, if there was one.
+///
+/// Fails if the string contains characters outside the printable ASCII
+/// range, if an escape sequence is malformed, or if the label is longer
+/// than 63 octets. An empty label is not an error.
+pub fn unescape_label(input: &str) -> Result<(Vec<u8>, &str), FromStrError> {
+    let mut label = Vec::new();
+    let mut chars = input.char_indices();
+    let end = loop {
+        match chars.next() {
+            Some((pos, '.')) => break pos,
+            Some((_, '\\')) => {
+                let ch = chars.next().ok_or(FromStrError::UnexpectedEnd)?.1;
+                if let Some(digit) = ch.to_digit(10) {
+                    let mut value = digit;
+                    for _ in 0..2 {
+                        let ch = chars.next()
+                                      .ok_or(FromStrError::UnexpectedEnd)?.1;
+                        value = value * 10
+                              + ch.to_digit(10)
+                                  .ok_or(FromStrError::IllegalEscape)?;
+                    }
+                    if value > 255 {
+                        return Err(FromStrError::IllegalEscape)
+                    }
+                    label.push(value as u8)
+                }
+                else if ch >= ' ' && ch <= '~' {
+                    label.push(ch as u8)
+                }
+                else {
+                    return Err(FromStrError::IllegalEscape)
+                }
+            }
+            Some((_, ch)) if ch >= ' ' && ch <= '~' => label.push(ch as u8),
+            Some(_) => return Err(FromStrError::IllegalCharacter),
+            None => break input.len()
+        }
+    };
+    if label.len() > 63 {
+        return Err(FromStrError::LongLabel)
+    }
+    Ok((label, &input[end..]))
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn escape(label: &[u8]) -> String {
+        let mut res = String::new();
+        escape_label(label, &mut res).unwrap();
+        res
+    }
+
+    #[test]
+    fn round_trip() {
+        let label = b"a.b\x00c d\\\xFF";
+        let escaped = escape(label);
+        assert_eq!(escaped, "a\\.b\\000c\\ d\\\\\\255");
+        assert_eq!(unescape_label(&escaped).unwrap(), (label.to_vec(), ""));
+    }
+
+    #[test]
+    fn unescape() {
+        assert_eq!(unescape_label("www.example.com").unwrap(),
+                   (b"www".to_vec(), ".example.com"));
+        assert_eq!(unescape_label("w\\.w.com").unwrap(),
+                   (b"w.w".to_vec(), ".com"));
+        assert_eq!(unescape_label("\\065\\a").unwrap(),
+                   (b"Aa".to_vec(), ""));
+        assert_eq!(unescape_label(".com").unwrap(), (Vec::new(), ".com"));
+        assert_eq!(unescape_label("a\\25"), Err(FromStrError::UnexpectedEnd));
+        assert_eq!(unescape_label("a\\256"),
+                   Err(FromStrError::IllegalEscape));
+        assert_eq!(unescape_label("a\\1x2"),
+                   Err(FromStrError::IllegalEscape));
+        assert_eq!(unescape_label("\u{e4}"),
+                   Err(FromStrError::IllegalCharacter));
+        assert_eq!(unescape_label(&"a".repeat(64)),
+                   Err(FromStrError::LongLabel));
+    }
+}
@@ -9,6 +9,7 @@
 
 use std::str::Chars;
 use super::FromStrError;
+use super::escape::unescape_label;
 
 
 /// Returns owned bytes of the domain name resulting from a string.
@@ -49,53 +50,11 @@ fn label(chars: &mut Chars, target: &mut Vec<u8>)
 /// Returns `Ok(true)` if there are more labels or `Ok(false)` if that’s it.
 fn normal_label(chars: &mut Chars, target: &mut Vec<u8>)
                 -> Result<bool, FromStrError> {
-    let start = target.len();
-    target.push(0);
-    while let Some(ch) = chars.next() {
-        match ch {
-            '.' => {
-                let len = target.len() - start - 1;
-                if len > 63 {
-                    return Err(FromStrError::LongLabel)
-                }
-                target[start] = len as u8;
-                return Ok(true)
-            }
-            '\\' => {
-                target.push(try!(parse_escape(chars)))
-            }
-            ' ' ... '-' | '/' ... '[' | ']' ... '~' => {
-                target.push(ch as u8);
-            }
-            _ => return Err(FromStrError::IllegalCharacter)
-        }
-    }
-    let len = target.len() - start - 1;
-    if len > 63 {
-        return Err(FromStrError::LongLabel)
-    }
-    target[start] = len as u8;
-    Ok(false)
-}
-
-
-/// Parses the contents of an escape sequence from `chars`.
-///
-/// The backslash should already have been taken out of `chars`.
-fn parse_escape(chars: &mut Chars) -> Result<u8, FromStrError> {
-    let ch = try!(chars.next().ok_or(FromStrError::UnexpectedEnd));
-    if ch == '0' || ch == '1' || ch == '2' {
-        let v = ch.to_digit(10).unwrap() * 100
-              + try!(chars.next().ok_or(FromStrError::UnexpectedEnd)
-                     .and_then(|c| c.to_digit(10)
-                                    .ok_or(FromStrError::IllegalEscape)))
-                     * 10
-              + try!(chars.next().ok_or(FromStrError::UnexpectedEnd)
-                     .and_then(|c| c.to_digit(10)
-                                    .ok_or(FromStrError::IllegalEscape)));
-        Ok(v as u8)
-    }
-    else { Ok(ch as u8) }
+    let (label, rest) = unescape_label(chars.as_str())?;
+    target.push(label.len() as u8);
+    target.extend_from_slice(&label);
+    *chars = rest.chars();
+    Ok(chars.next().is_some())
 }
 
 
@@ -4,6 +4,7 @@ use std::{borrow, cmp, fmt, hash, mem, ops, str};
 use std::borrow::Cow;
 use std::ascii::AsciiExt;
 use std::ops::Deref;
+use super::escape::escape_label;
 use super::plain::{DNameBuf, DNameSlice, PushError};
 
 
@@ -721,18 +722,7 @@ impl<'a> LabelContent<'a> {
     /// This is here because normal labels don’t depend on the format
     /// specifier.
     fn fmt_normal(bytes: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
-        for &ch in bytes {
-            if ch == b' ' || ch == b'.' || ch == b'\\' {
-                try!(write!(f, "\\{}", ch as char));
-            }
-            else if ch < b' '  || ch >= 0x7F {
-                try!(write!(f, "\\{:03}", ch));
-            }
-            else {
-                try!(write!(f, "{}", (ch as char)));
-            }
-        }
-        Ok(())
+        escape_label(bytes, f)
     }
 }
 
@@ -60,14 +60,16 @@
 
 pub use self::builder::{DNameBuilder, DNameBuildInto};
 pub use self::dname::DName;
+pub use self::escape::{escape_label, unescape_label};
 pub use self::iter::{NameLabels, NameLabelettes};
 pub use self::label::{Label, LabelBuf, LabelContent, Labelette, LabelIter};
 pub use self::parsed::ParsedDName;
 pub use self::plain::{DNameBuf, DNameSlice, FromStrError, PushError,
-                      StripSuffixError};
+                      RelativeDName, StripSuffixError};
 
 mod builder;
 mod dname;
+mod escape;
 mod from_str;
 mod iter;
 mod label;
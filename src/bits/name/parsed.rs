@@ -50,18 +50,34 @@ pub struct ParsedDName<'a> {
 /// # Creation and Conversion
 ///
 impl<'a> ParsedDName<'a> {
+    /// The maximum number of compression pointers followed for a name.
+    ///
+    /// A domain name has at most 127 labels. Since there is no point in
+    /// having a pointer point to another pointer, no valid name needs more
+    /// pointers than that.
+    pub const MAX_POINTERS: usize = 127;
+
     /// Creates a new parsed domain name.
     ///
     /// This parses out the leading uncompressed labels from the parser and
     /// then quickly jumps over any possible remaining compressing to check
     /// that the name is valid.
+    ///
+    /// Every compression pointer has to point to a position before the
+    /// labels visited so far. This rejects pointers pointing forward as
+    /// well as any loops. In addition, at most [`MAX_POINTERS`] pointers
+    /// are followed. A name violating any of these rules results in
+    /// `ParseError::FormErr`.
+    ///
+    /// [`MAX_POINTERS`]: #associatedconstant.MAX_POINTERS
     pub fn parse(parser: &mut Parser<'a>) -> ParseResult<Self> {
         let res = ParsedDName{message: parser.bytes(), start: parser.pos()};
+        let mut len = 0;
 
         // Step 1: Walk over uncompressed labels to advance the parser.
-        let pos;
+        let mut pos;
         loop {
-            match try!(Self::parse_label(parser)) {
+            match try!(Self::parse_label_len(parser, &mut len)) {
                 Ok(true) => return Ok(res),
                 Ok(false) => { }
                 Err(x) => {
@@ -72,15 +88,29 @@ impl<'a> ParsedDName<'a> {
         }
 
         // Step 2: Walk over the rest to see if the name is valid.
+        //
+        // To avoid looping forever, every pointer has to point to a
+        // position before the start of the labels seen so far.
         let mut parser = parser.clone();
         parser.remove_limit();
-        try!(parser.seek(pos));
+        let mut first = res.start;
+        let mut pointers = 0;
         loop {
-            let step = try!(Self::parse_label(&mut parser));
-            match step {
-                Ok(true) => return Ok(res),
-                Ok(false) => { }
-                Err(pos) => try!(parser.seek(pos))
+            pointers += 1;
+            if pos >= first || pointers > Self::MAX_POINTERS {
+                return Err(ParseError::FormErr)
+            }
+            try!(parser.seek(pos));
+            first = pos;
+            loop {
+                match try!(Self::parse_label_len(&mut parser, &mut len)) {
+                    Ok(true) => return Ok(res),
+                    Ok(false) => { }
+                    Err(x) => {
+                        pos = x;
+                        break
+                    }
+                }
             }
         }
     }
@@ -208,6 +238,23 @@ impl<'a> ParsedDName<'a> {
         }
     }
 
+    /// Parses a label and adds its length to `len`.
+    ///
+    /// Behaves like `parse_label()` but fails if `len` grows beyond the
+    /// maximum length of a domain name.
+    fn parse_label_len(parser: &mut Parser<'a>, len: &mut usize)
+                       -> ParseResult<Result<bool, usize>> {
+        let start = parser.pos();
+        let res = try!(Self::parse_label(parser));
+        if res.is_ok() {
+            *len += parser.pos() - start;
+            if *len > 255 {
+                return Err(ParseError::FormErr)
+            }
+        }
+        Ok(res)
+    }
+
     /// Parses a label.
     ///
     /// Returns `Ok(is_root)` if the label is a normal label. Returns
@@ -378,3 +425,62 @@ impl<'a> fmt::Debug for ParsedDName<'a> {
 }
 
 
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(message: &[u8], start: usize) -> ParseResult<ParsedDName> {
+        let mut parser = Parser::new(message);
+        parser.seek(start).unwrap();
+        ParsedDName::parse(&mut parser)
+    }
+
+    fn assert_formerr(res: ParseResult<ParsedDName>) {
+        match res {
+            Err(ParseError::FormErr) => { }
+            res => panic!("unexpected result {:?}", res)
+        }
+    }
+
+    #[test]
+    fn compressed() {
+        // example.com. at 0, www + pointer to 0 at 13.
+        let message = b"\x07example\x03com\x00\x03www\xc0\x00";
+        let name = parse(message, 13).unwrap();
+        assert_eq!(name.to_string(), "www.example.com.");
+        assert!(name.as_slice().is_none());
+        assert_eq!(name.unpack().as_bytes(),
+                   b"\x03www\x07example\x03com\x00");
+    }
+
+    #[test]
+    fn pointer_loops() {
+        // A pointer pointing to itself.
+        assert_formerr(parse(b"\xc0\x00", 0));
+
+        // Two labels pointing at each other.
+        assert_formerr(parse(b"\x01a\xc0\x04\x01b\xc0\x00", 4));
+
+        // A pointer pointing forward.
+        assert_formerr(parse(b"\xc0\x02\x00", 0));
+
+        // A pointer pointing backward into a loop.
+        assert_formerr(parse(b"\x01a\xc0\x00\x01b\xc0\x00", 4));
+    }
+
+    #[test]
+    fn pointer_limit() {
+        // A long chain of pointers, each pointing to the one before.
+        let mut message = vec![0];
+        for i in 0..(ParsedDName::MAX_POINTERS + 1) {
+            let target = if i == 0 { 0 } else { 1 + (i - 1) * 2 };
+            message.push(0xc0 | (target >> 8) as u8);
+            message.push(target as u8);
+        }
+        let last = message.len() - 2;
+        assert_formerr(parse(&message, last));
+        assert!(parse(&message, last - 2).is_ok());
+    }
+}
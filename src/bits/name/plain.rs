@@ -9,9 +9,11 @@ use std::mem;
 use std::ops::Deref;
 use std::ptr;
 use std::str;
+use rand::Rng;
+use ::bits::{ParseError, ParseResult};
 use ::master::{Scanner, ScanResult};
 use super::from_str::from_str;
-use super::{DName, Label, NameLabels, NameLabelettes};
+use super::{DName, Label, LabelContent, NameLabels, NameLabelettes};
 
 
 //------------ DNameSlice ----------------------------------------------------
@@ -55,18 +57,47 @@ impl DNameSlice {
     /// As this function traverses the slice to check that it is correctly
     /// encoded, it may take a bit of time.
     pub fn from_bytes(bytes: &[u8]) -> Option<&Self> {
+        DNameSlice::from_slice(bytes).ok()
+    }
+
+    /// Creates a domain name from a bytes slice, reporting what is wrong.
+    ///
+    /// This is the same as [`from_bytes()`] but returns a parse error
+    /// describing why `bytes` isn’t a valid domain name. The name borrows
+    /// from `bytes`, so no data is copied. You can use [`labels()`] to
+    /// iterate over its labels.
+    ///
+    /// If a label extends beyond the end of the slice, the function
+    /// returns `ParseError::UnexpectedEnd`. A label of an unknown type,
+    /// which includes a normal label with more than 63 octets, results in
+    /// `ParseError::UnknownLabel`. Finally, if the name contains a
+    /// compression pointer, data after the root label, or is longer than
+    /// 255 octets, `ParseError::FormErr` is returned.
+    ///
+    /// [`from_bytes()`]: #method.from_bytes
+    /// [`labels()`]: #method.labels
+    pub fn from_slice(bytes: &[u8]) -> ParseResult<&Self> {
+        if bytes.len() > 255 {
+            return Err(ParseError::FormErr)
+        }
         let mut tmp = bytes;
         while !tmp.is_empty() {
             let (label, tail) = match Label::split_from(tmp) {
                 Some(data) => data,
-                None => return None
+                None => {
+                    return Err(match tmp[0] {
+                        0 ..= 0x3F | 0x41 => ParseError::UnexpectedEnd,
+                        0xC0 ..= 0xFF => ParseError::FormErr,
+                        _ => ParseError::UnknownLabel
+                    })
+                }
             };
             if label.is_root() && !tail.is_empty() {
-                return None
+                return Err(ParseError::FormErr)
             }
             tmp = tail;
         }
-        Some(unsafe { DNameSlice::from_bytes_unsafe(bytes) })
+        Ok(unsafe { DNameSlice::from_bytes_unsafe(bytes) })
     }
 
     /// Creates a domain name slice for the root domain name.
@@ -88,6 +119,65 @@ impl DNameSlice {
     pub fn to_owned(&self) -> DNameBuf {
         unsafe { DNameBuf::from_vec_unsafe(self.inner.to_owned()) }
     }
+
+    /// Converts the name into an owned name with all letters in lowercase.
+    ///
+    /// Only the ASCII letters in normal labels are converted. Binary labels
+    /// are copied unchanged.
+    pub fn to_lowercase(&self) -> DNameBuf {
+        use std::ascii::AsciiExt;
+
+        let mut res = Vec::with_capacity(self.inner.len());
+        for label in self.labels() {
+            match label.content() {
+                LabelContent::Normal(content) => {
+                    res.push(content.len() as u8);
+                    res.extend(content.iter().map(u8::to_ascii_lowercase));
+                }
+                _ => res.extend_from_slice(label.as_bytes())
+            }
+        }
+        unsafe { DNameBuf::from_vec_unsafe(res) }
+    }
+
+    /// Converts the name into an owned name with randomized letter case.
+    ///
+    /// Each ASCII letter in normal labels is changed to upper or lower
+    /// case at random using `rng`. This is used in queries for the DNS
+    /// 0x20 technique described in [draft-vixie-dnsext-dns0x20] that makes
+    /// spoofing responses harder. Use [`matches_0x20()`] to check whether
+    /// the question name of a response echoes the exact case.
+    ///
+    /// [draft-vixie-dnsext-dns0x20]: https://tools.ietf.org/html/draft-vixie-dnsext-dns0x20-00
+    /// [`matches_0x20()`]: #method.matches_0x20
+    pub fn randomize_case<R: Rng>(&self, rng: &mut R) -> DNameBuf {
+        let mut res = Vec::with_capacity(self.inner.len());
+        for label in self.labels() {
+            match label.content() {
+                LabelContent::Normal(content) => {
+                    res.push(content.len() as u8);
+                    res.extend(content.iter().map(|ch| {
+                        if rng.gen() { ch.to_ascii_uppercase() }
+                        else { ch.to_ascii_lowercase() }
+                    }));
+                }
+                _ => res.extend_from_slice(label.as_bytes())
+            }
+        }
+        unsafe { DNameBuf::from_vec_unsafe(res) }
+    }
+
+    /// Checks whether `name` is identical to `self` including letter case.
+    ///
+    /// Whereas normal comparison of domain names ignores the case of ASCII
+    /// letters, this method requires the exact same case. It can be used
+    /// to check the question name of a response against a query name
+    /// created via [`randomize_case()`].
+    ///
+    /// [`randomize_case()`]: #method.randomize_case
+    pub fn matches_0x20<N: DName>(&self, name: &N) -> bool {
+        self.as_bytes() == name.to_cow().as_bytes()
+    }
 }
 
 /// Unsafely creates a domain name slice from a bytes slice.
@@ -195,6 +285,24 @@ impl DNameSlice {
         }
     }
 
+    /// Returns the wildcard name covering the domain name.
+    ///
+    /// The wildcard name is the domain name with its leftmost label
+    /// replaced by an asterisk label. This is the owner name of a wildcard
+    /// record that may match `self`. Returns `None` under the same
+    /// conditions as [`parent()`], i.e., for the root name and for an
+    /// empty name.
+    ///
+    /// [`parent()`]: #method.parent
+    pub fn wildcard_parent(&self) -> Option<DNameBuf> {
+        self.parent().map(|parent| {
+            let mut res = DNameBuf::with_capacity(parent.inner.len() + 2);
+            res.push_normal(b"*").unwrap();
+            res.append(&parent).unwrap();
+            res
+        })
+    }
+
     /// Returns a domain name slice relative to `base`.
     ///
     /// This fails if `base` isn’t a suffix of `self`.
@@ -222,6 +330,17 @@ impl DNameSlice {
         }
     }
 
+    /// Returns the name relative to `origin` if it is below it.
+    ///
+    /// The suffix is compared ignoring the case of ASCII letters. If
+    /// `self` is `origin` itself, the result is the empty name that is
+    /// displayed as `@` in master files. If `origin` isn’t a suffix of
+    /// `self`, returns `None`.
+    pub fn relative_to<'a, N: DName>(&'a self, origin: &'a N)
+                                     -> Option<RelativeDName<'a>> {
+        self.strip_suffix(origin).ok().map(RelativeDName)
+    }
+
     /// Determines whether `base` is a prefix of `self`.
     pub fn starts_with<N: DName>(&self, base: &N) -> bool {
         let mut self_iter = self.labelettes();
@@ -882,7 +1001,7 @@ impl fmt::Debug for DNameBuf {
 
 //------------ FromStrError --------------------------------------------
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum FromStrError {
     /// The string ended when there should have been more characters.
     ///
@@ -994,6 +1113,47 @@ impl fmt::Display for StripSuffixError {
 }
 
 
+//------------ RelativeDName -------------------------------------------------
+
+/// A domain name made relative to an origin.
+///
+/// Values of this type are returned by [`DNameSlice::relative_to()`].
+/// The type dereferences into the relative domain name. Its `Display`
+/// implementation produces the master file representation, i.e., the
+/// origin itself is displayed as `@`.
+///
+/// [`DNameSlice::relative_to()`]: struct.DNameSlice.html#method.relative_to
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RelativeDName<'a>(Cow<'a, DNameSlice>);
+
+impl<'a> RelativeDName<'a> {
+    /// Returns whether the name is the origin itself.
+    pub fn is_apex(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Converts the value into the relative domain name.
+    pub fn into_name(self) -> Cow<'a, DNameSlice> {
+        self.0
+    }
+}
+
+impl<'a> Deref for RelativeDName<'a> {
+    type Target = DNameSlice;
+
+    fn deref(&self) -> &DNameSlice {
+        &self.0
+    }
+}
+
+impl<'a> fmt::Display for RelativeDName<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_apex() { "@".fmt(f) }
+        else { self.0.fmt(f) }
+    }
+}
+
+
 //============ Testing ======================================================
 
 #[cfg(test)]
@@ -1019,6 +1179,51 @@ mod test {
         assert!(DNameSlice::from_bytes(b"\x03foo\x03bar\x00\x03foo").is_none());
     }
 
+    #[test]
+    fn slice_from_slice() {
+        let name = DNameSlice::from_slice(b"\x03www\x07example\x03com\x00")
+                              .unwrap();
+        assert_eq!(name.labels().count(), 4);
+        assert_eq!(name.labels().next().unwrap().as_bytes(), b"\x03www");
+        assert_eq!(name.to_string(), "www.example.com.");
+
+        let mut long = vec![63];
+        long.extend_from_slice(&[b'x'; 63]);
+        assert!(DNameSlice::from_slice(&long).is_ok());
+        long[0] = 64;
+        long.push(b'x');
+        match DNameSlice::from_slice(&long) {
+            Err(ParseError::UnknownLabel) => { }
+            res => panic!("unexpected result {:?}", res)
+        }
+
+        let mut huge = Vec::new();
+        for _ in 0..4 {
+            huge.push(63);
+            huge.extend_from_slice(&[b'x'; 63]);
+        }
+        huge.push(0);
+        assert_eq!(huge.len(), 257);
+        match DNameSlice::from_slice(&huge) {
+            Err(ParseError::FormErr) => { }
+            res => panic!("unexpected result {:?}", res)
+        }
+        assert!(DNameSlice::from_bytes(&huge).is_none());
+
+        match DNameSlice::from_slice(b"\x03foo\x03ba") {
+            Err(ParseError::UnexpectedEnd) => { }
+            res => panic!("unexpected result {:?}", res)
+        }
+        match DNameSlice::from_slice(b"\x03foo\xc0\x0c") {
+            Err(ParseError::FormErr) => { }
+            res => panic!("unexpected result {:?}", res)
+        }
+        match DNameSlice::from_slice(b"\x00\x03foo") {
+            Err(ParseError::FormErr) => { }
+            res => panic!("unexpected result {:?}", res)
+        }
+    }
+
     #[test]
     fn slice_display() {
         assert_eq!(format!("{}", slice(b"\x03foo")), "foo");
@@ -1151,6 +1356,56 @@ mod test {
         assert_eq!(foodot.join(&foodot).unwrap(), foodot);
     }
 
+    #[test]
+    fn slice_0x20() {
+        use rand::{SeedableRng, XorShiftRng};
+        use ::bits::{ParsedDName, Parser};
+
+        let name = DNameBuf::from_str("www.example.com.").unwrap();
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let query = name.randomize_case(&mut rng);
+        assert_eq!(query, name);
+        assert!(!query.matches_0x20(&name));
+        assert!(query.matches_0x20(&query));
+
+        // Parsing must preserve the case.
+        let bytes = query.as_bytes().to_vec();
+        let parsed = ParsedDName::parse(&mut Parser::new(&bytes)).unwrap();
+        assert!(query.matches_0x20(&parsed));
+        assert!(!query.matches_0x20(&parsed.to_cow().to_lowercase()));
+    }
+
+    #[test]
+    fn slice_wildcard_parent() {
+        let name = DNameBuf::from_str("a.b.example.com.").unwrap();
+        assert_eq!(name.wildcard_parent().unwrap().to_string(),
+                   "*.b.example.com.");
+        let name = DNameBuf::from_str("com.").unwrap();
+        assert_eq!(name.wildcard_parent().unwrap().to_string(), "*.");
+        assert!(DNameSlice::root().wildcard_parent().is_none());
+        assert!(DNameSlice::empty().wildcard_parent().is_none());
+    }
+
+    #[test]
+    fn slice_relative_to() {
+        let origin = DNameBuf::from_str("Example.com.").unwrap();
+        let name = DNameBuf::from_str("www.example.COM.").unwrap();
+        let rel = name.relative_to(&origin).unwrap();
+        assert!(!rel.is_apex());
+        assert!(rel.is_relative());
+        assert_eq!(rel.to_string(), "www");
+
+        let name = DNameBuf::from_str("example.com.").unwrap();
+        let rel = name.relative_to(&origin).unwrap();
+        assert!(rel.is_apex());
+        assert_eq!(rel.to_string(), "@");
+
+        let name = DNameBuf::from_str("www.example.org.").unwrap();
+        assert!(name.relative_to(&origin).is_none());
+        let name = DNameBuf::from_str("www.badexample.com.").unwrap();
+        assert!(name.relative_to(&origin).is_none());
+    }
+
     //--- DNameBuf
     fn buf(bytes: &[u8]) -> DNameBuf {
         DNameBuf::from_vec(Vec::from(bytes)).unwrap()
@@ -1193,4 +1448,21 @@ mod test {
         name.append(&suffix).unwrap();
         assert_eq!(name.to_string(), "foo.bar.");
     }
+
+    #[test]
+    fn compose_len() {
+        let name = DNameBuf::from_str("www.example.com.").unwrap();
+        assert_eq!(name.compose_len(), 17);
+        assert_eq!(name.compose_len(), name.as_bytes().len());
+        assert_eq!(DNameSlice::root().compose_len(), 1);
+        assert_eq!(DNameBuf::from_str("www").unwrap().compose_len(), 4);
+    }
+
+    #[test]
+    fn escaped_round_trip() {
+        let name = DNameBuf::from_str("a\\.b\\000c.example.").unwrap();
+        assert_eq!(name.as_bytes(), b"\x05a.b\x00c\x07example\x00");
+        assert_eq!(name.to_string(), "a\\.b\\000c.example.");
+        assert_eq!(DNameBuf::from_str(&name.to_string()).unwrap(), name);
+    }
 }
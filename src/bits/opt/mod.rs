@@ -6,10 +6,11 @@
 //! `rdata` module and the types defined for operating on them differ from
 //! how other record types are handled.
 
+use std::fmt;
 use std::marker::PhantomData;
-use ::iana::{OptionCode, Rtype};
-use super::{Composer, ComposeResult, ParsedRecordData, Parser, ParseResult,
-            RecordData};
+use ::iana::{Class, OptionCode, OptRcode, Rcode, Rtype};
+use super::{Composer, ComposeResult, DName, DNameSlice, ParsedRecordData,
+            Parser, ParseResult, Record, RecordData};
 
 
 pub mod rfc5001;
@@ -21,6 +22,7 @@ pub mod rfc7871;
 pub mod rfc7873;
 pub mod rfc7901;
 pub mod rfc8145;
+pub mod rfc8914;
 
 
 //------------ Opt -----------------------------------------------------------
@@ -32,6 +34,28 @@ impl<'a> Opt<'a> {
     pub fn iter<O: ParsedOptData<'a>>(&self) -> OptIter<'a, O> {
         OptIter::new(self.0.clone())
     }
+
+    /// Returns the codes of all options in the order they appear.
+    ///
+    /// The option data is skipped without being parsed. If the options are
+    /// malformed, only the codes of the options before the offending one
+    /// are returned.
+    pub fn option_codes(&self) -> Vec<u16> {
+        let mut parser = self.0.clone();
+        let mut res = Vec::new();
+        while parser.remaining() > 0 {
+            let code = parser.parse_u16().and_then(|code| {
+                let len = parser.parse_u16()? as usize;
+                parser.skip(len)?;
+                Ok(code)
+            });
+            match code {
+                Ok(code) => res.push(code),
+                Err(_) => break
+            }
+        }
+        res
+    }
 }
 
 impl<'a> RecordData for Opt<'a> {
@@ -51,7 +75,7 @@ impl<'a> ParsedRecordData<'a> for Opt<'a> {
     fn parse(rtype: Rtype, parser: &mut Parser<'a>)
              -> ParseResult<Option<Self>> {
         if rtype == Rtype::Opt {
-            Ok(Some(Opt(parser.clone())))
+            Ok(Some(Opt(Parser::new(parser.parse_remaining()?))))
         }
         else {
             Ok(None)
@@ -60,6 +84,184 @@ impl<'a> ParsedRecordData<'a> for Opt<'a> {
 }
 
 
+//------------ OptRecord -----------------------------------------------------
+
+/// The values of an OPT record’s header.
+///
+/// OPT records repurpose the class and TTL fields of the record header.
+/// The class contains the requestor’s UDP payload size while the TTL is
+/// split into the upper eight bits of the extended rcode, the EDNS
+/// version, and a set of flags of which currently only the DNSSEC OK (DO)
+/// flag is defined.
+///
+/// A value of this type can be converted into an OPT record without any
+/// options for pushing to a message builder. If you need to add options,
+/// use `AdditionalBuilder::build_opt()` instead.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct OptRecord {
+    udp_payload_size: u16,
+    ext_rcode: u8,
+    version: u8,
+    flags: u16,
+}
+
+impl OptRecord {
+    /// Creates a new value from its components.
+    pub fn new(udp_payload_size: u16, ext_rcode: u8, version: u8,
+               dnssec_ok: bool) -> Self {
+        OptRecord {
+            udp_payload_size: udp_payload_size,
+            ext_rcode: ext_rcode,
+            version: version,
+            flags: if dnssec_ok { 0x8000 } else { 0 }
+        }
+    }
+
+    /// Creates a value for use in a query.
+    ///
+    /// The EDNS version and extended rcode will be zero.
+    pub fn for_query(payload_size: u16, dnssec_ok: bool) -> Self {
+        Self::new(payload_size, 0, 0, dnssec_ok)
+    }
+
+    /// Takes the values from the header of a parsed OPT record.
+    pub fn from_record<'a, N: DName>(record: &Record<N, Opt<'a>>) -> Self {
+        let ttl = record.ttl();
+        OptRecord {
+            udp_payload_size: record.class().to_int(),
+            ext_rcode: (ttl >> 24) as u8,
+            version: (ttl >> 16) as u8,
+            flags: ttl as u16
+        }
+    }
+
+    /// Returns the UDP payload size of the sender.
+    pub fn udp_payload_size(&self) -> u16 {
+        self.udp_payload_size
+    }
+
+    /// Returns the upper eight bits of the extended rcode.
+    pub fn ext_rcode(&self) -> u8 {
+        self.ext_rcode
+    }
+
+    /// Returns the EDNS version.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns whether the DNSSEC OK flag is set.
+    pub fn dnssec_ok(&self) -> bool {
+        self.flags & 0x8000 != 0
+    }
+
+    /// Returns the value of the record’s class field.
+    pub fn class(&self) -> Class {
+        Class::Int(self.udp_payload_size)
+    }
+
+    /// Returns the value of the record’s TTL field.
+    pub fn ttl(&self) -> u32 {
+        (self.ext_rcode as u32) << 24 | (self.version as u32) << 16
+            | self.flags as u32
+    }
+
+    /// Returns a value displaying the record like dig’s OPT pseudosection.
+    ///
+    /// The `options` are the record data of the OPT record. The `rcode`
+    /// is the rcode from the message header. It is combined with the upper
+    /// bits of the extended rcode and shown if the latter are not zero.
+    pub fn display_pseudosection<'a>(&self, options: &Opt<'a>,
+                                     rcode: Rcode) -> PseudoSection<'a> {
+        PseudoSection { opt: *self, options: options.clone(), rcode: rcode }
+    }
+}
+
+impl From<OptRecord> for Record<&'static DNameSlice, Opt<'static>> {
+    fn from(opt: OptRecord) -> Self {
+        Record::new(DNameSlice::root(), opt.class(), opt.ttl(),
+                    Opt(Parser::new(b"")))
+    }
+}
+
+
+//------------ PseudoSection -------------------------------------------------
+
+/// A helper type for displaying an OPT record like dig does.
+///
+/// A value of this type is returned by
+/// `OptRecord::display_pseudosection()`. Its `Display` implementation
+/// produces the lines of the ‘OPT PSEUDOSECTION’ printed by dig, each
+/// ending in a line feed.
+#[derive(Clone, Debug)]
+pub struct PseudoSection<'a> {
+    opt: OptRecord,
+    options: Opt<'a>,
+    rcode: Rcode,
+}
+
+impl<'a> fmt::Display for PseudoSection<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, ";; OPT PSEUDOSECTION:")?;
+        write!(f, "; EDNS: version: {}, flags:", self.opt.version())?;
+        if self.opt.dnssec_ok() {
+            f.write_str(" do")?;
+        }
+        writeln!(f, "; udp: {}", self.opt.udp_payload_size())?;
+        if self.opt.ext_rcode() != 0 {
+            writeln!(f, "; EXTENDED RCODE: {}",
+                     OptRcode::from_parts(self.rcode, self.opt.ext_rcode()))?;
+        }
+        let mut parser = self.options.0.clone();
+        while parser.remaining() > 0 {
+            let option = parser.parse_u16().and_then(|code| {
+                let len = parser.parse_u16()? as usize;
+                Ok((OptionCode::from_int(code), parser.parse_bytes(len)?))
+            });
+            let (code, data) = match option {
+                Ok(option) => option,
+                Err(_) => return writeln!(f, "; <invalid options>")
+            };
+            match code {
+                OptionCode::Cookie => {
+                    f.write_str("; COOKIE: ")?;
+                    for ch in data {
+                        write!(f, "{:02x}", ch)?;
+                    }
+                    writeln!(f)?;
+                }
+                OptionCode::Nsid => {
+                    f.write_str("; NSID:")?;
+                    fmt_option_data(data, f)?;
+                }
+                _ => {
+                    write!(f, "; OPT={}:", code.to_int())?;
+                    fmt_option_data(data, f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes option data as hex octets followed by their printable form.
+fn fmt_option_data(data: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+    for ch in data {
+        write!(f, " {:02x}", ch)?;
+    }
+    f.write_str(" (\"")?;
+    for &ch in data {
+        if ch >= 0x20 && ch < 0x7F {
+            write!(f, "{}", ch as char)?;
+        }
+        else {
+            f.write_str(".")?;
+        }
+    }
+    writeln!(f, "\")")
+}
+
+
 //------------ OptIter -------------------------------------------------------
 
 #[derive(Clone, Debug)]
@@ -82,7 +284,13 @@ impl<'a, O: ParsedOptData<'a>> Iterator for OptIter<'a, O> {
             match self.next_step() {
                 Ok(Some(res)) => return Some(Ok(res)),
                 Ok(None) => { }
-                Err(err) => return Some(Err(err)),
+                Err(err) => {
+                    // Fuse the iterator by skipping over all remaining data.
+                    self.parser.remove_limit();
+                    let len = self.parser.remaining();
+                    let _ = self.parser.skip(len);
+                    return Some(Err(err))
+                }
             }
         }
         None
@@ -93,8 +301,14 @@ impl<'a, O: ParsedOptData<'a>> OptIter<'a, O> {
     fn next_step(&mut self) -> ParseResult<Option<O>> {
         let code = self.parser.parse_u16()?.into();
         let len = self.parser.parse_u16()? as usize;
+        let end = self.parser.pos() + len;
         self.parser.set_limit(len)?;
-        O::parse(code, &mut self.parser)
+        let res = O::parse(code, &mut self.parser)?;
+        // Skip over whatever the option data left unparsed, including the
+        // entire option if it wasn’t of the requested type.
+        self.parser.seek(end)?;
+        self.parser.remove_limit();
+        Ok(res)
     }
 }
 
@@ -112,3 +326,62 @@ pub trait ParsedOptData<'a>: OptData {
 }
 
 
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use ::bits::{ComposeMode, Message, MessageBuilder};
+    use super::*;
+
+    #[test]
+    fn for_query() {
+        let opt = OptRecord::for_query(1232, true);
+        assert_eq!(opt.udp_payload_size(), 1232);
+        assert!(opt.dnssec_ok());
+        assert_eq!(opt.ext_rcode(), 0);
+        assert_eq!(opt.version(), 0);
+        assert_eq!(opt.ttl(), 0x8000);
+        assert!(!OptRecord::for_query(512, false).dnssec_ok());
+
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap().additional();
+        msg.push(opt).unwrap();
+        let bytes = msg.finish().unwrap();
+        let msg = Message::from_bytes(&bytes).unwrap();
+        let additional = msg.additional().unwrap();
+        let record = additional.limit_to::<Opt>().next().unwrap().unwrap();
+        assert_eq!(OptRecord::from_record(&record), opt);
+        assert_eq!(record.data().iter::<rfc5001::Nsid<&[u8]>>().count(), 0);
+    }
+
+    #[test]
+    fn display_pseudosection() {
+        use ::iana::{OptRcode, Rcode};
+
+        let msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                      true).unwrap().additional();
+        let mut opt = msg.build_opt(1232, OptRcode::NoError, true).unwrap();
+        opt.push(rfc7873::Cookie::new([1, 2, 3, 4, 5, 6, 7, 8])).unwrap();
+        opt.push(rfc5001::Nsid::new(b"ns1")).unwrap();
+        let bytes = opt.complete().unwrap().finish().unwrap();
+        let msg = Message::from_bytes(&bytes).unwrap();
+        let additional = msg.additional().unwrap();
+        let record = additional.limit_to::<Opt>().next().unwrap().unwrap();
+        let opt = OptRecord::from_record(&record);
+        assert_eq!(opt.display_pseudosection(record.data(), Rcode::NoError)
+                      .to_string(),
+                   ";; OPT PSEUDOSECTION:\n\
+                    ; EDNS: version: 0, flags: do; udp: 1232\n\
+                    ; COOKIE: 0102030405060708\n\
+                    ; NSID: 6e 73 31 (\"ns1\")\n");
+
+        let opt = OptRecord::new(512, 1, 0, false);
+        assert_eq!(opt.display_pseudosection(&Opt(Parser::new(b"")),
+                                             Rcode::YXRRSet).to_string(),
+                   ";; OPT PSEUDOSECTION:\n\
+                    ; EDNS: version: 0, flags:; udp: 512\n\
+                    ; EXTENDED RCODE: BADCOOKIE\n");
+    }
+}
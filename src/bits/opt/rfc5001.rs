@@ -1,5 +1,6 @@
 /// EDNS0 Options from RFC 5001.
 
+use std::borrow::Cow;
 use std::fmt;
 use ::bits::{Composer, ComposeResult, Parser, ParseResult};
 use ::iana::OptionCode;
@@ -18,6 +19,19 @@ impl<B: AsRef<[u8]>> Nsid<B> {
     pub fn new(data: B) -> Self {
         Nsid(data)
     }
+
+    /// Returns the raw payload of the option.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+
+    /// Returns the payload as a string.
+    ///
+    /// The payload is opaque but usually ASCII. Any octets that aren’t
+    /// valid UTF-8 are replaced with the replacement character.
+    pub fn as_str<'a>(&'a self) -> Cow<'a, str> {
+        String::from_utf8_lossy(self.0.as_ref())
+    }
 }
 
 impl<B: AsRef<[u8]>> OptData for Nsid<B> {
@@ -55,3 +69,25 @@ impl<B: AsRef<[u8]>> fmt::Display for Nsid<B> {
 }
 
 
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use ::bits::Parser;
+    use ::bits::opt::ParsedOptData;
+    use ::iana::OptionCode;
+    use super::*;
+
+    #[test]
+    fn parse_ascii() {
+        let mut parser = Parser::new(b"\x00\x03\x00\x07ns1.ams");
+        let code = OptionCode::from(parser.parse_u16().unwrap());
+        let len = parser.parse_u16().unwrap() as usize;
+        parser.set_limit(len).unwrap();
+        let nsid = Nsid::parse(code, &mut parser).unwrap().unwrap();
+        assert_eq!(parser.remaining(), 0);
+        assert_eq!(nsid.as_bytes(), b"ns1.ams");
+        assert_eq!(nsid.as_str(), "ns1.ams");
+        assert_eq!(Nsid::new(b"ns\xff").as_str(), "ns\u{fffd}");
+    }
+}
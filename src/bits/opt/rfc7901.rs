@@ -8,14 +8,24 @@ use super::{OptData, ParsedOptData};
 
 //------------ Chain --------------------------------------------------------
 
+/// The CHAIN option.
+///
+/// A client uses this option to ask a validating resolver to include all
+/// records necessary to validate the answer starting at the closest trust
+/// point the client already has. The option carries the name of this
+/// trust point in uncompressed form.
+///
+/// Specified in RFC 7901.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Chain<N: DName>(N);
 
 impl<N: DName> Chain<N> {
+    /// Creates a new CHAIN option for the given closest trust point.
     pub fn new(name: N) -> Self {
         Chain(name)
     }
 
+    /// Returns the closest trust point name.
     pub fn name(&self) -> &N {
         &self.0
     }
@@ -28,7 +38,7 @@ impl<N: DName> OptData for Chain<N> {
         let pos = target.pos();
         target.compose_u16(0)?;
         target.compose_dname(&self.0)?;
-        let len = target.pos() - pos;
+        let len = target.pos() - pos - 2;
         if len > ::std::u16::MAX as usize {
             return Err(ComposeError::SizeExceeded)
         }
@@ -49,3 +59,32 @@ impl<'a> ParsedOptData<'a> for Chain<ParsedDName<'a>> {
     }
 }
 
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use ::bits::{ComposeMode, Composer, DNameBuf, Parser};
+    use ::bits::opt::{OptData, ParsedOptData};
+    use ::iana::OptionCode;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let name = DNameBuf::from_str("example.com.").unwrap();
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        Chain::new(name.clone()).compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(wire, b"\x00\x0d\x00\x0d\x07example\x03com\x00");
+
+        let mut parser = Parser::new(&wire);
+        let code = OptionCode::from(parser.parse_u16().unwrap());
+        let len = parser.parse_u16().unwrap() as usize;
+        parser.set_limit(len).unwrap();
+        let chain = Chain::parse(code, &mut parser).unwrap().unwrap();
+        assert_eq!(chain.name().to_cow().into_owned(), name);
+        assert_eq!(parser.remaining(), 0);
+    }
+}
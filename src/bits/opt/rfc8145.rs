@@ -7,10 +7,28 @@ use super::{OptData, ParsedOptData};
 
 //------------ KeyTag -------------------------------------------------------
 
+/// The edns-key-tag option.
+///
+/// The option contains a list of the key tags of the trust anchors a
+/// resolver uses. Specified in RFC 8145.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct KeyTag<B: AsRef<[u8]>>(B);
 
 impl<B: AsRef<[u8]>> KeyTag<B> {
+    /// Returns the number of key tags in the option.
+    pub fn len(&self) -> usize {
+        self.0.as_ref().len() / 2
+    }
+
+    /// Returns whether the option contains no key tags.
+    pub fn is_empty(&self) -> bool {
+        self.0.as_ref().is_empty()
+    }
+
+    /// Returns an iterator over the key tags in the option.
+    pub fn iter<'b>(&'b self) -> KeyTagIter<'b> {
+        KeyTagIter(self.0.as_ref())
+    }
 }
 
 impl<'a> KeyTag<&'a [u8]> {
@@ -28,7 +46,7 @@ impl KeyTag<Vec<u8>> {
         if self.0.len() >= (0xFFFF - 2) {
             panic!("excessively large Keytag");
         }
-        self.0.push((tag & 0xFF00 >> 8) as u8);
+        self.0.push((tag >> 8) as u8);
         self.0.push((tag & 0xFF) as u8);
     }
 }
@@ -57,3 +75,57 @@ impl<'a> ParsedOptData<'a> for KeyTag<&'a [u8]> {
         }
     }
 }
+
+
+//------------ KeyTagIter ---------------------------------------------------
+
+/// An iterator over the key tags of a KEY-TAG option.
+#[derive(Clone, Debug)]
+pub struct KeyTagIter<'a>(&'a [u8]);
+
+impl<'a> Iterator for KeyTagIter<'a> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.len() < 2 {
+            return None
+        }
+        let (tag, tail) = self.0.split_at(2);
+        self.0 = tail;
+        Some((tag[0] as u16) << 8 | tag[1] as u16)
+    }
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use ::bits::{ComposeMode, Composer, Parser};
+    use ::bits::opt::{OptData, ParsedOptData};
+    use ::iana::OptionCode;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut key_tag = KeyTag::<Vec<u8>>::new();
+        key_tag.push(20326);
+        key_tag.push(19036);
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        key_tag.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(wire, b"\x00\x0e\x00\x04\x4f\x66\x4a\x5c");
+
+        let mut parser = Parser::new(&wire);
+        let code = OptionCode::from(parser.parse_u16().unwrap());
+        let len = parser.parse_u16().unwrap() as usize;
+        parser.set_limit(len).unwrap();
+        let parsed = KeyTag::parse(code, &mut parser).unwrap().unwrap();
+        assert_eq!(parser.remaining(), 0);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.iter().collect::<Vec<_>>(), [20326, 19036]);
+
+        let mut parser = Parser::new(b"\x4f");
+        assert!(KeyTag::parse(OptionCode::EdnsKeyTag, &mut parser).is_err());
+    }
+}
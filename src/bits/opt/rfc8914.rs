@@ -0,0 +1,156 @@
+//! EDNS Options from RFC 8914.
+
+use std::fmt;
+use ::bits::{Composer, ComposeError, ComposeResult, Parser, ParseResult};
+use ::iana::{ExtendedErrorCode, OptionCode, OptRcode};
+use super::{OptData, ParsedOptData};
+
+
+//------------ Ede ----------------------------------------------------------
+
+/// The Extended DNS Error (EDE) option.
+///
+/// A server can add this option to a response to give more detailed
+/// information about why it failed or why it answered the way it did. The
+/// option carries an info code and optional UTF-8 text meant for human
+/// consumption.
+///
+/// Specified in RFC 8914.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Ede<B: AsRef<[u8]>> {
+    code: ExtendedErrorCode,
+    extra_text: B,
+}
+
+impl<B: AsRef<[u8]>> Ede<B> {
+    /// Creates a new EDE option from the info code and extra text.
+    pub fn new(code: ExtendedErrorCode, extra_text: B) -> Self {
+        Ede { code: code, extra_text: extra_text }
+    }
+
+    /// Returns the info code of the error.
+    pub fn code(&self) -> ExtendedErrorCode {
+        self.code
+    }
+
+    /// Returns the raw extra text.
+    ///
+    /// The text should be UTF-8 but this isn’t checked during parsing.
+    /// It may also be empty.
+    pub fn extra_text(&self) -> &[u8] {
+        self.extra_text.as_ref()
+    }
+}
+
+impl<B: AsRef<[u8]>> OptData for Ede<B> {
+    fn compose<C: AsMut<Composer>>(&self, mut target: C) -> ComposeResult<()> {
+        let len = self.extra_text().len() + 2;
+        if len > ::std::u16::MAX as usize {
+            return Err(ComposeError::SizeExceeded)
+        }
+        let target = target.as_mut();
+        target.compose_u16(OptionCode::ExtendedError.into())?;
+        target.compose_u16(len as u16)?;
+        target.compose_u16(self.code.into())?;
+        target.compose_bytes(self.extra_text())
+    }
+}
+
+impl<'a> ParsedOptData<'a> for Ede<&'a [u8]> {
+    fn parse(code: OptionCode, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if code != OptionCode::ExtendedError {
+            return Ok(None)
+        }
+        let code = parser.parse_u16()?.into();
+        let extra_text = parser.parse_remaining()?;
+        Ok(Some(Ede::new(code, extra_text)))
+    }
+}
+
+impl<B: AsRef<[u8]>> fmt::Display for Ede<B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EDE {}", self.code.to_int())?;
+        if self.code.to_mnemonic().is_some() {
+            write!(f, " ({})", self.code)?;
+        }
+        if !self.extra_text().is_empty() {
+            write!(f, ": {}", String::from_utf8_lossy(self.extra_text()))?;
+        }
+        Ok(())
+    }
+}
+
+
+//------------ ExtendedError ------------------------------------------------
+
+/// An extended DNS error together with the rcode of its response.
+///
+/// Values of this type are returned by [`Message::extended_error()`] for
+/// diagnostic purposes. The rcode is the full extended rcode assembled
+/// from the message header and the OPT record.
+///
+/// When displayed, the value looks something like
+/// `SERVFAIL / EDE 6 (DNSSEC Bogus): signature expired`.
+///
+/// [`Message::extended_error()`]: ../../message/struct.Message.html#method.extended_error
+#[derive(Clone, Copy, Debug)]
+pub struct ExtendedError<'a> {
+    rcode: OptRcode,
+    ede: Ede<&'a [u8]>,
+}
+
+impl<'a> ExtendedError<'a> {
+    /// Creates a new value from the rcode and the EDE option.
+    pub fn new(rcode: OptRcode, ede: Ede<&'a [u8]>) -> Self {
+        ExtendedError { rcode: rcode, ede: ede }
+    }
+
+    /// Returns the rcode of the response.
+    pub fn rcode(&self) -> OptRcode {
+        self.rcode
+    }
+
+    /// Returns the EDE option.
+    pub fn ede(&self) -> &Ede<&'a [u8]> {
+        &self.ede
+    }
+}
+
+impl<'a> fmt::Display for ExtendedError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} / {}", self.rcode, self.ede)
+    }
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use ::bits::{ComposeMode, Composer, Parser};
+    use ::bits::opt::{OptData, ParsedOptData};
+    use ::iana::{ExtendedErrorCode, OptionCode};
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        Ede::new(ExtendedErrorCode::DnssecBogus, b"bad")
+            .compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(wire, b"\x00\x0f\x00\x05\x00\x06bad");
+
+        let mut parser = Parser::new(&wire);
+        let code = OptionCode::from(parser.parse_u16().unwrap());
+        let len = parser.parse_u16().unwrap() as usize;
+        parser.set_limit(len).unwrap();
+        let ede = Ede::parse(code, &mut parser).unwrap().unwrap();
+        assert_eq!(ede.code(), ExtendedErrorCode::DnssecBogus);
+        assert_eq!(ede.extra_text(), b"bad");
+        assert_eq!(parser.remaining(), 0);
+        assert_eq!(ede.to_string(), "EDE 6 (DNSSEC Bogus): bad");
+        assert_eq!(Ede::new(ExtendedErrorCode::Int(4711), b"").to_string(),
+                   "EDE 4711");
+    }
+}
@@ -11,6 +11,7 @@
 
 use std::{error, fmt, io};
 use byteorder::{BigEndian, ByteOrder};
+use ::iana::Rtype;
 
 
 //------------ Parser --------------------------------------------------------
@@ -51,13 +52,12 @@ impl<'a> Parser<'a> {
     /// If the limit would be beyond the end of the parser, returns
     /// `Err(ParseError::UnexpectedEnd)`.
     pub fn set_limit(&mut self, len: usize) -> ParseResult<()> {
-        let limit = self.pos + len;
-        if len > self.bytes.len() {
-            Err(ParseError::UnexpectedEnd)
-        }
-        else {
-            self.limit = limit;
-            Ok(())
+        match self.pos.checked_add(len) {
+            Some(limit) if limit <= self.bytes.len() => {
+                self.limit = limit;
+                Ok(())
+            }
+            _ => Err(ParseError::UnexpectedEnd)
         }
     }
 
@@ -127,10 +127,10 @@ impl<'a> Parser<'a> {
     /// length would take the parser beyond the current limit or the
     /// end of the message.
     pub fn parse_bytes(&mut self, len: usize) -> ParseResult<&'a [u8]> {
-        let end = self.pos + len;
-        if end > self.limit {
-            return Err(ParseError::UnexpectedEnd)
-        }
+        let end = match self.pos.checked_add(len) {
+            Some(end) if end <= self.limit => end,
+            _ => return Err(ParseError::UnexpectedEnd)
+        };
         let res = &self.bytes[self.pos..end];
         self.pos = end;
         Ok(res)
@@ -185,6 +185,18 @@ pub enum ParseError {
 
     /// A format error was encountered.
     FormErr,
+
+    /// The record data didn’t fill the record data length of a record.
+    ///
+    /// This happens if the parser for the record data of the given record
+    /// type stops before the end of the record data as given by the
+    /// record’s rdlength field, i.e., if the sender padded the record data.
+    /// If the record data is truncated, parsing results in `UnexpectedEnd`
+    /// instead.
+    RdlengthMismatch {
+        /// The record type of the offending record.
+        rtype: Rtype
+    },
 }
 
 impl error::Error for ParseError {
@@ -195,6 +207,7 @@ impl error::Error for ParseError {
             UnexpectedEnd => "unexpected end of data",
             UnknownLabel => "unknown label type in domain name",
             FormErr => "format error",
+            RdlengthMismatch { .. } => "record data length mismatch",
         }
     }
 }
@@ -207,7 +220,13 @@ impl From<ParseError> for io::Error {
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        error::Error::description(self).fmt(f)
+        match *self {
+            ParseError::RdlengthMismatch { rtype } => {
+                write!(f, "record data length mismatch for {} record",
+                       rtype)
+            }
+            _ => error::Error::description(self).fmt(f)
+        }
     }
 }
 
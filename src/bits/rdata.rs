@@ -19,15 +19,22 @@
 //! type that can deal with all record types but provides only a limited
 //! functionality.
 //!
+//! Record types that aren’t known at compile time can be handled at
+//! runtime by registering parsers for them with an [`RdataRegistry`].
+//!
 //! [`RecordData`]: trait.RecordData.html
 //! [`ParsedRecordData`]: trait.ParsedRecordData.html
 //! [`domain::rdata`]: ../../rdata/index.html
 //! [`GenericRecordData`]: struct.GenericRecordData.html
+//! [`RdataRegistry`]: struct.RdataRegistry.html
 
+use std::any::Any;
+use std::collections::HashMap;
 use std::fmt;
 use ::iana::Rtype;
 use ::rdata::fmt_rdata;
-use super::{Composer, ComposeResult, Parser, ParseResult};
+use super::{Composer, ComposeMode, ComposeResult, Parser, ParseError,
+            ParseResult};
 
 
 //----------- RecordData -----------------------------------------------------
@@ -42,6 +49,37 @@ pub trait RecordData: Sized {
 
     /// Appends the record data to the end of a composer.
     fn compose<C: AsMut<Composer>>(&self, target: C) -> ComposeResult<()>;
+
+    /// Appends the canonical form of the record data to a composer.
+    ///
+    /// The canonical form is defined in section 6.2 of [RFC 4034]. It
+    /// differs from the normal form only for those record types that
+    /// contain domain names which need to be uncompressed and in
+    /// lowercase. The default implementation therefore simply composes
+    /// the normal form. Types with embedded domain names override it.
+    ///
+    /// [RFC 4034]: https://tools.ietf.org/html/rfc4034
+    fn compose_canonical<C: AsMut<Composer>>(&self, target: C)
+                                             -> ComposeResult<()> {
+        self.compose(target)
+    }
+
+    /// Appends the canonical form of the record data to a vector.
+    ///
+    /// This is a convenience wrapper around [`compose_canonical()`] for
+    /// when the record data is needed on its own, such as when hashing
+    /// it for a signature. If composing fails, `buf` may contain part of
+    /// the record data.
+    ///
+    /// [`compose_canonical()`]: #method.compose_canonical
+    fn compose_canonical_vec(&self, buf: &mut Vec<u8>) -> ComposeResult<()> {
+        let vec = ::std::mem::replace(buf, Vec::new());
+        let mut composer = Composer::from_vec(vec, ComposeMode::Unlimited,
+                                              false);
+        let res = self.compose_canonical(&mut composer);
+        *buf = composer.finish();
+        res
+    }
 }
 
 
@@ -93,6 +131,47 @@ pub struct GenericRecordData<'a> {
 }
 
 impl<'a> GenericRecordData<'a> {
+    /// Returns the raw record data.
+    ///
+    /// Note that domain names embedded in the data may be compressed and
+    /// are then only meaningful within the message the data came from.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        let mut parser = self.parser.clone();
+        let len = parser.remaining();
+        parser.parse_bytes(len).unwrap()
+    }
+
+    /// Returns whether the record type may contain compressed names.
+    ///
+    /// These are the types defined in RFC 1035 that contain domain names.
+    fn may_be_compressed(&self) -> bool {
+        match self.rtype {
+            Rtype::Cname | Rtype::Mb | Rtype::Md | Rtype::Mf | Rtype::Mg |
+            Rtype::Minfo | Rtype::Mr | Rtype::Mx | Rtype::Ns | Rtype::Ptr |
+            Rtype::Soa => true,
+            _ => false
+        }
+    }
+
+    /// Returns whether two record data values are equal.
+    ///
+    /// For most record types, this simply compares the raw data without
+    /// parsing it. Only for the types that may contain compressed domain
+    /// names, the data is composed uncompressed before comparing. The
+    /// record types of the two values are not considered.
+    pub fn data_eq(&self, other: &Self) -> bool {
+        if !self.may_be_compressed() && !other.may_be_compressed() {
+            return self.as_bytes() == other.as_bytes()
+        }
+        let mut left = Composer::new(ComposeMode::Unlimited, false);
+        let mut right = Composer::new(ComposeMode::Unlimited, false);
+        if self.compose(&mut left).is_err() ||
+                other.compose(&mut right).is_err() {
+            return false
+        }
+        left.finish() == right.finish()
+    }
+
     /// Tries to re-parse the data for the given record data type.
     ///
     /// # Panics
@@ -136,6 +215,47 @@ impl<'a> RecordData for GenericRecordData<'a> {
             }
         }
     }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, target: C)
+                                             -> ComposeResult<()> {
+        use ::rdata::rfc1035::parsed::*;
+        use ::rdata::parsed::{Afsdb, Rt, Sig, Srv};
+
+        match self.rtype {
+            // All the types we know about that contain domain names and
+            // are listed in section 6.2 of RFC 4034.
+            Rtype::Cname => {
+                try!(self.reparse::<Cname>()).compose_canonical(target)
+            }
+            Rtype::Mb => try!(self.reparse::<Mb>()).compose_canonical(target),
+            Rtype::Md => try!(self.reparse::<Md>()).compose_canonical(target),
+            Rtype::Mf => try!(self.reparse::<Mf>()).compose_canonical(target),
+            Rtype::Mg => try!(self.reparse::<Mg>()).compose_canonical(target),
+            Rtype::Minfo => {
+                try!(self.reparse::<Minfo>()).compose_canonical(target)
+            }
+            Rtype::Mr => try!(self.reparse::<Mr>()).compose_canonical(target),
+            Rtype::Mx => try!(self.reparse::<Mx>()).compose_canonical(target),
+            Rtype::Ns => try!(self.reparse::<Ns>()).compose_canonical(target),
+            Rtype::Ptr => {
+                try!(self.reparse::<Ptr>()).compose_canonical(target)
+            }
+            Rtype::Soa => {
+                try!(self.reparse::<Soa>()).compose_canonical(target)
+            }
+            Rtype::Afsdb => {
+                try!(self.reparse::<Afsdb>()).compose_canonical(target)
+            }
+            Rtype::Rt => try!(self.reparse::<Rt>()).compose_canonical(target),
+            Rtype::Sig => {
+                try!(self.reparse::<Sig>()).compose_canonical(target)
+            }
+            Rtype::Srv => {
+                try!(self.reparse::<Srv>()).compose_canonical(target)
+            }
+            _ => self.compose(target)
+        }
+    }
 }
 
 impl<'a> ParsedRecordData<'a> for GenericRecordData<'a> {
@@ -157,3 +277,149 @@ impl<'a> fmt::Display for GenericRecordData<'a> {
     }
 }
 
+
+
+//------------ RdataRegistry -------------------------------------------------
+
+/// A parser registered with an [`RdataRegistry`].
+///
+/// [`RdataRegistry`]: struct.RdataRegistry.html
+pub type RdataParser = Box<dyn for<'a> Fn(&mut Parser<'a>)
+                                          -> ParseResult<Box<dyn Any>>
+                           + Send + Sync>;
+
+/// A collection of record data parsers registered at runtime.
+///
+/// Normally, record data types are picked at compile time through the
+/// [`ParsedRecordData`] trait. The registry allows adding parsers for
+/// record types while the program is running, e.g., by plugins. For each
+/// record type, one parser can be registered via [`register()`]. Records
+/// are then parsed into [`RegistryRecordData`] through [`parse()`] or, for
+/// a whole message, through [`Message::parse_with_registry()`].
+///
+/// [`ParsedRecordData`]: trait.ParsedRecordData.html
+/// [`RegistryRecordData`]: enum.RegistryRecordData.html
+/// [`register()`]: #method.register
+/// [`parse()`]: #method.parse
+/// [`Message::parse_with_registry()`]: ../message/struct.Message.html#method.parse_with_registry
+#[derive(Default)]
+pub struct RdataRegistry {
+    /// The parsers by record type.
+    parsers: HashMap<Rtype, RdataParser>,
+}
+
+impl RdataRegistry {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a parser for the given record type.
+    ///
+    /// The parser is handed a parser limited to the record data and has
+    /// to consume all of it. Its result is kept in the `Custom` variant of
+    /// [`RegistryRecordData`]. An earlier parser for the same record type
+    /// is replaced.
+    ///
+    /// [`RegistryRecordData`]: enum.RegistryRecordData.html
+    pub fn register<T, F>(&mut self, rtype: Rtype, parser: F)
+                    where T: Any,
+                          F: for<'a> Fn(&mut Parser<'a>) -> ParseResult<T>
+                             + Send + Sync + 'static {
+        self.parsers.insert(rtype, Box::new(move |data| {
+            parser(data).map(|data| Box::new(data) as Box<dyn Any>)
+        }));
+    }
+
+    /// Returns whether a parser has been registered for a record type.
+    pub fn is_registered(&self, rtype: Rtype) -> bool {
+        self.parsers.contains_key(&rtype)
+    }
+
+    /// Parses generic record data using the registered parsers.
+    ///
+    /// If there is no parser for the record type of `data`, it is returned
+    /// as `RegistryRecordData::Unknown`.
+    pub fn parse<'a>(&self, data: GenericRecordData<'a>)
+                     -> ParseResult<RegistryRecordData<'a>> {
+        let custom = match self.parsers.get(&data.rtype) {
+            Some(parser) => {
+                let mut rdata = data.parser.clone();
+                let custom = parser(&mut rdata)?;
+                if rdata.remaining() != 0 {
+                    return Err(ParseError::RdlengthMismatch {
+                        rtype: data.rtype
+                    })
+                }
+                custom
+            }
+            None => return Ok(RegistryRecordData::Unknown(data))
+        };
+        Ok(RegistryRecordData::Custom(custom, data))
+    }
+}
+
+impl fmt::Debug for RdataRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.parsers.keys()).finish()
+    }
+}
+
+
+//------------ RegistryRecordData --------------------------------------------
+
+/// Record data parsed with an [`RdataRegistry`].
+///
+/// The generic data is kept even for records parsed by a registered
+/// parser. It is used for composing and displaying the data.
+///
+/// [`RdataRegistry`]: struct.RdataRegistry.html
+#[derive(Debug)]
+pub enum RegistryRecordData<'a> {
+    /// The data was parsed by a registered parser.
+    Custom(Box<dyn Any>, GenericRecordData<'a>),
+
+    /// There is no parser for the record type.
+    Unknown(GenericRecordData<'a>),
+}
+
+impl<'a> RegistryRecordData<'a> {
+    /// Returns the result of the registered parser if it is of type `T`.
+    pub fn custom<T: Any>(&self) -> Option<&T> {
+        match *self {
+            RegistryRecordData::Custom(ref custom, _) => {
+                custom.downcast_ref()
+            }
+            RegistryRecordData::Unknown(_) => None
+        }
+    }
+
+    /// Returns the generic record data.
+    pub fn generic(&self) -> &GenericRecordData<'a> {
+        match *self {
+            RegistryRecordData::Custom(_, ref data) => data,
+            RegistryRecordData::Unknown(ref data) => data
+        }
+    }
+}
+
+impl<'a> RecordData for RegistryRecordData<'a> {
+    fn rtype(&self) -> Rtype {
+        self.generic().rtype()
+    }
+
+    fn compose<C: AsMut<Composer>>(&self, target: C) -> ComposeResult<()> {
+        self.generic().compose(target)
+    }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, target: C)
+                                             -> ComposeResult<()> {
+        self.generic().compose_canonical(target)
+    }
+}
+
+impl<'a> fmt::Display for RegistryRecordData<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.generic().fmt(f)
+    }
+}
@@ -9,7 +9,8 @@
 
 use std::fmt;
 use super::{Composer, ComposeError, ComposeResult, DName, GenericRecordData,
-            ParsedDName, ParsedRecordData, Parser, ParseResult, RecordData};
+            ParsedDName, ParsedRecordData, Parser, ParseError, ParseResult,
+            RecordData, RegistryRecordData};
 use ::iana::{Class, Rtype};
 
 
@@ -149,11 +150,15 @@ impl<'a, D: ParsedRecordData<'a>> Record<ParsedDName<'a>, D> {
         let class = try!(Class::parse(parser));
         let ttl = try!(parser.parse_u32());
         let rdlen = try!(parser.parse_u16()) as usize;
+        let end = parser.pos() + rdlen;
         try!(parser.set_limit(rdlen));
         let data = try!(D::parse(rtype, parser));
-        if data.is_none() {
-            try!(parser.skip(rdlen));
+        // If the record data was parsed, it has to be consumed entirely.
+        // Otherwise we explicitly move to its end.
+        if data.is_some() && parser.pos() != end {
+            return Err(ParseError::RdlengthMismatch { rtype: rtype })
         }
+        try!(parser.seek(end));
         parser.remove_limit();
         Ok(data.map(|data| Record::new(name, class, ttl, data)))
     }
@@ -165,6 +170,25 @@ impl<'a> Record<ParsedDName<'a>, GenericRecordData<'a>> {
         Self::parse(parser).map(Option::unwrap)
     }
 }
+
+
+/// # Comparing
+///
+impl<'a> Record<ParsedDName<'a>, GenericRecordData<'a>> {
+    /// Returns whether two records with generic data are equal.
+    ///
+    /// The records are equal if their names, ignoring the case of ASCII
+    /// letters, their record types, and their classes are equal and their
+    /// record data is equal as determined by
+    /// [`GenericRecordData::data_eq()`]. This avoids parsing the record data
+    /// for most record types. The TTLs are not considered.
+    ///
+    /// [`GenericRecordData::data_eq()`]: ../rdata/struct.GenericRecordData.html#method.data_eq
+    pub fn rdata_eq(&self, other: &Self) -> bool {
+        self.rtype() == other.rtype() && self.class == other.class
+            && self.name == other.name && self.data.data_eq(&other.data)
+    }
+}
     
 
 /// # Composing
@@ -215,6 +239,15 @@ impl<N, D> fmt::Display for Record<N, D>
 pub type GenericRecord<'a> = Record<ParsedDName<'a>, GenericRecordData<'a>>;
 
 
+//------------ RegistryRecord ------------------------------------------------
+
+/// A record with data parsed through an [`RdataRegistry`].
+///
+/// [`RdataRegistry`]: ../rdata/struct.RdataRegistry.html
+pub type RegistryRecord<'a> = Record<ParsedDName<'a>,
+                                     RegistryRecordData<'a>>;
+
+
 //------------ RecordBuilder -------------------------------------------------
 
 /// A type for building records in place.
@@ -260,3 +293,54 @@ impl<C: AsMut<Composer>> AsMut<Composer> for RecordBuilder<C> {
         self.composer.as_mut()
     }
 }
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use ::bits::Parser;
+    use super::*;
+
+    fn record(bytes: &[u8]) -> GenericRecord {
+        GenericRecord::parse_generic(&mut Parser::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn rdata_eq() {
+        let one = record(b"\x03www\x07example\x03com\x00\x00\x01\x00\x01\
+                           \x00\x00\x0e\x10\x00\x04\xc0\x00\x02\x01");
+        let two = record(b"\x03WWW\x07example\x03com\x00\x00\x01\x00\x01\
+                           \x00\x00\x01\x2c\x00\x04\xc0\x00\x02\x01");
+        let three = record(b"\x03www\x07example\x03com\x00\x00\x01\x00\x01\
+                             \x00\x00\x0e\x10\x00\x04\xc0\x00\x02\x02");
+        let four = record(b"\x03www\x07example\x03org\x00\x00\x01\x00\x01\
+                            \x00\x00\x0e\x10\x00\x04\xc0\x00\x02\x01");
+        assert!(one.rdata_eq(&two));
+        assert!(!one.rdata_eq(&three));
+        assert!(!one.rdata_eq(&four));
+    }
+
+    #[test]
+    fn rdlength_mismatch() {
+        use ::bits::ParseError;
+        use ::rdata::parsed::Mx;
+
+        // An MX record with a trailing octet after the exchange name.
+        let bytes = b"\x07example\x03com\x00\x00\x0f\x00\x01\
+                      \x00\x00\x0e\x10\x00\x0a\x00\x0a\
+                      \x04mail\xc0\x00\x00";
+        assert_eq!(Record::<_, Mx>::parse(&mut Parser::new(bytes)).err(),
+                   Some(ParseError::RdlengthMismatch { rtype: Rtype::Mx }));
+
+        // Generic record data accepts it.
+        assert!(GenericRecord::parse(&mut Parser::new(bytes)).is_ok());
+
+        // Without the trailing octet, all is fine.
+        let bytes = b"\x07example\x03com\x00\x00\x0f\x00\x01\
+                      \x00\x00\x0e\x10\x00\x09\x00\x0a\
+                      \x04mail\xc0\x00";
+        assert!(Record::<_, Mx>::parse(&mut Parser::new(bytes)).unwrap()
+                                                               .is_some());
+    }
+}
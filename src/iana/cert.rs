@@ -0,0 +1,73 @@
+//! Certificate types.
+
+use ::master::{ScanResult, Scanner, SyntaxError};
+use ::bits::{Composer, ComposeResult, Parser, ParseResult};
+
+int_enum!{
+    /// Certificate types.
+    ///
+    /// These values are used in the CERT record to describe the format of
+    /// the certificate stored in the record.
+    ///
+    /// Certificate types are represented by a 16 bit value. The enum wraps
+    /// these values.
+    ///
+    /// See [RFC 4398] for the definition of the CERT record and the
+    /// [Certificate Types IANA registry] for an overview of assigned values.
+    ///
+    /// [RFC 4398]: https://tools.ietf.org/html/rfc4398
+    /// [Certificate Types IANA registry]: http://www.iana.org/assignments/cert-rr-types/cert-rr-types.xhtml
+    =>
+    CertType, u16;
+
+    /// X.509 as per PKIX.
+    (Pkix => 1, b"PKIX")
+
+    /// SPKI certificate.
+    (Spki => 2, b"SPKI")
+
+    /// OpenPGP packet.
+    (Pgp => 3, b"PGP")
+
+    /// The URL of an X.509 data object.
+    (Ipkix => 4, b"IPKIX")
+
+    /// The URL of an SPKI certificate.
+    (Ispki => 5, b"ISPKI")
+
+    /// The fingerprint and URL of an OpenPGP packet.
+    (Ipgp => 6, b"IPGP")
+
+    /// Attribute certificate.
+    (Acpkix => 7, b"ACPKIX")
+
+    /// The URL of an attribute certificate.
+    (Iacpkix => 8, b"IACPKIX")
+
+    /// URI private.
+    (Uri => 253, b"URI")
+
+    /// OID private.
+    (Oid => 254, b"OID")
+}
+
+int_enum_str_with_prefix!(CertType, "", b"", u16,
+                          "unknown certificate type");
+
+impl CertType {
+    pub fn parse(parser: &mut Parser) -> ParseResult<Self> {
+        parser.parse_u16().map(CertType::from)
+    }
+
+    pub fn compose<C: AsMut<Composer>>(&self, mut composer: C)
+                                       -> ComposeResult<()> {
+        composer.as_mut().compose_u16(self.into())
+    }
+
+    pub fn scan<S: Scanner>(scanner: &mut S) -> ScanResult<Self> {
+        scanner.scan_word(|slice| {
+            Self::from_bytes(slice)
+                 .ok_or_else(|| SyntaxError::UnknownCertType(slice.into()))
+        })
+    }
+}
@@ -49,14 +49,17 @@ int_enum!{
     /// require that an RRset does not exist prior to the update.",
     (None => 0xFE, b"NONE")
 
-    /// Query class * (ANY).
+    /// Query class ANY (or *).
     /// 
     /// This class can be used in a query to indicate that records for the
-    /// given name from any class are requested.",
-    (Any => 0xFF, b"*")
+    /// given name from any class are requested. While RFC 1035 uses `*`
+    /// for it, the mnemonic used by common tools is ANY. Both are
+    /// accepted when parsing.",
+    (Any => 0xFF, b"ANY")
 }
 
-int_enum_str_with_prefix!(Class, "CLASS", b"CLASS", u16, "unknown class");
+int_enum_str_with_prefix!(Class, "CLASS", b"CLASS", u16, "unknown class";
+                          b"*" => Any);
 
 impl Class {
     pub fn parse(parser: &mut Parser) -> ParseResult<Self> {
@@ -75,3 +78,29 @@ impl Class {
         })
     }
 }
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use ::master::bufscanner::BufScanner;
+    use super::*;
+
+    #[test]
+    fn any() {
+        assert_eq!(Class::Any.to_int(), 255);
+        assert_eq!(Class::Any.to_string(), "ANY");
+        assert_eq!(Class::from_str("any").unwrap(), Class::Any);
+        assert_eq!(Class::from_str("*").unwrap(), Class::Any);
+        assert_eq!(Class::from_bytes(b"*"), Some(Class::Any));
+        assert_eq!(Class::from_bytes(b"ANY"), Some(Class::Any));
+        assert_eq!(Class::from_int(255).to_string(), "ANY");
+        assert_eq!(Class::scan(&mut BufScanner::create(&b"* "[..])).unwrap(),
+                   Class::Any);
+        assert_eq!(Class::scan(&mut BufScanner::create(&b"ANY "[..]))
+                         .unwrap(),
+                   Class::Any);
+    }
+}
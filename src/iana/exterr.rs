@@ -0,0 +1,120 @@
+//! Extended DNS Error Codes
+
+use std::fmt;
+
+
+//------------ ExtendedErrorCode ---------------------------------------------
+
+int_enum!{
+    /// Extended DNS error codes.
+    ///
+    /// These values are used in the Extended DNS Error EDNS option to give
+    /// additional information about the cause of a DNS failure.
+    ///
+    /// Extended error codes are represented by a 16 bit value. The enum
+    /// wraps these values. The mnemonics are the purposes given for each
+    /// code in the registry.
+    ///
+    /// See [RFC 8914] for the definition of the option and the
+    /// [Extended DNS Error Codes IANA registry] for an overview of assigned
+    /// values.
+    ///
+    /// [RFC 8914]: https://tools.ietf.org/html/rfc8914
+    /// [Extended DNS Error Codes IANA registry]: https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#extended-dns-error-codes
+    =>
+    ExtendedErrorCode, u16;
+
+    /// The error does not match any of the other codes.
+    (Other => 0, b"Other Error")
+
+    /// A DNSKEY uses an unsupported algorithm.
+    (UnsupportedDnskeyAlgorithm => 1, b"Unsupported DNSKEY Algorithm")
+
+    /// A DS record uses an unsupported digest type.
+    (UnsupportedDsDigestType => 2, b"Unsupported DS Digest Type")
+
+    /// The answer was served from stale data.
+    (StaleAnswer => 3, b"Stale Answer")
+
+    /// The answer was forged by policy.
+    (ForgedAnswer => 4, b"Forged Answer")
+
+    /// The DNSSEC validation ended in the Indeterminate state.
+    (DnssecIndeterminate => 5, b"DNSSEC Indeterminate")
+
+    /// The DNSSEC validation ended in the Bogus state.
+    (DnssecBogus => 6, b"DNSSEC Bogus")
+
+    /// No valid signatures were found because they have expired.
+    (SignatureExpired => 7, b"Signature Expired")
+
+    /// No valid signatures were found because they are not valid yet.
+    (SignatureNotYetValid => 8, b"Signature Not Yet Valid")
+
+    /// No DNSKEY matching the DS records of the parent could be found.
+    (DnskeyMissing => 9, b"DNSKEY Missing")
+
+    /// No RRSIGs could be found for a signed zone.
+    (RrsigsMissing => 10, b"RRSIGs Missing")
+
+    /// No zone key bit was set in the DNSKEY records.
+    (NoZoneKeyBitSet => 11, b"No Zone Key Bit Set")
+
+    /// The requested data is missing and no NSEC record proves this.
+    (NsecMissing => 12, b"NSEC Missing")
+
+    /// The error response was cached.
+    (CachedError => 13, b"Cached Error")
+
+    /// The server is not ready to serve requests yet.
+    (NotReady => 14, b"Not Ready")
+
+    /// The domain is on a blocklist of the server operator.
+    (Blocked => 15, b"Blocked")
+
+    /// The domain is on a blocklist due to an external requirement.
+    (Censored => 16, b"Censored")
+
+    /// The domain is on a blocklist requested by the client.
+    (Filtered => 17, b"Filtered")
+
+    /// The client is not allowed to use the server.
+    (Prohibited => 18, b"Prohibited")
+
+    /// A stale NXDOMAIN answer was served.
+    (StaleNxdomainAnswer => 19, b"Stale NXDOMAIN Answer")
+
+    /// The server is not authoritative for the query.
+    (NotAuthoritative => 20, b"Not Authoritative")
+
+    /// The requested operation or query is not supported.
+    (NotSupported => 21, b"Not Supported")
+
+    /// No authoritative server could be reached.
+    (NoReachableAuthority => 22, b"No Reachable Authority")
+
+    /// A network error prevented resolution.
+    (NetworkError => 23, b"Network Error")
+
+    /// The zone data of an authoritative server is invalid.
+    (InvalidData => 24, b"Invalid Data")
+}
+
+
+//--- Display
+
+impl fmt::Display for ExtendedErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use std::fmt::Write;
+
+        match self.to_mnemonic() {
+            Some(m) => {
+                for ch in m {
+                    f.write_char(*ch as char)?
+                }
+                Ok(())
+            }
+            None => self.to_int().fmt(f)
+        }
+    }
+}
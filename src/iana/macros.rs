@@ -256,13 +256,27 @@ macro_rules! int_enum_str_with_decimal {
 ///
 /// For `Display`, values without mnemonic will be written starting with the
 /// prefix directly followed by the decimal representation of the value.
+///
+/// Optionally, a list of additional mnemonics and the variants they stand
+/// for can be given after a semicolon. These are accepted when parsing but
+/// never used for display.
 macro_rules! int_enum_str_with_prefix {
     ($ianatype:ident, $str_prefix:expr, $u8_prefix:expr, $inttype:ident,
      $error:expr) => {
+        int_enum_str_with_prefix!($ianatype, $str_prefix, $u8_prefix,
+                                  $inttype, $error; );
+    };
+    ($ianatype:ident, $str_prefix:expr, $u8_prefix:expr, $inttype:ident,
+     $error:expr; $($alias:expr => $aliasvariant:ident),*) => {
         impl $ianatype {
             pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
                 use std::ascii::AsciiExt;
 
+                $(
+                    if bytes.eq_ignore_ascii_case($alias) {
+                        return Some($ianatype::$aliasvariant)
+                    }
+                )*
                 $ianatype::from_mnemonic(bytes).or_else(|| {
                     if bytes.len() <= $u8_prefix.len() {
                         return None
@@ -286,6 +300,11 @@ macro_rules! int_enum_str_with_prefix {
             fn from_str(s: &str) -> Result<Self, Self::Err> {
                 use std::ascii::AsciiExt;
 
+                $(
+                    if s.as_bytes().eq_ignore_ascii_case($alias) {
+                        return Ok($ianatype::$aliasvariant)
+                    }
+                )*
                 // We assume all mnemonics are always ASCII, so using
                 // the bytes representation of `s` is safe.
                 match $ianatype::from_mnemonic(s.as_bytes()) {
@@ -25,7 +25,9 @@
 //! re-exported here. This is mostly so we can have associated types like
 //! `FromStrError` without having to resort to devilishly long names.
 
+pub use self::cert::CertType;
 pub use self::class::Class;
+pub use self::exterr::ExtendedErrorCode;
 pub use self::opcode::Opcode;
 pub use self::opt::OptionCode;
 pub use self::rcode::{Rcode, OptRcode, TsigRcode};
@@ -34,7 +36,9 @@ pub use self::secalg::SecAlg;
 
 #[macro_use] mod macros;
 
+pub mod cert;
 pub mod class;
+pub mod exterr;
 pub mod opcode;
 pub mod opt;
 pub mod rcode;
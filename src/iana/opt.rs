@@ -29,6 +29,7 @@ pub enum OptionCode {
     Padding,
     Chain,
     EdnsKeyTag,
+    ExtendedError,
 
     /// A raw class value given through its integer. 
     Int(u16),
@@ -53,6 +54,7 @@ impl OptionCode {
             12 => Padding,
             13 => Chain,
             14 => EdnsKeyTag,
+            15 => ExtendedError,
             _ => Int(value)
         }
     }
@@ -75,6 +77,7 @@ impl OptionCode {
             Padding => 12,
             Chain => 13,
             EdnsKeyTag => 14,
+            ExtendedError => 15,
             Int(v) => v
         }
     }
@@ -116,6 +119,7 @@ impl fmt::Display for OptionCode {
             Padding => "Padding".fmt(f),
             Chain => "CHAIN".fmt(f),
             EdnsKeyTag => "edns-key-tag".fmt(f),
+            ExtendedError => "Extended DNS Error".fmt(f),
             Int(value) => {
                 match OptionCode::from_int(value) {
                     Int(value) => value.fmt(f),
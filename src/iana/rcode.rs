@@ -9,6 +9,12 @@
 //! All of these codes share the same defition space. Even so, we have
 //! separate types for each of these.
 //!
+//! Worse still, the shared space isn’t quite shared: value 16 is BADVERS
+//! in the OPT record but BADSIG in TSIG and TKEY records. If you have a
+//! raw value and know where it came from, [interpret_rcode()] picks the
+//! right type for it.
+//!
+//! [interpret_rcode()]: fn.interpret_rcode.html
 //! [Rcode]: 
 //! [RFC 2671]: https://tools.ietf.org/html/rfc2671
 //! [RFC 2845]: https://tools.ietf.org/html/rfc2845
@@ -19,6 +25,7 @@
 use std::cmp;
 use std::fmt;
 use std::hash;
+use std::{iter, slice, str};
 
 
 //------------ Rcode --------------------------------------------------------
@@ -40,12 +47,35 @@ use std::hash;
 /// All three codes share the same name space. Their values are defined in
 /// one registry, [IANA DNS RCODEs].
 ///
+/// Since IANA keeps assigning new values, the type is marked as
+/// non-exhaustive and may gain new variants in any release. Code outside
+/// this crate has to add a wildcard arm when matching on it. Values without
+/// a variant of their own are represented by the `Int` variant and the
+/// stable way to create and inspect values is through [`from_int()`] and
+/// [`to_int()`]. For example:
+///
+/// ```
+/// use domain::iana::Rcode;
+///
+/// fn is_nxdomain(rcode: Rcode) -> bool {
+///     match rcode {
+///         Rcode::NXDomain => true,
+///         _ => false,
+///     }
+/// }
+///
+/// assert!(is_nxdomain(Rcode::from_int(3)));
+/// ```
+///
 /// [OptRcode]: enum.OptRcode.html
 /// [TsigRcode]: enum.TsigRcode.html
+/// [`from_int()`]: #method.from_int
+/// [`to_int()`]: #method.to_int
 /// [IANA DNS RCODEs]: http://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-6
 /// [RFC 1035]: https://tools.ietf.org/html/rfc1035
 /// [RFC 2671]: https://tools.ietf.org/html/rfc2671
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
 pub enum Rcode {
     /// No error condition.
     ///
@@ -54,6 +84,7 @@ pub enum Rcode {
     /// Defined in [RFC 1035].
     ///
     /// [RFC 1035]: https://tools.ietf.org/html/rfc1035
+    #[default]
     NoError,
 
     /// Format error.
@@ -161,20 +192,59 @@ pub enum Rcode {
     /// [RFC 2136]: https://tools.ietf.org/html/rfc2136
     NotZone,
 
+    /// DSO-TYPE not implemented.
+    ///
+    /// The responding server does not implement the DSO-TYPE of the
+    /// primary TLV in a DNS stateful operations request.
+    ///
+    /// Defined in [RFC 8490].
+    ///
+    /// [RFC 8490]: https://tools.ietf.org/html/rfc8490
+    DsoTypeNi,
+
     /// A raw, integer rcode value.
     ///
     /// When converting to an `u8`, only the lower four bits are used.
     Int(u8)
 }
 
+
+/// The description of all values without a variant of their own.
+const UNASSIGNED_DESCRIPTION: &str = "unassigned response code";
+
+/// All values of `Rcode` with a variant of their own in ascending order.
+const RCODES: &[Rcode] = &[
+    Rcode::NoError, Rcode::FormErr, Rcode::ServFail, Rcode::NXDomain,
+    Rcode::NotImp, Rcode::Refused, Rcode::YXDomain, Rcode::YXRRSet,
+    Rcode::NXRRSet, Rcode::NotAuth, Rcode::NotZone, Rcode::DsoTypeNi,
+];
+
 impl Rcode {
+    /// The largest integer value of an rcode.
+    ///
+    /// Rcodes are four bits wide.
+    pub const MAX_INT: u8 = 0x0F;
+
+    /// The integer values and mnemonics of all values with a variant.
+    ///
+    /// The pairs are given in ascending order of the integer value. The
+    /// mnemonics are those produced by the `Display` impl.
+    pub const ALL: &'static [(u8, &'static str)] = &[
+        (0, "NOERROR"), (1, "FORMERR"), (2, "SERVFAIL"), (3, "NXDOMAIN"),
+        (4, "NOTIMP"), (5, "REFUSED"), (6, "YXDOMAIN"), (7, "YXRRSET"),
+        (8, "NXRRSET"), (9, "NOAUTH"), (10, "NOTZONE"), (11, "DSOTYPENI"),
+    ];
+
     /// Creates an rcode from an integer.
     ///
-    /// Only the lower four bits of `value` are considered.
-    pub fn from_int(value: u8) -> Rcode {
+    /// Only the lower four bits of `value` are considered, i.e., the value
+    /// is masked with [`MAX_INT`].
+    ///
+    /// [`MAX_INT`]: #associatedconstant.MAX_INT
+    pub const fn from_int(value: u8) -> Rcode {
         use self::Rcode::*;
 
-        match value & 0x0F {
+        match value & Rcode::MAX_INT {
             0 => NoError,
             1 => FormErr,
             2 => ServFail,
@@ -186,12 +256,30 @@ impl Rcode {
             8 => NXRRSet,
             9 => NotAuth,
             10 => NotZone,
+            11 => DsoTypeNi,
             value => Int(value)
         }
     }
 
+    /// Creates an rcode from an integer, rejecting out-of-range values.
+    ///
+    /// Unlike [`from_int()`], this doesn’t mask `value` but returns an
+    /// error if it is larger than [`MAX_INT`], i.e., if it doesn’t fit
+    /// into four bits.
+    ///
+    /// [`from_int()`]: #method.from_int
+    /// [`MAX_INT`]: #associatedconstant.MAX_INT
+    pub fn try_from_int(value: u8) -> Result<Rcode, RangeError> {
+        if value > Rcode::MAX_INT {
+            Err(RangeError)
+        }
+        else {
+            Ok(Rcode::from_int(value))
+        }
+    }
+
     /// Returns the integer value for this rcode.
-    pub fn to_int(self) -> u8 {
+    pub const fn to_int(self) -> u8 {
         use self::Rcode::*;
 
         match self {
@@ -206,9 +294,160 @@ impl Rcode {
             NXRRSet => 8,
             NotAuth => 9,
             NotZone => 10,
-            Int(value) => value & 0x0F
+            DsoTypeNi => 11,
+            Int(value) => value & Rcode::MAX_INT
+        }
+    }
+
+    /// Returns the flags word of a message header with the rcode set.
+    ///
+    /// The rcode occupies the lowest four bits of the second 16 bit word
+    /// of the message header which also contains the flags and the opcode.
+    /// The function replaces these four bits of `flags` with the rcode and
+    /// leaves all other bits as they are.
+    pub const fn apply_to_flags(self, flags: u16) -> u16 {
+        (flags & 0xFFF0) | self.to_int() as u16
+    }
+
+    /// Creates an rcode from the flags word of a message header.
+    ///
+    /// This takes the lowest four bits of `flags`. See
+    /// [`apply_to_flags()`] for more details.
+    ///
+    /// [`apply_to_flags()`]: #method.apply_to_flags
+    pub const fn from_flags(flags: u16) -> Rcode {
+        Rcode::from_int((flags & 0x0F) as u8)
+    }
+
+    /// Returns a one-line human readable description of the rcode.
+    ///
+    /// For values without a variant of their own, this is the generic
+    /// text “unassigned response code.”
+    pub fn description(self) -> &'static str {
+        use self::Rcode::*;
+
+        match Rcode::from_int(self.to_int()) {
+            NoError => "No error condition",
+            FormErr => "The name server was unable to interpret the query",
+            ServFail => {
+                "The name server was unable to process the query due to a \
+                 problem with the name server"
+            }
+            NXDomain => {
+                "The domain name referenced in the query does not exist"
+            }
+            NotImp => {
+                "The name server does not support the requested kind of \
+                 query"
+            }
+            Refused => {
+                "The name server refuses to perform the operation for \
+                 policy reasons"
+            }
+            YXDomain => "A name exists when it should not",
+            YXRRSet => "An RRset exists when it should not",
+            NXRRSet => "An RRset that should exist does not",
+            NotAuth => {
+                "The server is not authoritative for the zone or the \
+                 client is not authorized"
+            }
+            NotZone => "A name is not contained in the zone",
+            DsoTypeNi => "The DSO-TYPE is not implemented",
+            Int(_) => UNASSIGNED_DESCRIPTION
+        }
+    }
+
+    /// Returns the RFCs defining the rcode.
+    ///
+    /// The RFCs are given in the form “RFC 1035.” If a value has been
+    /// defined by more than one RFC, they are given in ascending order.
+    /// For values without a variant of their own, the slice is empty.
+    pub fn defining_rfc(self) -> &'static [&'static str] {
+        use self::Rcode::*;
+
+        match Rcode::from_int(self.to_int()) {
+            NoError | FormErr | ServFail | NXDomain | NotImp | Refused => {
+                &["RFC 1035"]
+            }
+            YXDomain => &["RFC 2136", "RFC 6672"],
+            YXRRSet | NXRRSet | NotZone => &["RFC 2136"],
+            NotAuth => &["RFC 2136", "RFC 2845"],
+            DsoTypeNi => &["RFC 8490"],
+            Int(_) => &[]
+        }
+    }
+
+    /// Returns whether the value has been assigned by IANA.
+    ///
+    /// This is `false` for all values that don’t have a variant of their
+    /// own, i.e., those that are unassigned or reserved.
+    pub fn is_assigned(&self) -> bool {
+        match *self {
+            Rcode::Int(value) => {
+                match Rcode::from_int(value) {
+                    Rcode::Int(_) => false,
+                    _ => true
+                }
+            }
+            _ => true
         }
     }
+
+    /// Returns whether the rcode indicates success.
+    ///
+    /// This is only the case for `NoError` or its integer value zero.
+    pub fn is_success(self) -> bool {
+        self.to_int() == 0
+    }
+
+    /// Returns whether the rcode indicates an error.
+    ///
+    /// This is the case for all values except `NoError`.
+    pub fn is_error(self) -> bool {
+        !self.is_success()
+    }
+
+    /// Returns an iterator over all values with a variant of their own.
+    ///
+    /// The values are returned in ascending order of their integer value.
+    /// The `Int` variant is never returned.
+    pub fn iter() -> iter::Cloned<slice::Iter<'static, Rcode>> {
+        RCODES.iter().cloned()
+    }
+
+    /// Compares two rcodes for display purposes.
+    ///
+    /// Unlike the `Ord` impl which purely compares the integer values,
+    /// this orders all values with a variant of their own before all
+    /// other values. Within each of these two groups, values are ordered
+    /// by their integer value. This is useful for listing rcodes to humans
+    /// with the well-known ones first.
+    pub fn display_cmp(&self, other: &Rcode) -> cmp::Ordering {
+        (!self.is_assigned(), self.to_int())
+            .cmp(&(!other.is_assigned(), other.to_int()))
+    }
+
+    /// Parses the rcode from a status as printed by dig and similar tools.
+    ///
+    /// The string can be the mnemonic itself, the mnemonic prefixed with
+    /// `status:`, or an entire header line such as
+    /// `;; ->>HEADER<<- opcode: QUERY, status: NXDOMAIN, id: 4711`. If the
+    /// string contains `status:`, everything up to and including it is
+    /// skipped. The rcode is then taken from the remainder up to the next
+    /// comma or white space with any surrounding punctuation removed and
+    /// parsed via `FromStr`.
+    pub fn from_dig_status(s: &str) -> Result<Rcode, FromStrError> {
+        let s = match s.find("status:") {
+            Some(pos) => &s[pos + "status:".len()..],
+            None => s
+        };
+        let s = s.trim_start_matches(|ch: char| {
+            ch.is_whitespace() || ch.is_ascii_punctuation()
+        });
+        let end = s.find(|ch: char| ch == ',' || ch.is_whitespace())
+                   .unwrap_or(s.len());
+        s[..end].trim_matches(|ch: char| ch.is_ascii_punctuation()).parse()
+    }
 }
 
 
@@ -223,6 +462,43 @@ impl From<Rcode> for u8 {
 }
 
 
+//--- FromStr
+
+impl str::FromStr for Rcode {
+    type Err = FromStrError;
+
+    /// Returns the rcode for the given string.
+    ///
+    /// Recognized are the mnemonics produced by the `Display` impl not
+    /// regarding case as well as decimal integer numbers up to 15. Since
+    /// the mnemonic for `NotAuth` is a bit contentious, both NOAUTH and
+    /// NOTAUTH are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use self::Rcode::*;
+
+        if s.eq_ignore_ascii_case("NOERROR") { Ok(NoError) }
+        else if s.eq_ignore_ascii_case("FORMERR") { Ok(FormErr) }
+        else if s.eq_ignore_ascii_case("SERVFAIL") { Ok(ServFail) }
+        else if s.eq_ignore_ascii_case("NXDOMAIN") { Ok(NXDomain) }
+        else if s.eq_ignore_ascii_case("NOTIMP") { Ok(NotImp) }
+        else if s.eq_ignore_ascii_case("REFUSED") { Ok(Refused) }
+        else if s.eq_ignore_ascii_case("YXDOMAIN") { Ok(YXDomain) }
+        else if s.eq_ignore_ascii_case("YXRRSET") { Ok(YXRRSet) }
+        else if s.eq_ignore_ascii_case("NXRRSET") { Ok(NXRRSet) }
+        else if s.eq_ignore_ascii_case("NOAUTH") ||
+                s.eq_ignore_ascii_case("NOTAUTH") { Ok(NotAuth) }
+        else if s.eq_ignore_ascii_case("NOTZONE") { Ok(NotZone) }
+        else if s.eq_ignore_ascii_case("DSOTYPENI") { Ok(DsoTypeNi) }
+        else {
+            match u8::from_str(s) {
+                Ok(value) if value <= 0x0F => Ok(Rcode::from_int(value)),
+                _ => Err(FromStrError)
+            }
+        }
+    }
+}
+
+
 //--- Display
 
 impl fmt::Display for Rcode {
@@ -241,6 +517,10 @@ impl fmt::Display for Rcode {
             NXRRSet => "NXRRSET".fmt(f),
             NotAuth => "NOAUTH".fmt(f),
             NotZone => "NOTZONE".fmt(f),
+            DsoTypeNi => "DSOTYPENI".fmt(f),
+            // Values beyond the width of the type would be masked by
+            // from_int() and could then show up as some unrelated name.
+            Int(i) if i > Rcode::MAX_INT => i.fmt(f),
             Int(i) => {
                 match Rcode::from_int(i) {
                     Rcode::Int(i) => i.fmt(f),
@@ -329,16 +609,27 @@ impl hash::Hash for Rcode {
 /// Because of this, we decided to have separate types.
 ///
 /// The values for all three response code types are defined in
-/// the [IANA DNS RCODEs] registry.
+/// the [IANA DNS RCODEs] registry. Like [Rcode], the type is marked as
+/// non-exhaustive, so matching on it outside this crate requires a
+/// wildcard arm. Use [`from_int()`] and [`to_int()`] to convert between
+/// values and integers.
+///
+/// Values are compared, ordered, and hashed by their integer value, so
+/// `NoError` and `Int(0)` are equal just like for [Rcode]. They can also
+/// be compared to an [Rcode] directly. Thus, extended values such as
+/// `BadVers` never compare equal to any [Rcode].
 ///
 /// [Rcode]: enum.Rcode.html
 /// [`TsigRcode`]: enum.TsigRcode.html
+/// [`from_int()`]: #method.from_int
+/// [`to_int()`]: #method.to_int
 /// [IANA DNS RCODEs]: http://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-6
 /// [RFC 2671]: https://tools.ietf.org/html/rfc2671
 /// [RFC 2845]: https://tools.ietf.org/html/rfc2845
 /// [RFC 2930]: https://tools.ietf.org/html/rfc2930
 /// [RFC 6891]: https://tools.ietf.org/html/rfc6891
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
 pub enum OptRcode {
     /// No error condition.
     ///
@@ -347,6 +638,7 @@ pub enum OptRcode {
     /// Defined in [RFC 1035].
     ///
     /// [RFC 1035]: https://tools.ietf.org/html/rfc1035
+    #[default]
     NoError,
 
     /// Format error.
@@ -454,6 +746,16 @@ pub enum OptRcode {
     /// [RFC 2136]: https://tools.ietf.org/html/rfc2136
     NotZone,
 
+    /// DSO-TYPE not implemented.
+    ///
+    /// The responding server does not implement the DSO-TYPE of the
+    /// primary TLV in a DNS stateful operations request.
+    ///
+    /// Defined in [RFC 8490].
+    ///
+    /// [RFC 8490]: https://tools.ietf.org/html/rfc8490
+    DsoTypeNi,
+
     /// Bad OPT version.
     ///
     /// A name server does not implement the EDNS version requested in the
@@ -485,14 +787,43 @@ pub enum OptRcode {
 }
 
 
+
+/// All values of `OptRcode` with a variant of their own in ascending order.
+const OPT_RCODES: &[OptRcode] = &[
+    OptRcode::NoError, OptRcode::FormErr, OptRcode::ServFail,
+    OptRcode::NXDomain, OptRcode::NotImp, OptRcode::Refused,
+    OptRcode::YXDomain, OptRcode::YXRRSet, OptRcode::NXRRSet,
+    OptRcode::NotAuth, OptRcode::NotZone, OptRcode::DsoTypeNi,
+    OptRcode::BadVers, OptRcode::BadCookie,
+];
+
 impl OptRcode {
+    /// The largest integer value of an extended rcode.
+    ///
+    /// Extended rcodes are twelve bits wide.
+    pub const MAX_INT: u16 = 0x0FFF;
+
+    /// The integer values and mnemonics of all values with a variant.
+    ///
+    /// The pairs are given in ascending order of the integer value. The
+    /// mnemonics are those produced by the `Display` impl.
+    pub const ALL: &'static [(u16, &'static str)] = &[
+        (0, "NOERROR"), (1, "FORMERR"), (2, "SERVFAIL"), (3, "NXDOMAIN"),
+        (4, "NOTIMP"), (5, "REFUSED"), (6, "YXDOMAIN"), (7, "YXRRSET"),
+        (8, "NXRRSET"), (9, "NOAUTH"), (10, "NOTZONE"), (11, "DSOTYPENI"),
+        (16, "BADVER"), (23, "BADCOOKIE"),
+    ];
+
     /// Creates an rcode from an integer.
     ///
-    /// Only the lower twelve bits of `value` are considered.
-    pub fn from_int(value: u16) -> OptRcode {
+    /// Only the lower twelve bits of `value` are considered, i.e., the
+    /// value is masked with [`MAX_INT`].
+    ///
+    /// [`MAX_INT`]: #associatedconstant.MAX_INT
+    pub const fn from_int(value: u16) -> OptRcode {
         use self::OptRcode::*;
 
-        match value & 0x0FFF {
+        match value & OptRcode::MAX_INT {
             0 => NoError,
             1 => FormErr,
             2 => ServFail,
@@ -504,14 +835,32 @@ impl OptRcode {
             8 => NXRRSet,
             9 => NotAuth,
             10 => NotZone,
+            11 => DsoTypeNi,
             16 => BadVers,
             23 => BadCookie,
             value => Int(value)
         }
     }
 
+    /// Creates an rcode from an integer, rejecting out-of-range values.
+    ///
+    /// Unlike [`from_int()`], this doesn’t mask `value` but returns an
+    /// error if it is larger than [`MAX_INT`], i.e., if it doesn’t fit
+    /// into twelve bits.
+    ///
+    /// [`from_int()`]: #method.from_int
+    /// [`MAX_INT`]: #associatedconstant.MAX_INT
+    pub fn try_from_int(value: u16) -> Result<OptRcode, RangeError> {
+        if value > OptRcode::MAX_INT {
+            Err(RangeError)
+        }
+        else {
+            Ok(OptRcode::from_int(value))
+        }
+    }
+
     /// Returns the integer value for this rcode.
-    pub fn to_int(self) -> u16 {
+    pub const fn to_int(self) -> u16 {
         use self::OptRcode::*;
 
         match self {
@@ -526,26 +875,64 @@ impl OptRcode {
             NXRRSet => 8,
             NotAuth => 9,
             NotZone => 10,
+            DsoTypeNi => 11,
             BadVers => 16,
             BadCookie => 23,
-            Int(value) => value & 0x0F
+            Int(value) => value & OptRcode::MAX_INT
         }
     }
 
     /// Creates an extended rcode value from its parts.
+    ///
+    /// The lower four bits of the extended rcode are taken from `rcode`,
+    /// the upper eight bits are `ext`. Since [`Rcode::to_int()`] never
+    /// returns more than four bits, the two parts never overlap and
+    /// [`to_parts()`] returns them unchanged. The only exception is an
+    /// `Rcode::Int` holding a value that doesn’t fit into four bits which
+    /// is treated like its lower four bits everywhere. Use
+    /// [`checked_from_parts()`] to catch this case.
+    ///
+    /// [`Rcode::to_int()`]: enum.Rcode.html#method.to_int
+    /// [`to_parts()`]: #method.to_parts
+    /// [`checked_from_parts()`]: #method.checked_from_parts
     pub fn from_parts(rcode: Rcode, ext: u8) -> OptRcode {
-        if ext == 0 {
-            rcode.into()
-        }
-        else {
-            OptRcode::from_int((ext as u16) << 4 | (rcode.to_int() as u16))
+        OptRcode::from_int((ext as u16) << 4 | (rcode.to_int() as u16))
+    }
+
+    /// Creates an extended rcode value from its parts, checking the rcode.
+    ///
+    /// This behaves exactly like [`from_parts()`] but refuses to silently
+    /// drop bits from an `Rcode::Int` with a value that doesn’t fit into
+    /// four bits. If `rcode` is `Rcode::Int(value)` with `value` larger
+    /// than 15, an error is returned instead.
+    ///
+    /// [`from_parts()`]: #method.from_parts
+    pub fn checked_from_parts(rcode: Rcode, ext: u8)
+                              -> Result<OptRcode, RangeError> {
+        match rcode {
+            Rcode::Int(value) if value > 0x0F => Err(RangeError),
+            _ => Ok(OptRcode::from_parts(rcode, ext))
         }
     }
 
+    /// Creates an extended rcode value from its raw wire representation.
+    ///
+    /// The `header_flags` is the second flags octet of the message header,
+    /// i.e., the fourth octet of the message, of which the lower four bits
+    /// are the rcode. The `opt_ttl` is the TTL field of the OPT record
+    /// whose most significant octet contains the upper eight bits of the
+    /// extended rcode as described in section 6.1.3 of [RFC 6891].
+    ///
+    /// [RFC 6891]: https://tools.ietf.org/html/rfc6891
+    pub fn from_raw(header_flags: u8, opt_ttl: u32) -> OptRcode {
+        OptRcode::from_int(((opt_ttl >> 24) as u16) << 4
+                           | (header_flags & 0x0F) as u16)
+    }
+
     /// Returns the two parts of an extended rcode value.
     pub fn to_parts(self) -> (Rcode, u8) {
         let res = self.to_int();
-        (Rcode::from_int(res as u8), (res >> 8) as u8)
+        (Rcode::from_int(res as u8), (res >> 4) as u8)
     }
 
     /// Returns the rcode part of the extended rcode.
@@ -557,6 +944,191 @@ impl OptRcode {
     pub fn ext(&self) -> u8 {
         self.to_parts().1
     }
+
+    /// Creates an extended rcode value from its raw wire bits.
+    ///
+    /// The lower four bits of `header` are the rcode bits from the message
+    /// header while `ext` is the upper eight bits of the extended rcode.
+    /// See [`wire_header_bits()`] and [`wire_ext_bits()`] for where exactly
+    /// these are found on the wire. Any upper bits in `header` are ignored.
+    ///
+    /// [`wire_header_bits()`]: #method.wire_header_bits
+    /// [`wire_ext_bits()`]: #method.wire_ext_bits
+    pub const fn from_wire_bits(header: u8, ext: u8) -> OptRcode {
+        OptRcode::from_int((ext as u16) << 4 | (header & 0x0F) as u16)
+    }
+
+    /// Returns the bits of the extended rcode that go into the header.
+    ///
+    /// These are the lower four bits of the extended rcode. They are
+    /// placed into the lower four bits of the fourth octet of the message,
+    /// i.e., the RCODE field of the message header.
+    pub const fn wire_header_bits(self) -> u8 {
+        (self.to_int() & 0x0F) as u8
+    }
+
+    /// Returns the bits of the extended rcode that go into the OPT record.
+    ///
+    /// These are the upper eight bits of the extended rcode. They are
+    /// placed into the most significant octet of the TTL field of the OPT
+    /// record as described in section 6.1.3 of [RFC 6891].
+    ///
+    /// [RFC 6891]: https://tools.ietf.org/html/rfc6891
+    pub const fn wire_ext_bits(self) -> u8 {
+        (self.to_int() >> 4) as u8
+    }
+
+    /// Returns a one-line human readable description of the rcode.
+    ///
+    /// Values that fit into four bits have the same description as the
+    /// corresponding [`Rcode`]. For values without a variant of their own,
+    /// this is the generic text “unassigned response code.”
+    ///
+    /// [`Rcode`]: enum.Rcode.html
+    pub fn description(self) -> &'static str {
+        match OptRcode::from_int(self.to_int()) {
+            OptRcode::BadVers => {
+                "The name server does not implement the requested EDNS \
+                 version"
+            }
+            OptRcode::BadCookie => "Bad or missing server cookie",
+            rcode => {
+                if rcode.to_int() < 16 {
+                    Rcode::from_int(rcode.to_int() as u8).description()
+                }
+                else {
+                    UNASSIGNED_DESCRIPTION
+                }
+            }
+        }
+    }
+
+    /// Returns the RFCs defining the rcode.
+    ///
+    /// The RFCs are given in the form “RFC 1035.” If a value has been
+    /// defined by more than one RFC, they are given in ascending order.
+    /// For values without a variant of their own, the slice is empty.
+    pub fn defining_rfc(self) -> &'static [&'static str] {
+        match OptRcode::from_int(self.to_int()) {
+            OptRcode::BadVers => &["RFC 6891"],
+            OptRcode::BadCookie => &["RFC 7873"],
+            rcode => {
+                if rcode.to_int() < 16 {
+                    Rcode::from_int(rcode.to_int() as u8).defining_rfc()
+                }
+                else {
+                    &[]
+                }
+            }
+        }
+    }
+
+    /// Returns whether the value has been assigned by IANA.
+    ///
+    /// This is `false` for all values that don’t have a variant of their
+    /// own, i.e., those that are unassigned or reserved.
+    pub fn is_assigned(&self) -> bool {
+        match *self {
+            OptRcode::Int(value) => {
+                match OptRcode::from_int(value) {
+                    OptRcode::Int(_) => false,
+                    _ => true
+                }
+            }
+            _ => true
+        }
+    }
+
+    /// Returns whether the value is reserved for private use.
+    ///
+    /// The IANA registry reserves the values from 3841 to 4095 for
+    /// private use. A server may use these for its own purposes.
+    pub fn is_private_use(self) -> bool {
+        self.to_int() >= 3841
+    }
+
+    /// Returns whether the value is reserved in the IANA registry.
+    ///
+    /// Within the twelve bits of an extended rcode, the only reserved
+    /// values are those reserved for private use, so this is the same as
+    /// [`is_private_use()`].
+    ///
+    /// [`is_private_use()`]: #method.is_private_use
+    pub fn is_reserved(self) -> bool {
+        self.is_private_use()
+    }
+
+    /// Returns whether the rcode indicates success.
+    ///
+    /// This is only the case for `NoError` or its integer value zero.
+    pub fn is_success(self) -> bool {
+        self.to_int() == 0
+    }
+
+    /// Returns whether the rcode indicates an error.
+    ///
+    /// This is the case for all values except `NoError`.
+    pub fn is_error(self) -> bool {
+        !self.is_success()
+    }
+
+    /// Returns an iterator over all values with a variant of their own.
+    ///
+    /// The values are returned in ascending order of their integer value.
+    /// The `Int` variant is never returned.
+    pub fn iter() -> iter::Cloned<slice::Iter<'static, OptRcode>> {
+        OPT_RCODES.iter().cloned()
+    }
+
+    /// Compares two rcodes for display purposes.
+    ///
+    /// Unlike the `Ord` impl which purely compares the integer values,
+    /// this orders all values with a variant of their own before all
+    /// other values. Within each of these two groups, values are ordered
+    /// by their integer value. This is useful for listing rcodes to humans
+    /// with the well-known ones first.
+    pub fn display_cmp(&self, other: &OptRcode) -> cmp::Ordering {
+        (!self.is_assigned(), self.to_int())
+            .cmp(&(!other.is_assigned(), other.to_int()))
+    }
+
+    /// Returns the mechanism that defines the rcode.
+    ///
+    /// Values below 16 are header rcodes. All other values can only be
+    /// transmitted with the help of the OPT record and are therefore
+    /// EDNS extended rcodes.
+    pub fn origin(self) -> RcodeOrigin {
+        if self.to_int() < 16 { RcodeOrigin::Header }
+        else { RcodeOrigin::Edns }
+    }
+
+    /// Converts the value into a TSIG error keeping its integer value.
+    ///
+    /// Both types share the same integer space but don’t agree on its
+    /// meaning. Most importantly, value 16 is BADVERS as an extended
+    /// rcode but BADSIG as a TSIG error. The conversion keeps the integer,
+    /// so `OptRcode::BadVers` becomes `TsigRcode::BadSig`. This is also
+    /// what the `From` impl does.
+    pub fn to_tsig_rcode_lossy(self) -> TsigRcode {
+        TsigRcode::from_int(self.to_int())
+    }
+
+    /// Checks the EDNS version of a request.
+    ///
+    /// The `requested` version is the one given in the OPT record of the
+    /// request while `supported` is the highest version the server
+    /// implements. As per section 6.1.3 of [RFC 6891], a server has to
+    /// answer a request for a version it doesn’t implement with BADVERS,
+    /// in which case this function returns `Some(OptRcode::BadVers)`. The
+    /// OPT record of that response should then carry `supported` as its
+    /// version. If the version is fine, returns `None`.
+    ///
+    /// [RFC 6891]: https://tools.ietf.org/html/rfc6891
+    pub fn for_edns_version(requested: u8, supported: u8)
+                            -> Option<OptRcode> {
+        if requested > supported { Some(OptRcode::BadVers) }
+        else { None }
+    }
 }
 
 
@@ -593,8 +1165,12 @@ impl fmt::Display for OptRcode {
             NXRRSet => "NXRRSET".fmt(f),
             NotAuth => "NOAUTH".fmt(f),
             NotZone => "NOTZONE".fmt(f),
+            DsoTypeNi => "DSOTYPENI".fmt(f),
             BadVers => "BADVER".fmt(f),
             BadCookie => "BADCOOKIE".fmt(f),
+            // Values beyond the width of the type would be masked by
+            // from_int() and could then show up as some unrelated name.
+            Int(i) if i > OptRcode::MAX_INT => i.fmt(f),
             Int(i) => {
                 match OptRcode::from_int(i) {
                     Int(i) => i.fmt(f),
@@ -606,6 +1182,77 @@ impl fmt::Display for OptRcode {
 }
 
 
+//--- PartialEq and Eq
+
+impl cmp::PartialEq for OptRcode {
+    fn eq(&self, other: &OptRcode) -> bool {
+        self.to_int() == other.to_int()
+    }
+}
+
+impl cmp::PartialEq<u16> for OptRcode {
+    fn eq(&self, other: &u16) -> bool {
+        self.to_int() == *other
+    }
+}
+
+impl cmp::PartialEq<OptRcode> for u16 {
+    fn eq(&self, other: &OptRcode) -> bool {
+        *self == other.to_int()
+    }
+}
+
+impl cmp::PartialEq<Rcode> for OptRcode {
+    fn eq(&self, other: &Rcode) -> bool {
+        self.to_int() == u16::from(other.to_int())
+    }
+}
+
+impl cmp::PartialEq<OptRcode> for Rcode {
+    fn eq(&self, other: &OptRcode) -> bool {
+        u16::from(self.to_int()) == other.to_int()
+    }
+}
+
+impl cmp::Eq for OptRcode { }
+
+
+//--- PartialOrd and Ord
+
+impl cmp::PartialOrd for OptRcode {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.to_int().partial_cmp(&other.to_int())
+    }
+}
+
+impl cmp::PartialOrd<u16> for OptRcode {
+    fn partial_cmp(&self, other: &u16) -> Option<cmp::Ordering> {
+        self.to_int().partial_cmp(other)
+    }
+}
+
+impl cmp::PartialOrd<OptRcode> for u16 {
+    fn partial_cmp(&self, other: &OptRcode) -> Option<cmp::Ordering> {
+        self.partial_cmp(&other.to_int())
+    }
+}
+
+impl cmp::Ord for OptRcode {
+    fn cmp(&self, other: &OptRcode) -> cmp::Ordering {
+        self.to_int().cmp(&other.to_int())
+    }
+}
+
+
+//--- Hash
+
+impl hash::Hash for OptRcode {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.to_int().hash(state)
+    }
+}
+
+
 
 //------------ TsigRcode ----------------------------------------------------
 
@@ -619,12 +1266,24 @@ impl fmt::Display for OptRcode {
 /// types.
 ///
 /// The values for all three response code types are defined in
-/// the [IANA DNS RCODEs] registry.
+/// the [IANA DNS RCODEs] registry. Like [`Rcode`], the type is marked as
+/// non-exhaustive, so matching on it outside this crate requires a
+/// wildcard arm. Use [`from_int()`] and [`to_int()`] to convert between
+/// values and integers.
 ///
-/// [`Rcode?]: enum.Rcode.html
+/// Values are compared, ordered, and hashed by their integer value, so
+/// `NoError` and `Int(0)` are equal just like for [`Rcode`]. They can also
+/// be compared to an [`Rcode`] directly. Thus, values beyond the four bits
+/// of an [`Rcode`], such as `BadSig`, never compare equal to any
+/// [`Rcode`].
+///
+/// [`Rcode`]: enum.Rcode.html
 /// [`OptRcode`]: enum.OptRcode.html
+/// [`from_int()`]: #method.from_int
+/// [`to_int()`]: #method.to_int
 /// [IANA DNS RCODEs]: http://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-6
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
 pub enum TsigRcode {
     /// No error condition.
     ///
@@ -633,6 +1292,7 @@ pub enum TsigRcode {
     /// Defined in [RFC 1035].
     ///
     /// [RFC 1035]: https://tools.ietf.org/html/rfc1035
+    #[default]
     NoError,
 
     /// Format error.
@@ -740,6 +1400,16 @@ pub enum TsigRcode {
     /// [RFC 2136]: https://tools.ietf.org/html/rfc2136
     NotZone,
 
+    /// DSO-TYPE not implemented.
+    ///
+    /// The responding server does not implement the DSO-TYPE of the
+    /// primary TLV in a DNS stateful operations request.
+    ///
+    /// Defined in [RFC 8490].
+    ///
+    /// [RFC 8490]: https://tools.ietf.org/html/rfc8490
+    DsoTypeNi,
+
     /// TSIG signature failure.
     ///
     /// The TSIG signature fails to verify.
@@ -821,15 +1491,48 @@ pub enum TsigRcode {
 
     /// A raw, integer rcode value.
     ///
-    /// When converting to a 12 bit code, the upper four bits are simply
-    /// ignored.
+    /// TSIG error codes are a full sixteen bit value, so all bits are used.
     Int(u16)
 }
 
 
+
+/// All values of `TsigRcode` with a variant of their own in ascending order.
+const TSIG_RCODES: &[TsigRcode] = &[
+    TsigRcode::NoError, TsigRcode::FormErr, TsigRcode::ServFail,
+    TsigRcode::NXDomain, TsigRcode::NotImp, TsigRcode::Refused,
+    TsigRcode::YXDomain, TsigRcode::YXRRSet, TsigRcode::NXRRSet,
+    TsigRcode::NotAuth, TsigRcode::NotZone, TsigRcode::DsoTypeNi,
+    TsigRcode::BadSig, TsigRcode::BadKey, TsigRcode::BadTime,
+    TsigRcode::BadMode, TsigRcode::BadName, TsigRcode::BadAlg,
+    TsigRcode::BadTrunc, TsigRcode::BadCookie,
+];
+
 impl TsigRcode {
+    /// The largest integer value of a TSIG error.
+    ///
+    /// TSIG errors use the full sixteen bits.
+    pub const MAX_INT: u16 = 0xFFFF;
+
+    /// The integer values and mnemonics of all values with a variant.
+    ///
+    /// The pairs are given in ascending order of the integer value. The
+    /// mnemonics are those produced by the `Display` impl.
+    pub const ALL: &'static [(u16, &'static str)] = &[
+        (0, "NOERROR"), (1, "FORMERR"), (2, "SERVFAIL"), (3, "NXDOMAIN"),
+        (4, "NOTIMP"), (5, "REFUSED"), (6, "YXDOMAIN"), (7, "YXRRSET"),
+        (8, "NXRRSET"), (9, "NOAUTH"), (10, "NOTZONE"), (11, "DSOTYPENI"),
+        (16, "BADSIG"), (17, "BADKEY"), (18, "BADTIME"), (19, "BADMODE"),
+        (20, "BADNAME"), (21, "BADALG"), (22, "BADTRUNC"), (23, "BADCOOKIE"),
+    ];
+
     /// Creates an rcode from an integer.
-    pub fn from_int(value: u16) -> TsigRcode {
+    ///
+    /// All sixteen bits of `value` are considered, so there is no masking
+    /// with [`MAX_INT`] here.
+    ///
+    /// [`MAX_INT`]: #associatedconstant.MAX_INT
+    pub const fn from_int(value: u16) -> TsigRcode {
         use self::TsigRcode::*;
 
         match value {
@@ -844,6 +1547,7 @@ impl TsigRcode {
             8 => NXRRSet,
             9 => NotAuth,
             10 => NotZone,
+            11 => DsoTypeNi,
             16 => BadSig,
             17 => BadKey,
             18 => BadTime,
@@ -856,8 +1560,16 @@ impl TsigRcode {
         }
     }
 
+    /// Creates an rcode from an integer, rejecting out-of-range values.
+    ///
+    /// Since TSIG errors use the full sixteen bits, this never fails. It
+    /// only exists for symmetry with the other rcode types.
+    pub fn try_from_int(value: u16) -> Result<TsigRcode, RangeError> {
+        Ok(TsigRcode::from_int(value))
+    }
+
     /// Returns the integer value for this rcode.
-    pub fn to_int(self) -> u16 {
+    pub const fn to_int(self) -> u16 {
         use self::TsigRcode::*;
 
         match self {
@@ -872,6 +1584,7 @@ impl TsigRcode {
             NXRRSet => 8,
             NotAuth => 9,
             NotZone => 10,
+            DsoTypeNi => 11,
             BadSig => 16,
             BadKey => 17,
             BadTime => 18,
@@ -880,15 +1593,162 @@ impl TsigRcode {
             BadAlg => 21,
             BadTrunc => 22,
             BadCookie => 23,
-            Int(value) => value & 0x0F
+            Int(value) => value
         }
     }
-}
 
+    /// Returns a one-line human readable description of the rcode.
+    ///
+    /// Values that fit into four bits have the same description as the
+    /// corresponding [`Rcode`]. For values without a variant of their own,
+    /// this is the generic text “unassigned response code.”
+    ///
+    /// [`Rcode`]: enum.Rcode.html
+    pub fn description(self) -> &'static str {
+        use self::TsigRcode::*;
 
-//--- From
-
-impl From<u16> for TsigRcode {
+        match TsigRcode::from_int(self.to_int()) {
+            BadSig => "The TSIG signature fails to verify",
+            BadKey => {
+                "The server did not recognize the key used for the signature"
+            }
+            BadTime => "The signature is outside the server’s time window",
+            BadMode => "The TKEY mode is not supported by the server",
+            BadName => "Duplicate key name",
+            BadAlg => "The algorithm is not supported",
+            BadTrunc => "The TSIG MAC is too short for the local policy",
+            BadCookie => "Bad or missing server cookie",
+            rcode => {
+                if rcode.to_int() < 16 {
+                    Rcode::from_int(rcode.to_int() as u8).description()
+                }
+                else {
+                    UNASSIGNED_DESCRIPTION
+                }
+            }
+        }
+    }
+
+    /// Returns the RFCs defining the rcode.
+    ///
+    /// The RFCs are given in the form “RFC 1035.” If a value has been
+    /// defined by more than one RFC, they are given in ascending order.
+    /// For values without a variant of their own, the slice is empty.
+    pub fn defining_rfc(self) -> &'static [&'static str] {
+        use self::TsigRcode::*;
+
+        match TsigRcode::from_int(self.to_int()) {
+            BadSig | BadKey | BadTime => &["RFC 2845"],
+            BadMode | BadName | BadAlg => &["RFC 2930"],
+            BadTrunc => &["RFC 4635"],
+            BadCookie => &["RFC 7873"],
+            rcode => {
+                if rcode.to_int() < 16 {
+                    Rcode::from_int(rcode.to_int() as u8).defining_rfc()
+                }
+                else {
+                    &[]
+                }
+            }
+        }
+    }
+
+    /// Returns whether the value has been assigned by IANA.
+    ///
+    /// This is `false` for all values that don’t have a variant of their
+    /// own, i.e., those that are unassigned or reserved.
+    pub fn is_assigned(&self) -> bool {
+        match *self {
+            TsigRcode::Int(value) => {
+                match TsigRcode::from_int(value) {
+                    TsigRcode::Int(_) => false,
+                    _ => true
+                }
+            }
+            _ => true
+        }
+    }
+
+    /// Returns whether the value is reserved for private use.
+    ///
+    /// The IANA registry reserves the values from 3841 to 4095 for
+    /// private use. A server may use these for its own purposes.
+    pub fn is_private_use(self) -> bool {
+        self.to_int() >= 3841 && self.to_int() <= 4095
+    }
+
+    /// Returns whether the value is reserved in the IANA registry.
+    ///
+    /// This is the case for the values reserved for private use and for
+    /// 65535 which may only be assigned by Standards Action.
+    pub fn is_reserved(self) -> bool {
+        self.is_private_use() || self.to_int() == 0xFFFF
+    }
+
+    /// Returns whether the rcode indicates success.
+    ///
+    /// This is only the case for `NoError` or its integer value zero.
+    pub fn is_success(self) -> bool {
+        self.to_int() == 0
+    }
+
+    /// Returns whether the rcode indicates an error.
+    ///
+    /// This is the case for all values except `NoError`.
+    pub fn is_error(self) -> bool {
+        !self.is_success()
+    }
+
+    /// Returns an iterator over all values with a variant of their own.
+    ///
+    /// The values are returned in ascending order of their integer value.
+    /// The `Int` variant is never returned.
+    pub fn iter() -> iter::Cloned<slice::Iter<'static, TsigRcode>> {
+        TSIG_RCODES.iter().cloned()
+    }
+
+    /// Compares two rcodes for display purposes.
+    ///
+    /// Unlike the `Ord` impl which purely compares the integer values,
+    /// this orders all values with a variant of their own before all
+    /// other values. Within each of these two groups, values are ordered
+    /// by their integer value. This is useful for listing rcodes to humans
+    /// with the well-known ones first.
+    pub fn display_cmp(&self, other: &TsigRcode) -> cmp::Ordering {
+        (!self.is_assigned(), self.to_int())
+            .cmp(&(!other.is_assigned(), other.to_int()))
+    }
+
+    /// Returns the mechanism that defines the rcode.
+    ///
+    /// Values below 16 are header rcodes and BADCOOKIE is an EDNS
+    /// extended rcode. All other values are treated as TSIG or TKEY
+    /// errors.
+    pub fn origin(self) -> RcodeOrigin {
+        match self.to_int() {
+            0..=15 => RcodeOrigin::Header,
+            23 => RcodeOrigin::Edns,
+            _ => RcodeOrigin::TsigTkey
+        }
+    }
+
+    /// Converts the value into an extended rcode keeping its integer value.
+    ///
+    /// Both types share the same integer space but don’t agree on its
+    /// meaning. Most importantly, value 16 is BADSIG as a TSIG error but
+    /// BADVERS as an extended rcode. The conversion keeps the integer, so
+    /// `TsigRcode::BadSig` becomes `OptRcode::BadVers`. Since extended
+    /// rcodes only have twelve bits, the upper four bits of the value are
+    /// dropped.
+    pub fn to_opt_rcode_lossy(self) -> OptRcode {
+        OptRcode::from_int(self.to_int())
+    }
+}
+
+
+//--- From
+
+impl From<u16> for TsigRcode {
     fn from(value: u16) -> TsigRcode { TsigRcode::from_int(value) }
 }
 
@@ -902,9 +1762,15 @@ impl From<Rcode> for TsigRcode {
     }
 }
 
+/// Converts an extended rcode keeping its integer value.
+///
+/// This turns `OptRcode::BadVers` into `TsigRcode::BadSig`. See
+/// [`OptRcode::to_tsig_rcode_lossy()`] for details.
+///
+/// [`OptRcode::to_tsig_rcode_lossy()`]: enum.OptRcode.html#method.to_tsig_rcode_lossy
 impl From<OptRcode> for TsigRcode {
     fn from(value: OptRcode) -> TsigRcode {
-        TsigRcode::from_int(value.to_int())
+        value.to_tsig_rcode_lossy()
     }
 }
 
@@ -927,6 +1793,7 @@ impl fmt::Display for TsigRcode {
             NXRRSet => "NXRRSET".fmt(f),
             NotAuth => "NOAUTH".fmt(f),
             NotZone => "NOTZONE".fmt(f),
+            DsoTypeNi => "DSOTYPENI".fmt(f),
             BadSig => "BADSIG".fmt(f),
             BadKey => "BADKEY".fmt(f),
             BadTime => "BADTIME".fmt(f),
@@ -945,3 +1812,1183 @@ impl fmt::Display for TsigRcode {
     }
 }
 
+
+//--- PartialEq and Eq
+
+impl cmp::PartialEq for TsigRcode {
+    fn eq(&self, other: &TsigRcode) -> bool {
+        self.to_int() == other.to_int()
+    }
+}
+
+impl cmp::PartialEq<u16> for TsigRcode {
+    fn eq(&self, other: &u16) -> bool {
+        self.to_int() == *other
+    }
+}
+
+impl cmp::PartialEq<TsigRcode> for u16 {
+    fn eq(&self, other: &TsigRcode) -> bool {
+        *self == other.to_int()
+    }
+}
+
+impl cmp::PartialEq<Rcode> for TsigRcode {
+    fn eq(&self, other: &Rcode) -> bool {
+        self.to_int() == u16::from(other.to_int())
+    }
+}
+
+impl cmp::PartialEq<TsigRcode> for Rcode {
+    fn eq(&self, other: &TsigRcode) -> bool {
+        u16::from(self.to_int()) == other.to_int()
+    }
+}
+
+impl cmp::Eq for TsigRcode { }
+
+
+//--- PartialOrd and Ord
+
+impl cmp::PartialOrd for TsigRcode {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.to_int().partial_cmp(&other.to_int())
+    }
+}
+
+impl cmp::PartialOrd<u16> for TsigRcode {
+    fn partial_cmp(&self, other: &u16) -> Option<cmp::Ordering> {
+        self.to_int().partial_cmp(other)
+    }
+}
+
+impl cmp::PartialOrd<TsigRcode> for u16 {
+    fn partial_cmp(&self, other: &TsigRcode) -> Option<cmp::Ordering> {
+        self.partial_cmp(&other.to_int())
+    }
+}
+
+impl cmp::Ord for TsigRcode {
+    fn cmp(&self, other: &TsigRcode) -> cmp::Ordering {
+        self.to_int().cmp(&other.to_int())
+    }
+}
+
+
+//--- Hash
+
+impl hash::Hash for TsigRcode {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.to_int().hash(state)
+    }
+}
+
+
+from_str_error!("unknown rcode");
+
+
+//------------ RangeError ----------------------------------------------------
+
+/// An integer was too large for an rcode type.
+///
+/// This error is returned by the `try_from_int()` functions of the rcode
+/// types and by [`OptRcode::checked_from_parts()`].
+///
+/// [`OptRcode::checked_from_parts()`]: enum.OptRcode.html#method.checked_from_parts
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RangeError;
+
+impl ::std::error::Error for RangeError {
+    fn description(&self) -> &str {
+        "rcode value out of range"
+    }
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "rcode value out of range".fmt(f)
+    }
+}
+
+
+//------------ RcodeValue ----------------------------------------------------
+
+/// A trait common to all three rcode types.
+///
+/// This allows writing code that is generic over [`Rcode`], [`OptRcode`],
+/// and [`TsigRcode`]. The methods behave exactly like the inherent methods
+/// of the same name.
+///
+/// [`Rcode`]: enum.Rcode.html
+/// [`OptRcode`]: enum.OptRcode.html
+/// [`TsigRcode`]: enum.TsigRcode.html
+pub trait RcodeValue: Copy + fmt::Display + Sized {
+    /// The integer type for the raw value of the rcode.
+    type Int: Copy;
+
+    /// Creates an rcode from an integer, masking it if necessary.
+    fn from_int(value: Self::Int) -> Self;
+
+    /// Returns the integer value of the rcode.
+    fn to_int(self) -> Self::Int;
+
+    /// Returns whether the rcode signals an error.
+    fn is_error(self) -> bool;
+}
+
+/// Implements `RcodeValue` for an rcode type.
+macro_rules! rcode_value {
+    ($rcode:ident, $inttype:ident) => {
+        impl RcodeValue for $rcode {
+            type Int = $inttype;
+
+            fn from_int(value: $inttype) -> Self {
+                $rcode::from_int(value)
+            }
+
+            fn to_int(self) -> $inttype {
+                $rcode::to_int(self)
+            }
+
+            fn is_error(self) -> bool {
+                $rcode::is_error(self)
+            }
+        }
+    }
+}
+
+rcode_value!(Rcode, u8);
+rcode_value!(OptRcode, u16);
+rcode_value!(TsigRcode, u16);
+
+
+//------------ RcodeContext and InterpretedRcode ----------------------------
+
+/// The place a raw rcode value was taken from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RcodeContext {
+    /// The four bit rcode field of the message header.
+    Header,
+
+    /// The twelve bit extended rcode assembled with the help of the OPT
+    /// record.
+    Opt,
+
+    /// The sixteen bit error field of a TSIG or TKEY record.
+    Tsig,
+}
+
+/// A raw rcode value interpreted according to its context.
+///
+/// Values of this type are created by [`interpret_rcode()`].
+///
+/// [`interpret_rcode()`]: fn.interpret_rcode.html
+#[derive(Clone, Copy, Debug)]
+pub enum InterpretedRcode {
+    /// A value from the message header.
+    Header(Rcode),
+
+    /// An extended rcode.
+    Opt(OptRcode),
+
+    /// A TSIG or TKEY error.
+    Tsig(TsigRcode),
+}
+
+impl InterpretedRcode {
+    /// Returns the context the value was interpreted in.
+    pub fn context(&self) -> RcodeContext {
+        match *self {
+            InterpretedRcode::Header(_) => RcodeContext::Header,
+            InterpretedRcode::Opt(_) => RcodeContext::Opt,
+            InterpretedRcode::Tsig(_) => RcodeContext::Tsig,
+        }
+    }
+
+    /// Returns the integer value.
+    pub fn to_int(&self) -> u16 {
+        match *self {
+            InterpretedRcode::Header(rcode) => u16::from(rcode.to_int()),
+            InterpretedRcode::Opt(rcode) => rcode.to_int(),
+            InterpretedRcode::Tsig(rcode) => rcode.to_int(),
+        }
+    }
+}
+
+impl fmt::Display for InterpretedRcode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InterpretedRcode::Header(rcode) => rcode.fmt(f),
+            InterpretedRcode::Opt(rcode) => rcode.fmt(f),
+            InterpretedRcode::Tsig(rcode) => rcode.fmt(f),
+        }
+    }
+}
+
+/// Interprets a raw rcode value taken from the given context.
+///
+/// The value is converted into the rcode type for `context`. Like the
+/// `from_int()` functions of these types, only the lower four bits are
+/// considered for `RcodeContext::Header` and only the lower twelve bits
+/// for `RcodeContext::Opt`.
+pub fn interpret_rcode(value: u16, context: RcodeContext)
+                       -> InterpretedRcode {
+    match context {
+        RcodeContext::Header => {
+            InterpretedRcode::Header(Rcode::from_int((value & 0x0F) as u8))
+        }
+        RcodeContext::Opt => {
+            InterpretedRcode::Opt(OptRcode::from_int(value))
+        }
+        RcodeContext::Tsig => {
+            InterpretedRcode::Tsig(TsigRcode::from_int(value))
+        }
+    }
+}
+
+
+//------------ RcodeOrigin ---------------------------------------------------
+
+/// The mechanism that defines an rcode value.
+///
+/// Values of this type are returned by [`OptRcode::origin()`] and
+/// [`TsigRcode::origin()`].
+///
+/// [`OptRcode::origin()`]: enum.OptRcode.html#method.origin
+/// [`TsigRcode::origin()`]: enum.TsigRcode.html#method.origin
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RcodeOrigin {
+    /// A value that fits into the rcode field of the message header.
+    Header,
+
+    /// An extended rcode that requires the OPT record of EDNS.
+    Edns,
+
+    /// An error that only occurs in TSIG or TKEY records.
+    TsigTkey,
+}
+
+
+//------------ Value Spaces --------------------------------------------------
+
+/// Returns an iterator over all raw values of an [`Rcode`].
+///
+/// The iterator covers the full range of a `u8` so that, together with
+/// [`Rcode::from_int()`], tests can be driven over every value a caller
+/// could possibly pass in. Since only the lower four bits are considered,
+/// each rcode will appear multiple times.
+///
+/// [`Rcode`]: enum.Rcode.html
+/// [`Rcode::from_int()`]: enum.Rcode.html#method.from_int
+pub fn all_values() -> impl Iterator<Item=u8> {
+    0..=0xFF
+}
+
+/// Returns an iterator over all raw values of an [`OptRcode`].
+///
+/// Extended rcodes are twelve bits long, so the iterator covers the
+/// values from 0 to 0x0FFF.
+///
+/// [`OptRcode`]: enum.OptRcode.html
+pub fn all_opt_values() -> impl Iterator<Item=u16> {
+    0..=0x0FFF
+}
+
+/// Returns an iterator over all raw values of a [`TsigRcode`].
+///
+/// TSIG error codes use the full sixteen bits, so this iterator covers
+/// all values of a `u16`.
+///
+/// [`TsigRcode`]: enum.TsigRcode.html
+pub fn all_tsig_values() -> impl Iterator<Item=u16> {
+    0..=0xFFFF
+}
+
+
+//------------ Mnemonics -----------------------------------------------------
+
+/// Implements a `mnemonic()` method for an rcode type.
+///
+/// The method returns the mnemonic `Display` produces for the value or
+/// `None` if the value is displayed as an integer. Just like with
+/// `Display`, values beyond the width of the type have no mnemonic even if
+/// their lower bits do.
+macro_rules! rcode_mnemonic {
+    ($rcode:ident) => {
+        impl $rcode {
+            fn mnemonic(self) -> Option<&'static str> {
+                let value = match self {
+                    $rcode::Int(i) if $rcode::try_from_int(i).is_err() => {
+                        return None
+                    }
+                    rcode => rcode.to_int()
+                };
+                $rcode::ALL.iter().find(|&&(int, _)| int == value)
+                           .map(|&(_, mnemonic)| mnemonic)
+            }
+        }
+    }
+}
+
+rcode_mnemonic!(Rcode);
+rcode_mnemonic!(OptRcode);
+rcode_mnemonic!(TsigRcode);
+
+
+//------------ TryFrom<&str> -------------------------------------------------
+
+/// Implements a strict conversion from a string for an rcode type.
+///
+/// Unlike `FromStr`, this only accepts the exact mnemonic produced by
+/// `Display` for one of the values with a variant of their own. Differently
+/// cased mnemonics, alternative spellings, and numbers are all rejected.
+macro_rules! rcode_try_from_str {
+    ($rcode:ident) => {
+        impl<'a> ::std::convert::TryFrom<&'a str> for $rcode {
+            type Error = FromStrError;
+
+            fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+                $rcode::ALL.iter().find(|&&(_, mnemonic)| mnemonic == s)
+                           .map(|&(value, _)| $rcode::from_int(value))
+                           .ok_or(FromStrError)
+            }
+        }
+    }
+}
+
+rcode_try_from_str!(Rcode);
+rcode_try_from_str!(OptRcode);
+rcode_try_from_str!(TsigRcode);
+
+
+//------------ Lowercase -----------------------------------------------------
+
+/// A wrapper for displaying an rcode in lowercase.
+///
+/// The `Display` impls of the rcode types produce the mnemonics in
+/// uppercase. If wrapped in this type, the mnemonic is displayed in
+/// lowercase instead, e.g., `nxdomain`. Values without a mnemonic are
+/// displayed as their integer value just like with the rcode types
+/// themselves.
+///
+/// `Display` is implemented for [`Rcode`], [`OptRcode`], and
+/// [`TsigRcode`].
+///
+/// [`Rcode`]: enum.Rcode.html
+/// [`OptRcode`]: enum.OptRcode.html
+/// [`TsigRcode`]: enum.TsigRcode.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Lowercase<R>(pub R);
+
+/// Implements `Display` for `Lowercase` wrapping an rcode type.
+macro_rules! lowercase_display {
+    ($rcode:ident) => {
+        impl fmt::Display for Lowercase<$rcode> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                match self.0.mnemonic() {
+                    Some(mnemonic) => {
+                        let mut buf = [0u8; 16];
+                        let buf = &mut buf[..mnemonic.len()];
+                        buf.copy_from_slice(mnemonic.as_bytes());
+                        buf.make_ascii_lowercase();
+                        f.pad(try!(str::from_utf8(buf)
+                                       .map_err(|_| fmt::Error)))
+                    }
+                    None => self.0.fmt(f)
+                }
+            }
+        }
+    }
+}
+
+lowercase_display!(Rcode);
+lowercase_display!(OptRcode);
+lowercase_display!(TsigRcode);
+
+
+//------------ Serialize and Deserialize -------------------------------------
+
+/// Implements `Serialize` and `Deserialize` for an rcode type.
+///
+/// Values with a mnemonic are serialized as the mnemonic string produced
+/// by `Display`, all other values as integers. Deserialization accepts
+/// both forms with mnemonics ignoring case and integers up to `$max`.
+/// Integers may also be given as strings of decimal digits.
+#[cfg(feature = "serde")]
+macro_rules! rcode_serde {
+    ($rcode:ident, $inttype:ident, $serialize:ident, $max:expr) => {
+        impl ::serde::Serialize for $rcode {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                         where S: ::serde::Serializer {
+                match self.mnemonic() {
+                    Some(mnemonic) => serializer.serialize_str(mnemonic),
+                    None => serializer.$serialize(self.to_int())
+                }
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $rcode {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                           where D: ::serde::Deserializer<'de> {
+                use ::serde::de::{Error, Unexpected, Visitor};
+
+                struct RcodeVisitor;
+
+                impl<'de> Visitor<'de> for RcodeVisitor {
+                    type Value = $rcode;
+
+                    fn expecting(&self, f: &mut fmt::Formatter)
+                                 -> fmt::Result {
+                        f.write_str("an rcode mnemonic or integer")
+                    }
+
+                    fn visit_str<E: Error>(self, value: &str)
+                                           -> Result<$rcode, E> {
+                        let found = $rcode::ALL.iter().find(|&&(_, name)| {
+                            name.eq_ignore_ascii_case(value)
+                        });
+                        if let Some(&(int, _)) = found {
+                            return Ok($rcode::from_int(int))
+                        }
+                        match value.parse::<u64>() {
+                            Ok(int) => self.visit_u64(int),
+                            Err(_) => {
+                                Err(E::invalid_value(Unexpected::Str(value),
+                                                     &self))
+                            }
+                        }
+                    }
+
+                    fn visit_i64<E: Error>(self, value: i64)
+                                           -> Result<$rcode, E> {
+                        if value < 0 {
+                            Err(E::invalid_value(Unexpected::Signed(value),
+                                                 &self))
+                        }
+                        else {
+                            self.visit_u64(value as u64)
+                        }
+                    }
+
+                    fn visit_u64<E: Error>(self, value: u64)
+                                           -> Result<$rcode, E> {
+                        if value > $max {
+                            Err(E::invalid_value(Unexpected::Unsigned(value),
+                                                 &self))
+                        }
+                        else {
+                            Ok($rcode::from_int(value as $inttype))
+                        }
+                    }
+                }
+
+                deserializer.deserialize_any(RcodeVisitor)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+rcode_serde!(Rcode, u8, serialize_u8, 0x0F);
+
+#[cfg(feature = "serde")]
+rcode_serde!(OptRcode, u16, serialize_u16, 0x0FFF);
+
+#[cfg(feature = "serde")]
+rcode_serde!(TsigRcode, u16, serialize_u16, 0xFFFF);
+
+
+//------------ Arbitrary -----------------------------------------------------
+
+/// Implements `Arbitrary` for an rcode type.
+///
+/// Half of the generated values are picked from the values with a variant
+/// of their own, the other half are random integers across the full width
+/// of the type.
+#[cfg(feature = "arbitrary")]
+macro_rules! rcode_arbitrary {
+    ($rcode:ident, $values:ident) => {
+        impl<'a> ::arbitrary::Arbitrary<'a> for $rcode {
+            fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>)
+                         -> ::arbitrary::Result<Self> {
+                if u.arbitrary()? {
+                    u.choose($values).map(|rcode| *rcode)
+                }
+                else {
+                    u.int_in_range(0..=$rcode::MAX_INT).map($rcode::from_int)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+rcode_arbitrary!(Rcode, RCODES);
+
+#[cfg(feature = "arbitrary")]
+rcode_arbitrary!(OptRcode, OPT_RCODES);
+
+#[cfg(feature = "arbitrary")]
+rcode_arbitrary!(TsigRcode, TSIG_RCODES);
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST: Rcode = Rcode::from_int(3);
+    const TEST_INT: u8 = Rcode::NXDomain.to_int();
+    const OPT_TEST: OptRcode = OptRcode::from_int(16);
+    const OPT_TEST_INT: u16 = OptRcode::BadCookie.to_int();
+    const TSIG_TEST: TsigRcode = TsigRcode::from_int(18);
+    const TSIG_TEST_INT: u16 = TsigRcode::BadSig.to_int();
+
+    #[test]
+    fn all_values_cover_variants() {
+        // For each type: the values of all its variants, all raw values,
+        // and the expected number of raw values.
+        let cases: Vec<(Vec<u16>, Vec<u16>, usize)> = vec![
+            (Rcode::iter().map(|rcode| u16::from(rcode.to_int())).collect(),
+             all_values().map(u16::from).collect(), 0x100),
+            (OptRcode::iter().map(|rcode| rcode.to_int()).collect(),
+             all_opt_values().collect(), 0x1000),
+            (TsigRcode::iter().map(|rcode| rcode.to_int()).collect(),
+             all_tsig_values().collect(), 0x10000),
+        ];
+        for (variants, values, count) in cases {
+            for value in variants {
+                assert!(values.contains(&value));
+            }
+            assert_eq!(values.len(), count);
+        }
+    }
+
+    #[test]
+    fn const_conversions() {
+        assert_eq!(TEST, Rcode::NXDomain);
+        assert_eq!(TEST_INT, 3);
+        assert!(OPT_TEST.is_assigned());
+        assert_eq!(OPT_TEST.to_int(), 16);
+        assert_eq!(OPT_TEST_INT, 23);
+        assert_eq!(TSIG_TEST.to_int(), 18);
+        assert_eq!(TSIG_TEST_INT, 16);
+    }
+
+    #[test]
+    fn try_from_int() {
+        assert_eq!(Rcode::try_from_int(3), Ok(Rcode::NXDomain));
+        assert_eq!(Rcode::try_from_int(15), Ok(Rcode::Int(15)));
+        assert_eq!(Rcode::try_from_int(0x13), Err(RangeError));
+        assert_eq!(OptRcode::try_from_int(16), Ok(OptRcode::BadVers));
+        assert_eq!(OptRcode::try_from_int(0x0FFF), Ok(OptRcode::Int(0x0FFF)));
+        assert_eq!(OptRcode::try_from_int(0x1003), Err(RangeError));
+        assert_eq!(TsigRcode::try_from_int(0xFFFF), Ok(TsigRcode::Int(0xFFFF)));
+    }
+
+    #[test]
+    fn rcode_value_trait() {
+        fn round_trip<R: RcodeValue>(rcode: R) -> (String, bool) {
+            let rcode = R::from_int(rcode.to_int());
+            (rcode.to_string(), rcode.is_error())
+        }
+
+        assert_eq!(round_trip(Rcode::NXDomain), ("NXDOMAIN".into(), true));
+        assert_eq!(round_trip(OptRcode::NoError), ("NOERROR".into(), false));
+        assert_eq!(round_trip(TsigRcode::BadSig), ("BADSIG".into(), true));
+        assert_eq!(<OptRcode as RcodeValue>::from_int(0x1010),
+                   OptRcode::BadVers);
+    }
+
+    #[test]
+    fn all_consts() {
+        assert_eq!(Rcode::ALL.len(), Rcode::iter().count());
+        for (&(value, name), rcode) in Rcode::ALL.iter().zip(Rcode::iter()) {
+            assert_eq!((value, name), (rcode.to_int(), &*rcode.to_string()));
+        }
+        assert_eq!(OptRcode::ALL.len(), OptRcode::iter().count());
+        for (&(value, name), rcode) in OptRcode::ALL.iter()
+                                                 .zip(OptRcode::iter()) {
+            assert_eq!((value, name), (rcode.to_int(), &*rcode.to_string()));
+        }
+        assert_eq!(TsigRcode::ALL.len(), TsigRcode::iter().count());
+        for (&(value, name), rcode) in TsigRcode::ALL.iter()
+                                                  .zip(TsigRcode::iter()) {
+            assert_eq!((value, name), (rcode.to_int(), &*rcode.to_string()));
+        }
+    }
+
+    #[test]
+    fn is_assigned() {
+        assert!(Rcode::NXDomain.is_assigned());
+        assert!(Rcode::from_int(3).is_assigned());
+        assert!(Rcode::Int(3).is_assigned());
+        assert!(Rcode::from_int(11).is_assigned());
+        assert!(!Rcode::from_int(12).is_assigned());
+        assert!(OptRcode::from_int(16).is_assigned());
+        assert!(!OptRcode::from_int(12).is_assigned());
+        assert!(!OptRcode::from_int(17).is_assigned());
+        assert!(TsigRcode::from_int(17).is_assigned());
+        assert!(!TsigRcode::from_int(24).is_assigned());
+    }
+
+    #[test]
+    fn is_success() {
+        assert!(Rcode::NoError.is_success());
+        assert!(Rcode::Int(0).is_success());
+        assert!(Rcode::NXDomain.is_error());
+        assert!(Rcode::Int(14).is_error());
+        assert!(OptRcode::Int(0).is_success());
+        assert!(OptRcode::BadVers.is_error());
+        assert!(OptRcode::Int(0x100).is_error());
+        assert!(TsigRcode::NoError.is_success());
+        assert!(TsigRcode::Int(0x1000).is_error());
+    }
+
+    #[test]
+    fn rcode_int_round_trip() {
+        for x in 0..=0xFFu8 {
+            assert_eq!(Rcode::from_int(x).to_int(), x & Rcode::MAX_INT);
+            assert!(Rcode::from_int(x).to_int() <= Rcode::MAX_INT);
+        }
+    }
+
+    #[test]
+    fn iter() {
+        let values: Vec<_> = Rcode::iter().map(Rcode::to_int).collect();
+        let assigned: Vec<_> = (0..16).filter(|&x| {
+            Rcode::from_int(x).is_assigned()
+        }).collect();
+        assert_eq!(values, assigned);
+
+        let values: Vec<_> = OptRcode::iter().map(OptRcode::to_int)
+                                     .collect();
+        let assigned: Vec<_> = (0..0x1000).filter(|&x| {
+            OptRcode::from_int(x).is_assigned()
+        }).collect();
+        assert_eq!(values, assigned);
+
+        let values: Vec<_> = TsigRcode::iter().map(TsigRcode::to_int)
+                                      .collect();
+        let assigned: Vec<_> = (0..0xFFFF).filter(|&x| {
+            TsigRcode::from_int(x).is_assigned()
+        }).collect();
+        assert_eq!(values, assigned);
+        assert!(TsigRcode::iter().all(|rcode| {
+            match rcode {
+                TsigRcode::Int(_) => false,
+                _ => true
+            }
+        }));
+    }
+
+    #[test]
+    fn dso_type_ni() {
+        assert_eq!(Rcode::from_int(11).to_string(), "DSOTYPENI");
+        assert_eq!(Rcode::DsoTypeNi.to_int(), 11);
+        assert_eq!(OptRcode::from_int(11).to_string(), "DSOTYPENI");
+        assert_eq!(OptRcode::DsoTypeNi.to_int(), 11);
+        assert_eq!(TsigRcode::from_int(11).to_string(), "DSOTYPENI");
+        assert_eq!(TsigRcode::DsoTypeNi.to_int(), 11);
+
+        // The neighbouring values keep their mappings.
+        assert_eq!(Rcode::from_int(10).to_string(), "NOTZONE");
+        assert_eq!(Rcode::from_int(12).to_string(), "12");
+        assert_eq!(OptRcode::from_int(16).to_string(), "BADVER");
+        assert_eq!(TsigRcode::from_int(16).to_string(), "BADSIG");
+    }
+
+    #[test]
+    fn lowercase() {
+        assert_eq!(Lowercase(Rcode::NXDomain).to_string(), "nxdomain");
+        assert_eq!(Lowercase(Rcode::Int(12)).to_string(), "12");
+        assert_eq!(Rcode::NXDomain.to_string(), "NXDOMAIN");
+        assert_eq!(Lowercase(OptRcode::BadCookie).to_string(), "badcookie");
+        assert_eq!(Lowercase(OptRcode::Int(0x0FFF)).to_string(), "4095");
+        assert_eq!(Lowercase(TsigRcode::BadSig).to_string(), "badsig");
+        assert_eq!(format!("{:>10}", Lowercase(Rcode::ServFail)),
+                   "  servfail");
+        assert_eq!(Lowercase(Rcode::Int(0x13)).to_string(), "19");
+        assert_eq!(Lowercase(OptRcode::Int(0x1010)).to_string(), "4112");
+        assert_eq!(Lowercase(TsigRcode::Int(16)).to_string(), "badsig");
+    }
+
+    #[test]
+    fn interpret() {
+        let value = interpret_rcode(16, RcodeContext::Opt);
+        match value {
+            InterpretedRcode::Opt(OptRcode::BadVers) => { }
+            _ => panic!("wrong value {:?}", value)
+        }
+        assert_eq!(value.to_string(), "BADVER");
+
+        let value = interpret_rcode(16, RcodeContext::Tsig);
+        match value {
+            InterpretedRcode::Tsig(TsigRcode::BadSig) => { }
+            _ => panic!("wrong value {:?}", value)
+        }
+        assert_eq!(value.to_string(), "BADSIG");
+
+        let value = interpret_rcode(0x13, RcodeContext::Header);
+        assert_eq!(value.context(), RcodeContext::Header);
+        assert_eq!(value.to_int(), 3);
+        assert_eq!(value.to_string(), "NXDOMAIN");
+    }
+
+    #[test]
+    fn origin() {
+        assert_eq!(OptRcode::NXDomain.origin(), RcodeOrigin::Header);
+        assert_eq!(OptRcode::BadVers.origin(), RcodeOrigin::Edns);
+        assert_eq!(OptRcode::BadCookie.origin(), RcodeOrigin::Edns);
+        assert_eq!(OptRcode::Int(0x0FFF).origin(), RcodeOrigin::Edns);
+        assert_eq!(TsigRcode::NotAuth.origin(), RcodeOrigin::Header);
+        assert_eq!(TsigRcode::BadSig.origin(), RcodeOrigin::TsigTkey);
+        assert_eq!(TsigRcode::BadTrunc.origin(), RcodeOrigin::TsigTkey);
+        assert_eq!(TsigRcode::BadCookie.origin(), RcodeOrigin::Edns);
+    }
+
+    #[test]
+    fn lossy_conversions() {
+        match OptRcode::BadVers.to_tsig_rcode_lossy() {
+            TsigRcode::BadSig => { }
+            value => panic!("BADVERS became {}", value)
+        }
+        match TsigRcode::from(OptRcode::BadVers) {
+            TsigRcode::BadSig => { }
+            value => panic!("BADVERS became {}", value)
+        }
+        match TsigRcode::BadSig.to_opt_rcode_lossy() {
+            OptRcode::BadVers => { }
+            value => panic!("BADSIG became {}", value)
+        }
+        match OptRcode::BadCookie.to_tsig_rcode_lossy() {
+            TsigRcode::BadCookie => { }
+            value => panic!("BADCOOKIE became {}", value)
+        }
+        assert_eq!(TsigRcode::Int(0x1010).to_opt_rcode_lossy().to_int(),
+                   0x010);
+    }
+
+    #[test]
+    fn from_conversions() {
+        for rcode in Rcode::iter().chain((0..=Rcode::MAX_INT).map(Rcode::Int)) {
+            let opt = OptRcode::from(rcode);
+            assert_eq!(opt.rcode(), rcode);
+            assert_eq!(opt.ext(), 0);
+            assert_eq!(opt.to_string(), rcode.to_string());
+            let tsig = TsigRcode::from(rcode);
+            assert_eq!(tsig.to_int(), rcode.to_int() as u16);
+            assert_eq!(tsig.to_string(), rcode.to_string());
+            assert_eq!(TsigRcode::from(opt), tsig);
+            assert_eq!(u8::from(rcode), rcode.to_int());
+            assert_eq!(Rcode::from(u8::from(rcode)), rcode);
+        }
+
+        // An Int with bits beyond the header width loses them.
+        assert_eq!(OptRcode::from(Rcode::Int(0x13)), OptRcode::NXDomain);
+        assert_eq!(TsigRcode::from(Rcode::Int(0x13)), TsigRcode::NXDomain);
+
+        // Extended rcodes keep their value when converted to TSIG errors.
+        for rcode in OptRcode::iter().filter(|rcode| *rcode >= 16) {
+            assert_eq!(TsigRcode::from(rcode).to_int(), rcode.to_int());
+            assert_eq!(OptRcode::from(u16::from(rcode)), rcode);
+        }
+        assert_eq!(TsigRcode::from(OptRcode::BadCookie),
+                   TsigRcode::BadCookie);
+        assert_eq!(TsigRcode::from(OptRcode::Int(0x0FFF)).to_int(), 0x0FFF);
+        for rcode in TsigRcode::iter() {
+            assert_eq!(TsigRcode::from(u16::from(rcode)), rcode);
+        }
+    }
+
+    #[test]
+    fn eq_and_hash() {
+        use std::collections::HashSet;
+
+        assert_eq!(OptRcode::NoError, OptRcode::Int(0));
+        assert_eq!(OptRcode::BadVers, 16);
+        assert!(OptRcode::Refused < OptRcode::BadVers);
+        assert!(OptRcode::Int(17) > OptRcode::BadVers);
+        let set: HashSet<_> = vec![OptRcode::BadCookie, OptRcode::Int(23)]
+                                  .into_iter().collect();
+        assert_eq!(set.len(), 1);
+
+        assert_eq!(TsigRcode::NoError, TsigRcode::Int(0));
+        assert_eq!(TsigRcode::BadSig, 16);
+        assert!(TsigRcode::BadSig < TsigRcode::BadKey);
+        let set: HashSet<_> = vec![TsigRcode::BadTime, TsigRcode::Int(18)]
+                                  .into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn display_int() {
+        assert_eq!(format!("{}", Rcode::Int(3)), "NXDOMAIN");
+        assert_eq!(format!("{}", Rcode::Int(12)), "12");
+        assert_eq!(format!("{}", Rcode::Int(0x13)), "19");
+        assert_eq!(format!("{}", Rcode::Int(0xF0)), "240");
+        assert_eq!(format!("{}", OptRcode::Int(16)), "BADVER");
+        assert_eq!(format!("{}", OptRcode::Int(0x1003)), "4099");
+        assert_eq!(format!("{}", OptRcode::Int(0x1010)), "4112");
+        assert_eq!(format!("{}", TsigRcode::Int(16)), "BADSIG");
+        assert_eq!(format!("{}", TsigRcode::Int(0x1003)), "4099");
+    }
+
+    #[test]
+    fn defining_rfc() {
+        assert_eq!(Rcode::NoError.defining_rfc(), &["RFC 1035"]);
+        assert_eq!(Rcode::YXDomain.defining_rfc(), &["RFC 2136", "RFC 6672"]);
+        assert!(Rcode::Int(12).defining_rfc().is_empty());
+        assert_eq!(OptRcode::NotAuth.defining_rfc(),
+                   Rcode::NotAuth.defining_rfc());
+        assert_eq!(OptRcode::BadVers.defining_rfc(), &["RFC 6891"]);
+        assert!(OptRcode::Int(17).defining_rfc().is_empty());
+        assert_eq!(TsigRcode::BadSig.defining_rfc(), &["RFC 2845"]);
+        assert_eq!(TsigRcode::BadTrunc.defining_rfc(), &["RFC 4635"]);
+        assert_eq!(TsigRcode::Int(11).defining_rfc(), &["RFC 8490"]);
+        for rcode in TsigRcode::iter() {
+            assert!(!rcode.defining_rfc().is_empty());
+        }
+    }
+
+    #[test]
+    fn display_cmp() {
+        let mut rcodes = vec![Rcode::Int(13), Rcode::NotZone, Rcode::Int(12),
+                              Rcode::NoError];
+        rcodes.sort_by(Rcode::display_cmp);
+        assert_eq!(rcodes, [Rcode::NoError, Rcode::NotZone, Rcode::Int(12),
+                            Rcode::Int(13)]);
+        rcodes.sort();
+        assert_eq!(rcodes, [Rcode::NoError, Rcode::NotZone, Rcode::Int(12),
+                            Rcode::Int(13)]);
+
+        let mut rcodes = vec![OptRcode::Int(17), OptRcode::BadCookie,
+                              OptRcode::Int(12), OptRcode::BadVers];
+        rcodes.sort_by(OptRcode::display_cmp);
+        assert_eq!(rcodes, [OptRcode::BadVers, OptRcode::BadCookie,
+                            OptRcode::Int(12), OptRcode::Int(17)]);
+        rcodes.sort();
+        assert_eq!(rcodes, [OptRcode::Int(12), OptRcode::BadVers,
+                            OptRcode::Int(17), OptRcode::BadCookie]);
+
+        assert_eq!(TsigRcode::Int(24).display_cmp(&TsigRcode::BadSig),
+                   cmp::Ordering::Greater);
+        assert_eq!(TsigRcode::Int(16).display_cmp(&TsigRcode::BadSig),
+                   cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn description() {
+        assert_eq!(Rcode::NXDomain.description(),
+                   "The domain name referenced in the query does not exist");
+        assert_eq!(Rcode::Int(3).description(),
+                   Rcode::NXDomain.description());
+        assert_eq!(Rcode::Int(12).description(), "unassigned response code");
+        assert_eq!(OptRcode::NXDomain.description(),
+                   Rcode::NXDomain.description());
+        assert_eq!(OptRcode::Int(0x0FFF).description(),
+                   "unassigned response code");
+        assert_ne!(OptRcode::BadVers.description(),
+                   TsigRcode::BadSig.description());
+        assert_eq!(TsigRcode::Int(17).description(),
+                   TsigRcode::BadKey.description());
+        for rcode in Rcode::iter() {
+            assert_ne!(rcode.description(), "unassigned response code");
+        }
+        for rcode in OptRcode::iter() {
+            assert_ne!(rcode.description(), "unassigned response code");
+        }
+        for rcode in TsigRcode::iter() {
+            assert_ne!(rcode.description(), "unassigned response code");
+        }
+    }
+
+    #[test]
+    fn flags() {
+        for flags in 0..=0xFFFFu16 {
+            for rcode in 0..16 {
+                let rcode = Rcode::from_int(rcode);
+                let applied = rcode.apply_to_flags(flags);
+                assert_eq!(applied & 0xFFF0, flags & 0xFFF0);
+                assert_eq!(Rcode::from_flags(applied), rcode);
+            }
+        }
+        assert_eq!(Rcode::NXDomain.apply_to_flags(0x8180), 0x8183);
+        assert_eq!(Rcode::Int(0x13).apply_to_flags(0x818F), 0x8183);
+    }
+
+    #[test]
+    fn reserved() {
+        assert!(!OptRcode::Int(3840).is_private_use());
+        assert!(OptRcode::Int(3841).is_private_use());
+        assert!(OptRcode::Int(4095).is_private_use());
+        assert!(OptRcode::Int(4095).is_reserved());
+        assert!(!OptRcode::BadCookie.is_reserved());
+        assert!(!OptRcode::Int(100).is_reserved());
+
+        assert!(!TsigRcode::Int(3840).is_private_use());
+        assert!(TsigRcode::Int(3841).is_private_use());
+        assert!(TsigRcode::Int(4095).is_reserved());
+        assert!(!TsigRcode::Int(4096).is_private_use());
+        assert!(!TsigRcode::Int(4096).is_reserved());
+        assert!(!TsigRcode::Int(0xFFFF).is_private_use());
+        assert!(TsigRcode::Int(0xFFFF).is_reserved());
+        assert!(!TsigRcode::BadSig.is_reserved());
+    }
+
+    #[test]
+    fn default() {
+        #[derive(Default)]
+        struct Rcodes {
+            rcode: Rcode,
+            opt: OptRcode,
+            tsig: TsigRcode,
+        }
+
+        let rcodes = Rcodes::default();
+        assert_eq!(rcodes.rcode, Rcode::NoError);
+        assert_eq!(rcodes.opt, OptRcode::NoError);
+        assert_eq!(rcodes.tsig, TsigRcode::NoError);
+    }
+
+    #[test]
+    fn rcode_from_str() {
+        use std::str::FromStr;
+
+        for value in 0..12 {
+            let rcode = Rcode::from_int(value);
+            assert_eq!(Rcode::from_str(&rcode.to_string()).unwrap(), rcode);
+            assert_eq!(Rcode::from_str(&rcode.to_string().to_lowercase())
+                             .unwrap(),
+                       rcode);
+        }
+        assert_eq!("NXDOMAIN".parse::<Rcode>().unwrap(), Rcode::NXDomain);
+        assert_eq!("NotAuth".parse::<Rcode>().unwrap(), Rcode::NotAuth);
+        assert_eq!("5".parse::<Rcode>().unwrap(), Rcode::Refused);
+        assert_eq!("14".parse::<Rcode>().unwrap(), Rcode::Int(14));
+        assert_eq!("15".parse::<Rcode>().unwrap(), Rcode::Int(15));
+        assert!("16".parse::<Rcode>().is_err());
+        assert!("19".parse::<Rcode>().is_err());
+        assert!("NXDOMAINX".parse::<Rcode>().is_err());
+        assert!("".parse::<Rcode>().is_err());
+    }
+
+    #[test]
+    fn from_dig_status() {
+        assert_eq!(Rcode::from_dig_status("NOERROR").unwrap(), Rcode::NoError);
+        assert_eq!(Rcode::from_dig_status("  status: nxdomain\n").unwrap(),
+                   Rcode::NXDomain);
+        assert_eq!(Rcode::from_dig_status("status: SERVFAIL,").unwrap(),
+                   Rcode::ServFail);
+        assert_eq!(Rcode::from_dig_status("(REFUSED)").unwrap(),
+                   Rcode::Refused);
+        assert_eq!(
+            Rcode::from_dig_status(
+                ";; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 4711"
+            ).unwrap(),
+            Rcode::NoError
+        );
+        assert!(Rcode::from_dig_status("status:").is_err());
+        assert!(Rcode::from_dig_status("status: BOGUS,").is_err());
+    }
+
+    #[test]
+    fn try_from_str() {
+        use std::convert::TryFrom;
+
+        for rcode in Rcode::iter() {
+            assert_eq!(Rcode::try_from(rcode.to_string().as_str()).unwrap(),
+                       rcode);
+        }
+        for rcode in OptRcode::iter() {
+            assert_eq!(OptRcode::try_from(rcode.to_string().as_str())
+                                .unwrap().to_int(),
+                       rcode.to_int());
+        }
+        for rcode in TsigRcode::iter() {
+            assert_eq!(TsigRcode::try_from(rcode.to_string().as_str())
+                                 .unwrap().to_int(),
+                       rcode.to_int());
+        }
+        assert_eq!(OptRcode::try_from("BADVER").unwrap().to_int(), 16);
+        assert!(Rcode::try_from("nxdomain").is_err());
+        assert!(Rcode::try_from("NOTAUTH").is_err());
+        assert!(Rcode::try_from("3").is_err());
+        assert!(Rcode::try_from("14").is_err());
+        assert!(OptRcode::try_from("16").is_err());
+        assert!(TsigRcode::try_from("").is_err());
+        assert!("3".parse::<Rcode>().is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        use serde::Deserialize;
+        use serde::de::IntoDeserializer;
+        use serde::de::value::Error;
+
+        fn from_str<'a, R: Deserialize<'a>>(s: &'a str) -> Result<R, Error> {
+            R::deserialize(s.into_deserializer())
+        }
+
+        fn from_u64<'a, R: Deserialize<'a>>(v: u64) -> Result<R, Error> {
+            R::deserialize(v.into_deserializer())
+        }
+
+        assert_eq!(from_str::<Rcode>("nxdomain").unwrap(), Rcode::NXDomain);
+        assert_eq!(from_u64::<Rcode>(3).unwrap(), Rcode::NXDomain);
+        assert_eq!(from_u64::<Rcode>(14).unwrap(), Rcode::Int(14));
+        assert!(from_u64::<Rcode>(16).is_err());
+        assert!(from_str::<Rcode>("BADVER").is_err());
+        assert_eq!(from_str::<OptRcode>("BADVER").unwrap().to_int(), 16);
+        assert_eq!(from_u64::<OptRcode>(0x234).unwrap().to_int(), 0x234);
+        assert!(from_u64::<OptRcode>(0x1000).is_err());
+        assert_eq!(from_str::<TsigRcode>("BadSig").unwrap().to_int(), 16);
+        assert_eq!(from_u64::<TsigRcode>(0xFFFF).unwrap().to_int(), 0xFFFF);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use serde_test::{assert_de_tokens, assert_tokens, Token};
+
+        assert_tokens(&Rcode::NXDomain, &[Token::Str("NXDOMAIN")]);
+        assert_tokens(&Rcode::Int(3), &[Token::Str("NXDOMAIN")]);
+        assert_tokens(&Rcode::Int(12), &[Token::U8(12)]);
+        assert_tokens(&Rcode::Int(0x13), &[Token::U8(3)]);
+        assert_tokens(&OptRcode::Int(16), &[Token::Str("BADVER")]);
+        assert_tokens(&OptRcode::Int(0x234), &[Token::U16(0x234)]);
+        assert_tokens(&TsigRcode::Int(0x1000), &[Token::U16(0x1000)]);
+        assert_de_tokens(&Rcode::Int(12), &[Token::Str("12")]);
+        assert_de_tokens(&Rcode::Int(12), &[Token::I64(12)]);
+        assert_de_tokens(&OptRcode::Int(0x234), &[Token::U64(0x234)]);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let data: Vec<u8> = (0..=255).rev().collect();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..64 {
+            let rcode = Rcode::arbitrary(&mut u).unwrap();
+            assert_eq!(Rcode::from_int(rcode.to_int()), rcode);
+            let rcode = OptRcode::arbitrary(&mut u).unwrap();
+            assert_eq!(OptRcode::from_int(rcode.to_int()), rcode);
+            TsigRcode::arbitrary(&mut u).unwrap();
+        }
+    }
+
+    #[test]
+    fn opt_rcode_int_round_trip() {
+        for x in 0..0x1_0000u32 {
+            let x = x as u16;
+            assert_eq!(OptRcode::from_int(x).to_int(), x & OptRcode::MAX_INT);
+        }
+        assert_eq!(OptRcode::Int(0x234).to_int(), 0x234);
+    }
+
+    #[test]
+    fn tsig_rcode_int_round_trip() {
+        for x in 0..=TsigRcode::MAX_INT {
+            assert_eq!(TsigRcode::from_int(x).to_int(), x);
+            assert_eq!(TsigRcode::Int(x).to_int(), x);
+        }
+        assert_eq!(TsigRcode::BadSig.to_int(), 16);
+        assert_eq!(TsigRcode::BadCookie.to_int(), 23);
+        match TsigRcode::from_int(24) {
+            TsigRcode::Int(24) => { }
+            rcode => panic!("unexpected rcode {:?}", rcode)
+        }
+    }
+
+    #[test]
+    fn compare_with_rcode() {
+        assert!(OptRcode::NoError == Rcode::NoError);
+        assert!(Rcode::NXDomain == OptRcode::NXDomain);
+        assert!(OptRcode::Int(3) == Rcode::NXDomain);
+        assert!(OptRcode::ServFail != Rcode::Refused);
+        assert!(TsigRcode::NotAuth == Rcode::NotAuth);
+        assert!(Rcode::Int(14) == TsigRcode::Int(14));
+        for rcode in Rcode::iter() {
+            assert!(OptRcode::BadVers != rcode);
+            assert!(OptRcode::from_int(0x100) != rcode);
+            assert!(rcode != TsigRcode::BadSig);
+            assert!(OptRcode::from_int(u16::from(rcode.to_int())) == rcode);
+        }
+    }
+
+    #[test]
+    fn opt_rcode_wire_bits() {
+        for x in 0..=OptRcode::MAX_INT {
+            let rcode = OptRcode::from_int(x);
+            let (header, ext) = (rcode.wire_header_bits(),
+                                 rcode.wire_ext_bits());
+            assert!(header <= 0x0F);
+            assert_eq!(OptRcode::from_wire_bits(header, ext).to_int(), x);
+            assert_eq!((header, ext), (rcode.rcode().to_int(), rcode.ext()));
+        }
+        assert_eq!(OptRcode::BadVers.wire_header_bits(), 0);
+        assert_eq!(OptRcode::BadVers.wire_ext_bits(), 1);
+        assert_eq!(OptRcode::from_wire_bits(0xF7, 1), OptRcode::BadCookie);
+    }
+
+    #[test]
+    fn for_edns_version() {
+        assert_eq!(OptRcode::for_edns_version(0, 0), None);
+        assert_eq!(OptRcode::for_edns_version(0, 1), None);
+        assert_eq!(OptRcode::for_edns_version(1, 0), Some(OptRcode::BadVers));
+        assert_eq!(OptRcode::for_edns_version(255, 254),
+                   Some(OptRcode::BadVers));
+    }
+
+    #[test]
+    fn opt_rcode_from_raw() {
+        match OptRcode::from_raw(0x82, 0x0100_8000) {
+            OptRcode::Int(0x12) => { }
+            rcode => panic!("unexpected rcode {:?}", rcode)
+        }
+        assert_eq!(OptRcode::from_raw(0x82, 0x0100_8000).to_int(), 0x12);
+        assert_eq!(OptRcode::from_raw(0x80, 0x0100_0000).to_int(), 16);
+        assert_eq!(OptRcode::from_raw(0x03, 0).to_int(), 3);
+    }
+
+    #[test]
+    fn opt_rcode_from_parts_without_ext() {
+        // Without extended bits, the result equals the plain rcode. Since
+        // `From<Rcode>` is implemented via `from_parts()`, both must
+        // return without going through the other.
+        assert_eq!(OptRcode::from_parts(Rcode::Refused, 0),
+                   OptRcode::Refused);
+        assert_eq!(OptRcode::from(Rcode::NXDomain), OptRcode::NXDomain);
+        assert_eq!(OptRcode::from(Rcode::Int(0x0E)).to_int(), 0x0E);
+    }
+
+    #[test]
+    fn opt_rcode_parts_round_trip() {
+        for r in 0..16 {
+            let rcode = Rcode::from_int(r);
+            for e in 0..=255 {
+                assert_eq!(OptRcode::from_parts(rcode, e).to_parts(),
+                           (rcode, e));
+                assert_eq!(OptRcode::checked_from_parts(rcode, e).unwrap()
+                                    .to_parts(),
+                           (rcode, e));
+                assert_eq!(OptRcode::from_parts(Rcode::Int(r), e)
+                                    .to_int(),
+                           (e as u16) << 4 | r as u16);
+            }
+        }
+        assert_eq!(OptRcode::from_parts(Rcode::Int(0x13), 1).to_int(), 0x13);
+    }
+
+    #[test]
+    fn opt_rcode_checked_from_parts() {
+        assert_eq!(OptRcode::checked_from_parts(Rcode::Int(0x13), 1),
+                   Err(RangeError));
+        assert_eq!(OptRcode::checked_from_parts(Rcode::Int(0x03), 1),
+                   Ok(OptRcode::from_int(0x13)));
+    }
+}
@@ -119,9 +119,13 @@ int_enum!{
     (Nsapptr => 23, b"NSAPPTR")
 
     /// For security signature.
+    ///
+    /// See RFC 2535 and RFC 2931.
     (Sig => 24, b"SIG")
 
     /// For security key.
+    ///
+    /// See RFC 2535 and RFC 3445.
     (Key => 25, b"KEY")
 
     /// X.400 mail mapping information.
@@ -217,7 +221,7 @@ int_enum!{
 
     /// IPSECKEY
     ///
-    /// See RFC 4255.
+    /// See RFC 4025.
     (Ipseckey => 45, b"IPSECKEY")
 
     /// RRSIG.
@@ -257,7 +261,7 @@ int_enum!{
 
     /// S/MIME cert association.
     ///
-    /// See draft-ietf-dane-smime.
+    /// See RFC 8162.
     (Smimea => 53, b"SMIMEA")
 
     /// Host Identity Protocol.
@@ -286,7 +290,7 @@ int_enum!{
     
     /// OpenPGP key.
     ///
-    /// See draft-ietf-dane-openpgpkey.
+    /// See RFC 7929.
     (Openpgpkey => 61, b"OPENPGPKEY")
 
     /// Child-to-parent synchronization.
@@ -423,4 +427,132 @@ impl Rtype {
                  .ok_or_else(|| SyntaxError::UnknownClass(slice.into()))
         })
     }
+
+    /// Returns the record data length for record types of fixed length.
+    ///
+    /// Some record types always have record data of the same length. For
+    /// these, the method returns that length. For all other record types,
+    /// including those unknown to this crate, it returns `None`.
+    pub fn fixed_rdlength(&self) -> Option<u16> {
+        match *self {
+            Rtype::A => Some(4),
+            Rtype::Aaaa => Some(16),
+            Rtype::Nid => Some(10),
+            Rtype::L32 => Some(6),
+            Rtype::L64 => Some(10),
+            Rtype::Eui48 => Some(6),
+            Rtype::Eui64 => Some(8),
+            Rtype::Int(value) => {
+                match Rtype::from_int(value) {
+                    Rtype::Int(_) => None,
+                    rtype => rtype.fixed_rdlength()
+                }
+            }
+            _ => None
+        }
+    }
+
+    /// Returns the number of the RFC that defines the record type.
+    ///
+    /// If a record type has been updated or redefined by later RFCs, the
+    /// RFC that contains the current definition is returned. For record
+    /// types not defined in an RFC and those unknown to this crate, the
+    /// method returns `None`.
+    pub fn defining_rfc(&self) -> Option<u32> {
+        use self::Rtype::*;
+
+        match *self {
+            A | Ns | Md | Mf | Cname | Soa | Mb | Mg | Mr | Null | Wks
+                | Ptr | Hinfo | Minfo | Mx | Txt | Mailb | Maila
+                | Any => Some(1035),
+            Rp | Afsdb | X25 | Isdn | Rt => Some(1183),
+            Nsap => Some(1706),
+            Nsapptr => Some(1348),
+            Sig | Key | Nxt => Some(2535),
+            Px => Some(2163),
+            Gpos => Some(1712),
+            Aaaa => Some(3596),
+            Loc => Some(1876),
+            Srv => Some(2782),
+            Naptr => Some(3403),
+            Kx => Some(2230),
+            Cert => Some(4398),
+            A6 => Some(2874),
+            Dname => Some(6672),
+            Opt => Some(6891),
+            Apl => Some(3123),
+            Ds | Rrsig | Nsec | Dnskey => Some(4034),
+            Sshfp => Some(4255),
+            Ipseckey => Some(4025),
+            Dhcid => Some(4701),
+            Nsec3 | Nsec3param => Some(5155),
+            Tlsa => Some(6698),
+            Smimea => Some(8162),
+            Hip => Some(8005),
+            Cds | Cdnskey => Some(7344),
+            Openpgpkey => Some(7929),
+            Csync => Some(7477),
+            Spf => Some(7208),
+            Nid | L32 | L64 | Lp => Some(6742),
+            Eui48 | Eui64 => Some(7043),
+            Tkey => Some(2930),
+            Tsig => Some(8945),
+            Ixfr => Some(1995),
+            Axfr => Some(5936),
+            Uri => Some(7553),
+            Caa => Some(8659),
+            Dlv => Some(4431),
+            Int(value) => {
+                match Rtype::from_int(value) {
+                    Int(_) => None,
+                    rtype => rtype.defining_rfc()
+                }
+            }
+            _ => None
+        }
+    }
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fixed_rdlength() {
+        assert_eq!(Rtype::A.fixed_rdlength(), Some(4));
+        assert_eq!(Rtype::Aaaa.fixed_rdlength(), Some(16));
+        assert_eq!(Rtype::Int(1).fixed_rdlength(), Some(4));
+        assert_eq!(Rtype::Txt.fixed_rdlength(), None);
+        assert_eq!(Rtype::Int(4711).fixed_rdlength(), None);
+    }
+
+    #[test]
+    fn defining_rfc() {
+        assert_eq!(Rtype::A.defining_rfc(), Some(1035));
+        assert_eq!(Rtype::Tlsa.defining_rfc(), Some(6698));
+        assert_eq!(Rtype::Int(52).defining_rfc(), Some(6698));
+        assert_eq!(Rtype::Hip.defining_rfc(), Some(8005));
+        assert_eq!(Rtype::Caa.defining_rfc(), Some(8659));
+        assert_eq!(Rtype::Tsig.defining_rfc(), Some(8945));
+        assert_eq!(Rtype::Int(252).defining_rfc(), Some(5936));
+        assert_eq!(Rtype::Ninfo.defining_rfc(), None);
+        assert_eq!(Rtype::Int(4711).defining_rfc(), None);
+    }
+
+    #[test]
+    fn meta_types() {
+        use std::str::FromStr;
+
+        assert_eq!(Rtype::Axfr.to_int(), 252);
+        assert_eq!(Rtype::Axfr.to_string(), "AXFR");
+        assert_eq!(Rtype::Ixfr.to_int(), 251);
+        assert_eq!(Rtype::Ixfr.to_string(), "IXFR");
+        assert_eq!(Rtype::Mailb.to_int(), 253);
+        assert_eq!(Rtype::Any.to_int(), 255);
+        assert_eq!(Rtype::Any.to_string(), "ANY");
+        assert_eq!(Rtype::from_str("axfr").unwrap(), Rtype::Axfr);
+    }
 }
@@ -22,6 +22,9 @@ extern crate byteorder;
 #[macro_use] extern crate futures;
 extern crate rand;
 #[macro_use] extern crate tokio_core;
+#[cfg(feature = "serde")] extern crate serde;
+#[cfg(all(test, feature = "serde"))] extern crate serde_test;
+#[cfg(feature = "arbitrary")] extern crate arbitrary;
 
 pub mod bits;
 pub mod iana;
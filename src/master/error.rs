@@ -6,6 +6,7 @@ use std::num::ParseIntError;
 use std::result;
 use std::str::Utf8Error;
 use ::bits::name;
+use ::utils::base64;
 
 
 //------------ SyntaxError ---------------------------------------------------
@@ -19,6 +20,7 @@ pub enum SyntaxError {
     IllegalEscape,
     IllegalInteger,
     IllegalAddr(AddrParseError),
+    IllegalBase64(base64::DecodeError),
     IllegalName,
     IllegalString(Utf8Error),
     LongCharStr,
@@ -33,8 +35,10 @@ pub enum SyntaxError {
     RelativeName,
     Unexpected(u8),
     UnexpectedEof,
+    UnknownCertType(Vec<u8>),
     UnknownClass(Vec<u8>),
     UnknownProto(String),
+    UnknownSecAlg(Vec<u8>),
     UnknownServ(String),
 }
 
@@ -50,6 +54,12 @@ impl From<AddrParseError> for SyntaxError {
     }
 }
 
+impl From<base64::DecodeError> for SyntaxError {
+    fn from(err: base64::DecodeError) -> SyntaxError {
+        SyntaxError::IllegalBase64(err)
+    }
+}
+
 impl From<Utf8Error> for SyntaxError {
     fn from(err: Utf8Error) -> SyntaxError {
         SyntaxError::IllegalString(err)
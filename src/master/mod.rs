@@ -9,4 +9,5 @@ pub mod error;
 pub mod reader;
 pub mod record;
 pub mod scanner;
+pub mod zone;
 
@@ -3,7 +3,8 @@ use std::str;
 use ::bits::CharStrBuf;
 use ::bits::{DNameBuf, DNameSlice};
 use ::bits::name::{DNameBuilder, DNameBuildInto};
-use super::error::{Pos, ScanResult, SyntaxError, SyntaxResult};
+use ::utils::base64;
+use super::error::{Pos, ScanError, ScanResult, SyntaxError, SyntaxResult};
 
 
 //------------ Scanner -------------------------------------------------------
@@ -226,6 +227,40 @@ pub trait Scanner {
         })
     }
 
+    /// Scans a sequence of words containing Base 64 encoded data.
+    ///
+    /// The data may be spread over several words and continues until the
+    /// next token isn’t a word anymore, normally at the end of the entry.
+    /// The decoded data is returned as a bytes vec.
+    fn scan_base64_words(&mut self) -> ScanResult<Vec<u8>> {
+        let mut decoder = base64::Decoder::new();
+        try!(self.scan_word(|slice| {
+            for &ch in slice {
+                try!(decoder.push(ch))
+            }
+            Ok(())
+        }));
+        loop {
+            let res = self.scan_word(|slice| {
+                for &ch in slice {
+                    try!(decoder.push(ch))
+                }
+                Ok(())
+            });
+            match res {
+                Ok(()) => { }
+                Err(ScanError::Syntax(SyntaxError::Unexpected(_), _)) |
+                Err(ScanError::Syntax(SyntaxError::UnexpectedEof, _)) => {
+                    break
+                }
+                Err(err) => return Err(err)
+            }
+        }
+        decoder.finalize().map_err(|err| {
+            ScanError::Syntax(err.into(), self.pos())
+        })
+    }
+
     /// Skips over the word with the content `literal`.
     ///
     /// The content indeed needs to be literally the literal. Escapes are
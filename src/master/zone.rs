@@ -0,0 +1,619 @@
+//! A simple collection of the records of a zone.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::iter::FromIterator;
+use std::{error, fmt, slice, vec};
+use ::bits::{Composer, ComposeMode, ComposeResult, DName, DNameBuf, Message,
+             MessageBuilder, RecordData};
+use ::iana::{Class, Rcode, Rtype};
+use ::master::record::MasterRecord;
+use ::rdata::MasterRecordData;
+
+
+//------------ Zone ----------------------------------------------------------
+
+/// The records of a zone.
+///
+/// This type simply collects all the records read from a master file. The
+/// records are kept in the order they were added. They can be accessed
+/// either in that order via `iter()` or grouped into RRsets in canonical
+/// order via `iter_canonical()`.
+#[derive(Clone, Debug, Default)]
+pub struct Zone {
+    records: Vec<MasterRecord>,
+}
+
+impl Zone {
+    /// Creates a new, empty zone.
+    pub fn new() -> Self {
+        Zone { records: Vec::new() }
+    }
+
+    /// Adds a record to the zone.
+    pub fn push(&mut self, record: MasterRecord) {
+        self.records.push(record)
+    }
+
+    /// Returns the number of records in the zone.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns whether the zone is empty.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns an iterator over the records in the order they were added.
+    pub fn iter(&self) -> slice::Iter<MasterRecord> {
+        self.records.iter()
+    }
+
+    /// Returns an iterator over the RRsets of the zone in canonical order.
+    ///
+    /// The RRsets are ordered by their owner names in the canonical order
+    /// defined in section 6.1 of [RFC 4034], then by class, and then by
+    /// record type. The records within each RRset are ordered by their
+    /// canonical record data as defined in section 6.3. This is the order
+    /// a signer needs to process the zone in.
+    ///
+    /// The method fails if the record data of a record cannot be composed,
+    /// for instance, because it contains a relative domain name.
+    ///
+    /// [RFC 4034]: https://tools.ietf.org/html/rfc4034
+    pub fn iter_canonical(&self) -> ComposeResult<RrsetIter> {
+        let mut records = Vec::with_capacity(self.records.len());
+        for record in &self.records {
+            let mut composer = Composer::new(ComposeMode::Unlimited, false);
+            record.rdata.compose_canonical(&mut composer)?;
+            records.push((record, composer.finish()));
+        }
+        records.sort_by(|left, right| {
+            cmp_rrset(left.0, right.0).then_with(|| left.1.cmp(&right.1))
+        });
+
+        let mut rrsets: Vec<Rrset> = Vec::new();
+        for (record, data) in records {
+            let same = match rrsets.last() {
+                Some(rrset) => {
+                    cmp_rrset(rrset.records[0], record) == Ordering::Equal
+                }
+                None => false
+            };
+            if same {
+                let rrset = rrsets.last_mut().unwrap();
+                rrset.records.push(record);
+                rrset.data.push(data);
+            }
+            else {
+                rrsets.push(Rrset { records: vec![record], data: vec![data] })
+            }
+        }
+        Ok(RrsetIter(rrsets.into_iter()))
+    }
+
+    /// Returns the names occluded by a delegation or a Dname record.
+    ///
+    /// Names below a zone cut, ie., an NS record anywhere but at the zone
+    /// apex, belong to the child zone and must not be served from this
+    /// zone (RFC 1034, section 4.2.1). The exception are glue records,
+    /// which are taken to be any A and AAAA records below a zone cut.
+    /// Similarly, names below a Dname record are hidden entirely (RFC
+    /// 6672, section 2.4).
+    ///
+    /// The zone apex is the owner of the Soa record. If there is none,
+    /// all NS records are considered delegations.
+    ///
+    /// The method returns each name with occluded records once in
+    /// canonical order. Occluded records at the delegation point itself
+    /// are not considered.
+    pub fn occluded_names(&self) -> Vec<DNameBuf> {
+        let apex = self.records.iter().find(|record| {
+            record.rdata.rtype() == Rtype::Soa
+        }).map(|record| &record.owner);
+        let cuts: Vec<_> = self.records.iter().filter_map(|record| {
+            match record.rdata.rtype() {
+                Rtype::Ns if Some(&record.owner) != apex => {
+                    Some((&record.owner, false))
+                }
+                Rtype::Dname => Some((&record.owner, true)),
+                _ => None
+            }
+        }).collect();
+        let mut res: Vec<DNameBuf> = Vec::new();
+        for record in &self.records {
+            let rtype = record.rdata.rtype();
+            let glue = rtype == Rtype::A || rtype == Rtype::Aaaa;
+            let occluded = cuts.iter().any(|&(cut, dname)| {
+                (dname || !glue) && record.owner != *cut
+                    && record.owner.ends_with(cut.as_ref())
+            });
+            if occluded {
+                res.push(DNameBuf::clone(&record.owner))
+            }
+        }
+        res.sort();
+        res.dedup();
+        res
+    }
+
+    /// Returns the owner names that differ only in case.
+    ///
+    /// Domain names are compared case-insensitively, so owner names that
+    /// are spelled differently but only in case refer to the same node
+    /// and their records collide. This is most likely a mistake in the
+    /// zone data.
+    ///
+    /// For each such spelling, the method returns a pair of the spelling
+    /// first encountered and the different spelling in the order the
+    /// records were added. Each pair is returned only once.
+    pub fn check_case_collisions(&self) -> Vec<(DNameBuf, DNameBuf)> {
+        let mut first: HashMap<DNameBuf, &DNameBuf> = HashMap::new();
+        let mut res: Vec<(DNameBuf, DNameBuf)> = Vec::new();
+        for record in &self.records {
+            let owner: &DNameBuf = &record.owner;
+            let known = *first.entry(owner.to_lowercase())
+                              .or_insert(owner);
+            if known.as_bytes() != owner.as_bytes()
+                    && !res.iter().any(|&(_, ref name)| {
+                        name.as_bytes() == owner.as_bytes()
+                    }) {
+                res.push((known.clone(), owner.clone()))
+            }
+        }
+        res
+    }
+
+    /// Produces the response to a query from the zone’s data.
+    ///
+    /// The zone apex is the owner of the SOA record. Queries for names
+    /// outside the zone, for a different class, or for a zone without an
+    /// SOA record are refused. Otherwise the response is authoritative and
+    /// contains the records of the queried type. If there is a CNAME record
+    /// for the name instead, it is added to the answer and followed if its
+    /// target is within the zone, too. If the target is outside the zone,
+    /// the response only contains the CNAME records, leaving it to the
+    /// resolver to follow the chain further. Since the authoritative answer
+    /// bit only covers the owner names in the zone, it stays set in this
+    /// case. If there is no data, the SOA record is added to the authority
+    /// section and, if the name doesn’t exist at all, the rcode is set to
+    /// NXDomain.
+    ///
+    /// This is a simple responder only. In particular, delegations,
+    /// wildcards, and DNAME records are not considered.
+    pub fn answer(&self, query: &Message, mode: ComposeMode)
+                  -> ComposeResult<Vec<u8>> {
+        let mut response = MessageBuilder::new(mode, true)?;
+        response.header_mut().set_id(query.header().id());
+        response.header_mut().set_qr(true);
+        response.header_mut().set_opcode(query.header().opcode());
+        response.header_mut().set_rd(query.header().rd());
+        let question = match query.first_question() {
+            Some(question) => question,
+            None => {
+                response.header_mut().set_rcode(Rcode::FormErr);
+                return Ok(response.finish())
+            }
+        };
+        response.push(question.clone())?;
+        let soa = self.records.iter().find(|record| {
+            record.rdata.rtype() == Rtype::Soa
+        });
+        let soa = match soa {
+            Some(soa) if soa.class == question.qclass() => soa,
+            _ => {
+                response.header_mut().set_rcode(Rcode::Refused);
+                return Ok(response.finish())
+            }
+        };
+        let mut name = question.qname().to_cow().into_owned();
+        if !name.ends_with(soa.owner.as_ref()) {
+            response.header_mut().set_rcode(Rcode::Refused);
+            return Ok(response.finish())
+        }
+        response.header_mut().set_aa(true);
+        let mut response = response.answer();
+        let mut visited = Vec::new();
+        loop {
+            let mut found = false;
+            let mut cname = None;
+            for record in &self.records {
+                if *record.owner != name || record.class != soa.class {
+                    continue
+                }
+                let rtype = record.rdata.rtype();
+                if rtype == question.qtype()
+                        || question.qtype() == Rtype::Any {
+                    response.push(record_tuple(record))?;
+                    found = true;
+                }
+                else if let MasterRecordData::Cname(ref data) = record.rdata {
+                    cname = Some((record, data.cname().clone()));
+                }
+            }
+            if found {
+                return Ok(response.finish())
+            }
+            let (record, target) = match cname {
+                Some(cname) => cname,
+                None => break
+            };
+            response.push(record_tuple(record))?;
+            visited.push(name);
+            if !target.ends_with(soa.owner.as_ref())
+                    || visited.contains(&target) {
+                // Out of zone targets are left to the resolver and loops
+                // are simply cut short.
+                return Ok(response.finish())
+            }
+            name = target;
+        }
+        let exists = self.records.iter().any(|record| {
+            record.owner.ends_with(&name)
+        });
+        if !exists {
+            response.header_mut().set_rcode(Rcode::NXDomain);
+        }
+        let mut response = response.authority();
+        response.push(record_tuple(soa))?;
+        Ok(response.finish())
+    }
+}
+
+/// Returns a record tuple of the record for adding it to a message.
+fn record_tuple(record: &MasterRecord)
+                -> (&DNameBuf, Class, u32, MasterRecordData) {
+    (&record.owner, record.class, record.ttl, record.rdata.clone())
+}
+
+/// Compares the parts of two records that determine their RRset.
+fn cmp_rrset(left: &MasterRecord, right: &MasterRecord) -> Ordering {
+    left.owner.cmp(&right.owner)
+        .then_with(|| left.class.cmp(&right.class))
+        .then_with(|| left.rdata.rtype().cmp(&right.rdata.rtype()))
+}
+
+
+//--- FromIterator and IntoIterator
+
+impl FromIterator<MasterRecord> for Zone {
+    fn from_iter<I>(iter: I) -> Self
+                 where I: IntoIterator<Item=MasterRecord> {
+        Zone { records: Vec::from_iter(iter) }
+    }
+}
+
+impl<'a> IntoIterator for &'a Zone {
+    type Item = &'a MasterRecord;
+    type IntoIter = slice::Iter<'a, MasterRecord>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+
+//------------ Rrset ---------------------------------------------------------
+
+/// The records of a zone with the same owner, class, and record type.
+///
+/// Values of this type are produced by `Zone::iter_canonical()`. They are
+/// never empty.
+#[derive(Clone, Debug)]
+pub struct Rrset<'a> {
+    records: Vec<&'a MasterRecord>,
+
+    /// The canonical record data of the records.
+    data: Vec<Vec<u8>>,
+}
+
+impl<'a> Rrset<'a> {
+    /// Returns the owner name of the RRset.
+    pub fn owner(&self) -> &'a DNameBuf {
+        &self.records[0].owner
+    }
+
+    /// Returns the class of the RRset.
+    pub fn class(&self) -> Class {
+        self.records[0].class
+    }
+
+    /// Returns the record type of the RRset.
+    pub fn rtype(&self) -> Rtype {
+        self.records[0].rdata.rtype()
+    }
+
+    /// Returns the TTL of the first record of the RRset.
+    ///
+    /// All records of an RRset should have the same TTL but this isn’t
+    /// enforced.
+    pub fn ttl(&self) -> u32 {
+        self.records[0].ttl
+    }
+
+    /// Returns the number of records in the RRset.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns the records of the RRset in canonical order.
+    pub fn records(&self) -> &[&'a MasterRecord] {
+        &self.records
+    }
+
+    /// Checks that the RRset is well-formed as required by RFC 2181.
+    ///
+    /// Section 5 of [RFC 2181] forbids records with identical record data
+    /// within an RRset and requires all records of an RRset to have the
+    /// same TTL. Record data is compared in its canonical form, so
+    /// domain names that only differ in case are considered identical.
+    ///
+    /// [RFC 2181]: https://tools.ietf.org/html/rfc2181
+    pub fn validate(&self) -> Result<(), RrsetError> {
+        // The records are sorted by their canonical data, so duplicates
+        // are always next to each other.
+        if self.data.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(RrsetError::Duplicate)
+        }
+        if self.records.iter().any(|record| record.ttl != self.ttl()) {
+            return Err(RrsetError::MixedTtl)
+        }
+        Ok(())
+    }
+}
+
+
+//------------ RrsetError ----------------------------------------------------
+
+/// An RRset is malformed.
+///
+/// This is returned by `Rrset::validate()`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RrsetError {
+    /// The RRset contains records with identical record data.
+    Duplicate,
+
+    /// The records of the RRset have different TTLs.
+    MixedTtl,
+}
+
+impl error::Error for RrsetError {
+    fn description(&self) -> &str {
+        match *self {
+            RrsetError::Duplicate => "duplicate record in RRset",
+            RrsetError::MixedTtl => "differing TTLs in RRset",
+        }
+    }
+}
+
+impl fmt::Display for RrsetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        error::Error::description(self).fmt(f)
+    }
+}
+
+
+//------------ RrsetIter -----------------------------------------------------
+
+/// An iterator over the RRsets of a zone in canonical order.
+#[derive(Clone, Debug)]
+pub struct RrsetIter<'a>(vec::IntoIter<Rrset<'a>>);
+
+impl<'a> Iterator for RrsetIter<'a> {
+    type Item = Rrset<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::iana::Rtype;
+    use ::master::reader::{Reader, ReaderItem};
+
+    #[test]
+    fn iter_canonical() {
+        let zone: Zone = Reader::create(&b"$ORIGIN example.com.
+$TTL 3600
+@       IN  SOA     ns hostmaster 1 7200 600 3600000 60
+        MX      10  mail
+        NS      ns
+        A       192.0.2.1
+b       A       192.0.2.2
+z.A     A       192.0.2.3
+*       TXT     \"wild\"
+a       A       192.0.2.5
+        A       192.0.2.4
+        MX      20 Mail
+        MX      10 mail
+"[..]).map(|item| {
+            match item.unwrap() {
+                ReaderItem::Record(record) => record,
+                _ => panic!("unexpected include")
+            }
+        }).collect();
+        assert_eq!(zone.len(), 11);
+
+        let rrsets: Vec<_> = zone.iter_canonical().unwrap().map(|rrset| {
+            (rrset.owner().to_string(), rrset.rtype(), rrset.len())
+        }).collect();
+        assert_eq!(rrsets, vec![
+            ("example.com.".to_owned(), Rtype::A, 1),
+            ("example.com.".to_owned(), Rtype::Ns, 1),
+            ("example.com.".to_owned(), Rtype::Soa, 1),
+            ("example.com.".to_owned(), Rtype::Mx, 1),
+            ("*.example.com.".to_owned(), Rtype::Txt, 1),
+            ("a.example.com.".to_owned(), Rtype::A, 2),
+            ("a.example.com.".to_owned(), Rtype::Mx, 2),
+            ("z.A.example.com.".to_owned(), Rtype::A, 1),
+            ("b.example.com.".to_owned(), Rtype::A, 1),
+        ]);
+
+        let rrset = zone.iter_canonical().unwrap().nth(5).unwrap();
+        let data: Vec<_> = rrset.records().iter().map(|record| {
+            record.rdata.to_string()
+        }).collect();
+        assert_eq!(data, vec!["192.0.2.4", "192.0.2.5"]);
+
+        let rrset = zone.iter_canonical().unwrap().nth(6).unwrap();
+        let data: Vec<_> = rrset.records().iter().map(|record| {
+            record.rdata.to_string()
+        }).collect();
+        assert_eq!(data, vec!["10 mail.example.com.",
+                              "20 Mail.example.com."]);
+    }
+
+    #[test]
+    fn validate_rrsets() {
+        let zone: Zone = Reader::create(&b"$ORIGIN example.com.
+$TTL 3600
+@       IN  SOA     ns hostmaster 1 7200 600 3600000 60
+        NS      ns
+dup     MX      10 mail
+        MX      10 MAIL
+        MX      20 mail
+ttl     A       192.0.2.1
+        300 A   192.0.2.2
+ok      A       192.0.2.1
+        A       192.0.2.2
+"[..]).map(|item| {
+            match item.unwrap() {
+                ReaderItem::Record(record) => record,
+                _ => panic!("unexpected include")
+            }
+        }).collect();
+        let res: Vec<_> = zone.iter_canonical().unwrap().map(|rrset| {
+            (rrset.owner().to_string(), rrset.validate())
+        }).collect();
+        assert_eq!(res, vec![
+            ("example.com.".to_owned(), Ok(())),
+            ("example.com.".to_owned(), Ok(())),
+            ("dup.example.com.".to_owned(), Err(RrsetError::Duplicate)),
+            ("ok.example.com.".to_owned(), Ok(())),
+            ("ttl.example.com.".to_owned(), Err(RrsetError::MixedTtl)),
+        ]);
+    }
+
+    #[test]
+    fn occluded_names() {
+        let zone: Zone = Reader::create(&b"$ORIGIN example.com.
+$TTL 3600
+@       IN  SOA     ns hostmaster 1 7200 600 3600000 60
+        NS      ns
+ns      A       192.0.2.1
+sub     NS      ns.sub
+        A       192.0.2.2
+ns.sub  A       192.0.2.3
+www.sub A       192.0.2.4
+www.sub TXT     \"hidden\"
+deep.www.sub MX 10 mail
+old     DNAME   \\# 17 036e6577076578616d706c6503636f6d00
+x.old   A       192.0.2.5
+"[..]).map(|item| {
+            match item.unwrap() {
+                ReaderItem::Record(record) => record,
+                _ => panic!("unexpected include")
+            }
+        }).collect();
+        let names: Vec<_> = zone.occluded_names().iter().map(|name| {
+            name.to_string()
+        }).collect();
+        assert_eq!(names, vec!["x.old.example.com.",
+                               "www.sub.example.com.",
+                               "deep.www.sub.example.com."]);
+    }
+
+    #[test]
+    fn check_case_collisions() {
+        let zone: Zone = Reader::create(&b"$ORIGIN com.
+$TTL 3600
+example IN  SOA     ns hostmaster 1 7200 600 3600000 60
+Example     A       192.0.2.1
+www.example A       192.0.2.2
+EXAMPLE     MX      10 mail
+Example     TXT     \"again\"
+www.example AAAA    2001:db8::1
+"[..]).map(|item| {
+            match item.unwrap() {
+                ReaderItem::Record(record) => record,
+                _ => panic!("unexpected include")
+            }
+        }).collect();
+        let pairs: Vec<_> = zone.check_case_collisions().iter()
+                                .map(|&(ref left, ref right)| {
+            (left.to_string(), right.to_string())
+        }).collect();
+        assert_eq!(pairs, vec![
+            ("example.com.".to_owned(), "Example.com.".to_owned()),
+            ("example.com.".to_owned(), "EXAMPLE.com.".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn answer() {
+        use std::str::FromStr;
+        use ::bits::{ComposeMode, DNameBuf, MessageBuf, MessageBuilder};
+        use ::iana::Rcode;
+
+        let zone: Zone = Reader::create(&b"$ORIGIN example.com.
+$TTL 3600
+@       IN  SOA     ns hostmaster 1 7200 600 3600000 60
+        NS      ns
+ns      A       192.0.2.1
+www     CNAME   web
+web     A       192.0.2.2
+ext     CNAME   www.example.net.
+"[..]).map(|item| {
+            match item.unwrap() {
+                ReaderItem::Record(record) => record,
+                _ => panic!("unexpected include")
+            }
+        }).collect();
+        let ask = |name: &str| {
+            let mut query = MessageBuilder::new(ComposeMode::Unlimited,
+                                                true).unwrap();
+            query.header_mut().set_id(12);
+            query.push((DNameBuf::from_str(name).unwrap(), Rtype::A))
+                 .unwrap();
+            let query = MessageBuf::from_vec(query.finish()).unwrap();
+            let response = zone.answer(&query, ComposeMode::Unlimited)
+                               .unwrap();
+            MessageBuf::from_vec(response).unwrap()
+        };
+
+        // In-zone CNAME is followed.
+        let response = ask("www.example.com.");
+        assert_eq!(response.header().id(), 12);
+        assert!(response.header().qr());
+        assert!(response.header().aa());
+        assert_eq!(response.header().rcode(), Rcode::NoError);
+        let answer: Vec<_> = response.answer().unwrap().map(|record| {
+            record.unwrap().rtype()
+        }).collect();
+        assert_eq!(answer, vec![Rtype::Cname, Rtype::A]);
+
+        // Out-of-zone target is left for recursion.
+        let response = ask("ext.example.com.");
+        assert!(response.header().aa());
+        assert_eq!(response.header().rcode(), Rcode::NoError);
+        let answer: Vec<_> = response.answer().unwrap().map(|record| {
+            record.unwrap().rtype()
+        }).collect();
+        assert_eq!(answer, vec![Rtype::Cname]);
+        assert_eq!(response.authority().unwrap().count(), 0);
+
+        // Name errors and refusals.
+        let response = ask("nope.example.com.");
+        assert_eq!(response.header().rcode(), Rcode::NXDomain);
+        assert_eq!(response.authority().unwrap().count(), 1);
+        let response = ask("www.example.net.");
+        assert_eq!(response.header().rcode(), Rcode::Refused);
+        assert!(!response.header().aa());
+    }
+}
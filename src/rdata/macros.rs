@@ -4,7 +4,8 @@
 //! are here so that `mod.rs` doesn’t become too unwieldly.
 
 macro_rules! master_types {
-    ( $( $module:ident::{  $( $rtype:ident => $full_rtype:ty, )*  })* ) => {
+    ( $( $module:ident::{  $( $rtype:ident => $full_rtype:ty
+                                             | $parsed_rtype:ty, )*  })* ) => {
         $(
             pub use self::$module::{ $( $rtype ),* };
         )*
@@ -82,6 +83,23 @@ macro_rules! master_types {
                     }
                 }
             }
+
+            fn compose_canonical<C>(&self, mut target: C)
+                                    -> ::bits::ComposeResult<()>
+                                 where C: AsMut<::bits::Composer> {
+                match *self {
+                    $(
+                        $(
+                            MasterRecordData::$rtype(ref data) => {
+                                data.compose_canonical(target)
+                            }
+                        )*
+                    )*
+                    MasterRecordData::Generic(_, ref data) => {
+                        target.as_mut().compose_bytes(data)
+                    }
+                }
+            }
         }
 
         impl ::std::fmt::Display for MasterRecordData {
@@ -102,6 +120,120 @@ macro_rules! master_types {
             }
         }
 
+        /// An enum with all the record data that can be parsed from messages.
+        ///
+        /// This enum contains variants for all the implemented record data
+        /// types in their parsed form plus the `Unknown` variant for record
+        /// data of any other type. It allows treating the record data of
+        /// records of all types alike, for instance when logging or
+        /// filtering them.
+        ///
+        /// Values are created by parsing via the [`ParsedRecordData`]
+        /// trait.
+        ///
+        /// [`ParsedRecordData`]: ../bits/rdata/trait.ParsedRecordData.html
+        #[derive(Clone, Debug)]
+        pub enum AllRecordData<'a> {
+            $(
+                $(
+                    $rtype($parsed_rtype),
+                )*
+            )*
+            Unknown(::bits::GenericRecordData<'a>),
+        }
+
+        impl<'a> ::bits::RecordData for AllRecordData<'a> {
+            fn rtype(&self) -> ::iana::Rtype {
+                match *self {
+                    $(
+                        $(
+                            AllRecordData::$rtype(ref data) => {
+                                data.rtype()
+                            }
+                        )*
+                    )*
+                    AllRecordData::Unknown(ref data) => data.rtype()
+                }
+            }
+
+            fn compose<C>(&self, target: C) -> ::bits::ComposeResult<()>
+                       where C: AsMut<::bits::Composer> {
+                match *self {
+                    $(
+                        $(
+                            AllRecordData::$rtype(ref data) => {
+                                data.compose(target)
+                            }
+                        )*
+                    )*
+                    AllRecordData::Unknown(ref data) => data.compose(target)
+                }
+            }
+
+            fn compose_canonical<C>(&self, target: C)
+                                    -> ::bits::ComposeResult<()>
+                                 where C: AsMut<::bits::Composer> {
+                match *self {
+                    $(
+                        $(
+                            AllRecordData::$rtype(ref data) => {
+                                data.compose_canonical(target)
+                            }
+                        )*
+                    )*
+                    AllRecordData::Unknown(ref data) => {
+                        data.compose_canonical(target)
+                    }
+                }
+            }
+        }
+
+        impl<'a> ::bits::ParsedRecordData<'a> for AllRecordData<'a> {
+            /// Parses record data of any type.
+            ///
+            /// Record data of the implemented types is parsed into their
+            /// respective variant, everything else ends up as `Unknown`.
+            /// Consequently, this never returns `Ok(None)`.
+            fn parse(rtype: ::iana::Rtype, parser: &mut ::bits::Parser<'a>)
+                     -> ::bits::ParseResult<Option<Self>> {
+                use ::bits::ParsedRecordData;
+
+                match rtype {
+                    $(
+                        $(
+                            ::iana::Rtype::$rtype => {
+                                <$parsed_rtype as ParsedRecordData<'a>>
+                                    ::parse(rtype, parser)
+                                    .map(|res| res.map(AllRecordData::$rtype))
+                            }
+                        )*
+                    )*
+                    _ => {
+                        ::bits::GenericRecordData::parse(rtype, parser)
+                            .map(|res| res.map(AllRecordData::Unknown))
+                    }
+                }
+            }
+        }
+
+        impl<'a> ::std::fmt::Display for AllRecordData<'a> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter)
+                   -> ::std::fmt::Result {
+                match *self {
+                    $(
+                        $(
+                            AllRecordData::$rtype(ref data) => {
+                                ::std::fmt::Display::fmt(data, f)
+                            }
+                        )*
+                    )*
+                    AllRecordData::Unknown(ref data) => {
+                        ::std::fmt::Display::fmt(data, f)
+                    }
+                }
+            }
+        }
+
         /// Helper function for `fmt_rdata()`.
         ///
         /// This function contains the part of `fmt_rdata()` that needs to
@@ -17,8 +17,15 @@
 //! [`Rtype`]: ../iana/enum.Rtype.html
 
 pub mod rfc1035;
+pub mod rfc1183;
+pub mod rfc1712;
+pub mod rfc2535;
 pub mod rfc2782;
 pub mod rfc3596;
+pub mod rfc4025;
+pub mod rfc4034;
+pub mod rfc4398;
+pub mod rfc8005;
 
 #[macro_use] mod macros;
 mod generic;
@@ -31,39 +38,64 @@ use ::bits::{CharStrBuf, DNameBuf};
 // Include all record types that can occur in master files. Place the name of
 // the variant (identical to the type name) on the left side of the double
 // arrow and the name of the type on the right. If the type is generic, use
-// the owned version.
+// the owned version. After a vertical bar, add the type used for parsed
+// record data from the parsed module below with a lifetime of 'a if needed.
 //
-// The macro creates the re-export of the record data type.
+// The macro creates the re-export of the record data type as well as the
+// AllRecordData enum used for parsed record data.
 master_types!{
     rfc1035::{
-        A => A,
-        Cname => Cname<DNameBuf>,
-        Hinfo => Hinfo<CharStrBuf>,
-        Mb => Mb<DNameBuf>,
-        Md => Md<DNameBuf>,
-        Mf => Mf<DNameBuf>,
-        Mg => Mg<DNameBuf>,
-        Minfo => Minfo<DNameBuf>,
-        Mr => Mr<DNameBuf>,
-        Mx => Mx<DNameBuf>,
-        Ns => Ns<DNameBuf>,
-        Ptr => Ptr<DNameBuf>,
-        Soa => Soa<DNameBuf>,
-        Txt => Txt<Vec<u8>>,
-        Wks => Wks<rfc1035::WksBitmapBuf>,
+        A => A | parsed::A,
+        Cname => Cname<DNameBuf> | parsed::Cname<'a>,
+        Hinfo => Hinfo<CharStrBuf> | parsed::Hinfo<'a>,
+        Mb => Mb<DNameBuf> | parsed::Mb<'a>,
+        Md => Md<DNameBuf> | parsed::Md<'a>,
+        Mf => Mf<DNameBuf> | parsed::Mf<'a>,
+        Mg => Mg<DNameBuf> | parsed::Mg<'a>,
+        Minfo => Minfo<DNameBuf> | parsed::Minfo<'a>,
+        Mr => Mr<DNameBuf> | parsed::Mr<'a>,
+        Mx => Mx<DNameBuf> | parsed::Mx<'a>,
+        Ns => Ns<DNameBuf> | parsed::Ns<'a>,
+        Null => Null<Vec<u8>> | parsed::Null<'a>,
+        Ptr => Ptr<DNameBuf> | parsed::Ptr<'a>,
+        Soa => Soa<DNameBuf> | parsed::Soa<'a>,
+        Txt => Txt<Vec<u8>> | parsed::Txt<'a>,
+        Wks => Wks<rfc1035::WksBitmapBuf> | parsed::Wks<'a>,
+    }
+    rfc1183::{
+        Afsdb => Afsdb<DNameBuf> | parsed::Afsdb<'a>,
+        Rt => Rt<DNameBuf> | parsed::Rt<'a>,
+    }
+    rfc1712::{
+        Gpos => Gpos<CharStrBuf> | parsed::Gpos<'a>,
+    }
+    rfc2535::{
+        Key => Key<Vec<u8>> | parsed::Key<'a>,
+        Sig => Sig<DNameBuf, Vec<u8>> | parsed::Sig<'a>,
     }
     rfc2782::{
-        Srv => Srv<DNameBuf>,
+        Srv => Srv<DNameBuf> | parsed::Srv<'a>,
     }
     rfc3596::{
-        Aaaa => Aaaa,
+        Aaaa => Aaaa | parsed::Aaaa,
+    }
+    rfc4025::{
+        Ipseckey => Ipseckey<DNameBuf, Vec<u8>> | parsed::Ipseckey<'a>,
+    }
+    rfc4034::{
+        Dnskey => Dnskey<Vec<u8>> | parsed::Dnskey<'a>,
+    }
+    rfc4398::{
+        Cert => Cert<Vec<u8>> | parsed::Cert<'a>,
+    }
+    rfc8005::{
+        Hip => Hip<DNameBuf, Vec<u8>> | parsed::Hip<'a>,
     }
 }
 
 // The pseudo_types! macro (defined in self::macros) creates the re-exports
 // for all the types not part of master_types! above.
 pseudo_types!{
-    rfc1035::{Null};
     //rfc6891::{Opt};
 }
 
@@ -98,7 +130,14 @@ pub fn fmt_rdata(rtype: ::iana::Rtype, parser: &mut ::bits::Parser,
 /// Use the types from this module when working with wire format DNS messages.
 pub mod parsed {
     pub use super::rfc1035::parsed::*;
+    pub use super::rfc1183::parsed::*;
+    pub use super::rfc1712::parsed::*;
+    pub use super::rfc2535::parsed::*;
     pub use super::rfc3596::Aaaa;
+    pub use super::rfc4025::parsed::*;
+    pub use super::rfc4034::parsed::*;
+    pub use super::rfc4398::parsed::*;
+    pub use super::rfc8005::parsed::*;
     pub type Srv<'a> = super::rfc2782::Srv<::bits::ParsedDName<'a>>;
 }
 
@@ -112,6 +151,50 @@ pub mod parsed {
 /// or if you are constructing your own values.
 pub mod owned {
     pub use super::rfc1035::owned::*;
+    pub use super::rfc1183::owned::*;
+    pub use super::rfc1712::owned::*;
+    pub use super::rfc2535::owned::*;
     pub use super::rfc3596::Aaaa;
+    pub use super::rfc4025::owned::*;
+    pub use super::rfc4034::owned::*;
+    pub use super::rfc4398::owned::*;
+    pub use super::rfc8005::owned::*;
     pub type Srv = super::rfc2782::Srv<::bits::DNameBuf>;
 }
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use ::bits::{Composer, ComposeMode, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::Rtype;
+    use super::*;
+
+    fn round_trip(rtype: Rtype, data: &[u8]) -> AllRecordData {
+        let mut parser = Parser::new(data);
+        let rdata = AllRecordData::parse(rtype, &mut parser).unwrap()
+                                  .unwrap();
+        assert_eq!(parser.remaining(), 0);
+        assert_eq!(rdata.rtype(), rtype);
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        rdata.compose(&mut composer).unwrap();
+        assert_eq!(composer.finish(), data);
+        rdata
+    }
+
+    #[test]
+    fn all_record_data() {
+        match round_trip(Rtype::A, b"\xc0\x00\x02\x01") {
+            AllRecordData::A(a) => {
+                assert_eq!(a, A::from_octets(192, 0, 2, 1))
+            }
+            _ => panic!("not an A record")
+        }
+        match round_trip(Rtype::Int(65280), b"\x01\x02\x03") {
+            AllRecordData::Unknown(_) => { }
+            _ => panic!("not unknown")
+        }
+    }
+}
@@ -14,7 +14,7 @@ use ::bits::name::{DName, DNameBuf, DNameSlice, ParsedDName};
 use ::bits::parse::{Parser, ParseError, ParseResult};
 use ::bits::rdata::{ParsedRecordData, RecordData};
 use ::iana::Rtype;
-use ::master::{Scanner, ScanResult, SyntaxError};
+use ::master::{ScanError, Scanner, ScanResult, SyntaxError};
 use ::utils::netdb::{ProtoEnt, ServEnt};
 
 
@@ -64,6 +64,11 @@ macro_rules! dname_type {
                                         -> ComposeResult<()> {
                 self.$field.compose_compressed(target)
             }
+
+            fn compose_canonical<C: AsMut<Composer>>(&self, target: C)
+                                                     -> ComposeResult<()> {
+                self.$field.compose_canonical(target)
+            }
         }
 
         impl<'a> ParsedRecordData<'a> for $target<ParsedDName<'a>> {
@@ -362,6 +367,12 @@ impl<N: DName> RecordData for Minfo<N> {
         try!(self.rmailbx.compose(target.as_mut()));
         self.emailbx.compose(target.as_mut())
     }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, mut target: C)
+                                             -> ComposeResult<()> {
+        try!(self.rmailbx.compose_canonical(target.as_mut()));
+        self.emailbx.compose_canonical(target.as_mut())
+    }
 }
 
 impl<'a> ParsedRecordData<'a> for Minfo<ParsedDName<'a>> {
@@ -448,6 +459,12 @@ impl<N: DName> RecordData for Mx<N> {
         try!(target.as_mut().compose_u16(self.preference));
         self.exchange.compose(target)
     }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, mut target: C)
+                                             -> ComposeResult<()> {
+        try!(target.as_mut().compose_u16(self.preference));
+        self.exchange.compose_canonical(target)
+    }
 }
 
 impl<'a> ParsedRecordData<'a> for Mx<ParsedDName<'a>> {
@@ -479,8 +496,9 @@ dname_type!(Ns, Ns, nsdname);
 
 /// Null record data.
 ///
-/// Null records can contain whatever data. They are experimental and not
-/// allowed in master files.
+/// Null records can contain whatever data of up to 65535 octets. They are
+/// experimental and have no presentation format of their own. In master
+/// files, they can only appear in the generic format defined in RFC 3597.
 ///
 /// The Null record type is defined in RFC 1035, section 3.3.10.
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
@@ -507,6 +525,13 @@ impl<'a> Null<&'a [u8]> {
     }
 }
 
+impl Null<Vec<u8>> {
+    pub fn scan<S: Scanner>(scanner: &mut S, _origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        ::rdata::generic::scan(scanner).map(Null::new)
+    }
+}
+
 impl<D: AsRef<[u8]>> RecordData for Null<D> {
     fn rtype(&self) -> Rtype { Rtype::Null }
 
@@ -525,16 +550,12 @@ impl<'a> ParsedRecordData<'a> for Null<&'a [u8]> {
 
 impl<D: AsRef<[u8]>> fmt::Display for Null<D> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        try!(write!(f, "\\# {} ", self.data().len()));
-        let mut iter = self.data().iter();
-        loop {
-            for _ in 0..8 {
-                match iter.next() {
-                    Some(ch) => try!(write!(f, "{:02x}", ch)),
-                    None => return Ok(()),
-                }
-            }
+        try!(write!(f, "\\# {}", self.data().len()));
+        if !self.data().is_empty() {
+            try!(f.write_str(" "));
+            try!(::rdata::generic::fmt(self.data(), f));
         }
+        Ok(())
     }
 }
 
@@ -652,6 +673,18 @@ impl<N: DName> RecordData for Soa<N> {
         try!(self.minimum.compose(target.as_mut()));
         Ok(())
     }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, mut target: C)
+                                             -> ComposeResult<()> {
+        try!(self.mname.compose_canonical(target.as_mut()));
+        try!(self.rname.compose_canonical(target.as_mut()));
+        try!(self.serial.compose(target.as_mut()));
+        try!(self.refresh.compose(target.as_mut()));
+        try!(self.retry.compose(target.as_mut()));
+        try!(self.expire.compose(target.as_mut()));
+        try!(self.minimum.compose(target.as_mut()));
+        Ok(())
+    }
 }
 
 impl<'a> ParsedRecordData<'a> for Soa<ParsedDName<'a>> {
@@ -725,7 +758,7 @@ impl<'a> Txt<&'a [u8]> {
         let bytes = try!(parser.parse_bytes(len));
         let mut tmp = bytes;
         while !tmp.is_empty() {
-            let len = tmp[0] as usize;
+            let len = tmp[0] as usize + 1;
             if len > tmp.len() {
                 return Err(ParseError::FormErr)
             }
@@ -863,6 +896,15 @@ impl<B: AsRef<WksBitmap>> Wks<B> {
         self.bitmap.as_ref().serves(port)
     }
 
+    /// Returns whether the bit for `port` is set in the bitmap.
+    ///
+    /// This is the same as [`serves()`].
+    ///
+    /// [`serves()`]: #method.serves
+    pub fn has_port(&self, port: u16) -> bool {
+        self.serves(port)
+    }
+
     /// Returns an iterator over the served ports.
     pub fn iter(&self) -> WksIter {
         self.bitmap.as_ref().iter()
@@ -899,19 +941,29 @@ impl Wks<WksBitmapBuf> {
         }));
 
         let mut bitmap = WksBitmapBuf::new();
-        while let Ok(()) = scanner.scan_str_phrase(|s| {
-            if let Some(ent) = ServEnt::by_name(s) {
-                bitmap.set_serves(ent.port, true);
-                Ok(())
-            }
-            else if let Ok(number) = u16::from_str_radix(s, 10) {
-                bitmap.set_serves(number, true);
-                Ok(())
-            }
-            else {
-                Err(SyntaxError::UnknownServ(s.into()))
+        loop {
+            let res = scanner.scan_str_phrase(|s| {
+                if let Some(ent) = ServEnt::by_name(s) {
+                    bitmap.set_serves(ent.port, true);
+                    Ok(())
+                }
+                else if let Ok(number) = u16::from_str_radix(s, 10) {
+                    bitmap.set_serves(number, true);
+                    Ok(())
+                }
+                else {
+                    Err(SyntaxError::UnknownServ(s.into()))
+                }
+            });
+            match res {
+                Ok(()) => { }
+                Err(ScanError::Syntax(SyntaxError::Unexpected(_), _)) |
+                Err(ScanError::Syntax(SyntaxError::UnexpectedEof, _)) => {
+                    break
+                }
+                Err(err) => return Err(err)
             }
-        }) { }
+        }
         Ok(Self::new(a.addr(), proto, bitmap))
     }
 }
@@ -970,19 +1022,29 @@ impl WksBitmap {
 
     /// Returns whether a certain service is being provided.
     pub fn serves(&self, port: u16) -> bool {
-        let (octet, bit) = WksBitmap::port_location(port);
+        let (octet, mask) = WksBitmap::port_location(port);
         match self.inner.get(octet) {
-            Some(x) => (x >> bit) > 0,
+            Some(x) => x & mask != 0,
             None => false
         }
     }
 
+    /// Returns whether the bit for `port` is set.
+    ///
+    /// This is the same as [`serves()`].
+    ///
+    /// [`serves()`]: #method.serves
+    pub fn has_port(&self, port: u16) -> bool {
+        self.serves(port)
+    }
+
     /// Translates a port number to where it’ll be in the bitmap.
     ///
-    /// Returns a pair of the index in the bytes slice and the bit number in
-    /// that slice.
-    fn port_location(port: u16) -> (usize, usize) {
-        ((port / 8) as usize, (port % 8) as usize)
+    /// Returns a pair of the index in the bytes slice and the mask for the
+    /// bit in that octet. As per RFC 1035, the first bit of the bitmap,
+    /// i.e., the most significant bit of the first octet, is for port 0.
+    fn port_location(port: u16) -> (usize, u8) {
+        ((port / 8) as usize, 0x80 >> (port % 8))
     }
 }
 
@@ -1045,15 +1107,15 @@ impl WksBitmapBuf {
 
     /// Enables or disables the given service.
     pub fn set_serves(&mut self, port: u16, enable: bool) {
-        let (octet, bit) = WksBitmap::port_location(port);
+        let (octet, mask) = WksBitmap::port_location(port);
         if self.inner.len() <= octet {
             self.inner.resize(octet + 1, 0);
         }
         if enable {
-            self.inner[octet] |= 1 << bit
+            self.inner[octet] |= mask
         }
         else {
-            self.inner[octet] &= 0xFF ^ (1 << bit)
+            self.inner[octet] &= !mask
         }
     }
 }
@@ -1111,7 +1173,7 @@ impl<'a> WksIter<'a> {
     }
 
     fn serves(&self) -> bool {
-        (self.bitmap[self.octet] >> self.bit) > 0
+        self.bitmap[self.octet] & (0x80 >> self.bit) != 0
     }
 }
 
@@ -1122,11 +1184,13 @@ impl<'a> Iterator for WksIter<'a> {
         loop {
             if self.octet >= self.bitmap.len() { return None }
             else {
-                if self.serves() {
-                    return Some((self.octet * 8 + self.bit) as u16)
+                let res = if self.serves() {
+                    Some((self.octet * 8 + self.bit) as u16)
                 }
+                else { None };
                 if self.bit == 7 { self.octet += 1; self.bit = 0 }
                 else { self.bit += 1 }
+                if res.is_some() { return res }
             }
         }
     }
@@ -1149,6 +1213,7 @@ pub mod parsed {
     pub type Mr<'a> = super::Mr<ParsedDName<'a>>;
     pub type Mx<'a> = super::Mx<ParsedDName<'a>>;
     pub type Ns<'a> = super::Ns<ParsedDName<'a>>;
+    pub type Null<'a> = super::Null<&'a [u8]>;
     pub type Ptr<'a> = super::Ptr<ParsedDName<'a>>;
     pub type Soa<'a> = super::Soa<ParsedDName<'a>>;
     pub type Txt<'a> = super::Txt<&'a [u8]>;
@@ -1169,8 +1234,117 @@ pub mod owned {
     pub type Mr = super::Mr<DNameBuf>;
     pub type Mx = super::Mx<DNameBuf>;
     pub type Ns = super::Ns<DNameBuf>;
+    pub type Null = super::Null<Vec<u8>>;
     pub type Ptr = super::Ptr<DNameBuf>;
     pub type Soa = super::Soa<DNameBuf>;
     pub type Txt = super::Txt<DNameBuf>;
     pub type Wks = super::Wks<DNameBuf>;
 }
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use ::bits::{ComposeMode, Composer, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::Rtype;
+    use ::master::bufscanner::BufScanner;
+    use super::*;
+
+    #[test]
+    fn null_round_trip() {
+        let payload: Vec<u8> = (0..300).map(|i| (i * 7) as u8).collect();
+        let null = Null::new(payload.clone());
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        null.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(wire, payload);
+        let parsed = Null::parse(Rtype::Null, &mut Parser::new(&wire))
+                          .unwrap().unwrap();
+        assert_eq!(parsed.data(), &payload[..]);
+
+        let text = format!("{}\n", null);
+        assert!(text.starts_with("\\# 300 0007 0e15 "));
+        let scanned = Null::scan(&mut BufScanner::create(text.as_bytes()),
+                                 None).unwrap();
+        assert_eq!(scanned, null);
+
+        assert_eq!(format!("{}", Null::new(b"")), "\\# 0");
+    }
+
+    #[test]
+    fn minfo_round_trip() {
+        use std::str::FromStr;
+
+        let minfo = Minfo::new(DNameBuf::from_str("list.example.com.")
+                                        .unwrap(),
+                               DNameBuf::from_str("errors.example.com.")
+                                        .unwrap());
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        minfo.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(&wire[..], &b"\x04list\x07example\x03com\x00\
+                                 \x06errors\x07example\x03com\x00"[..]);
+        let parsed = Minfo::parse(Rtype::Minfo, &mut Parser::new(&wire))
+                           .unwrap().unwrap();
+        assert_eq!(parsed.rmailbx(), minfo.rmailbx());
+        assert_eq!(parsed.emailbx(), minfo.emailbx());
+        assert!(Minfo::parse(Rtype::Mx, &mut Parser::new(&wire)).unwrap()
+                      .is_none());
+
+        let text = format!("{}\n", minfo);
+        assert_eq!(text, "list.example.com. errors.example.com.\n");
+        let scanned = Minfo::scan(&mut BufScanner::create(text.as_bytes()),
+                                  None).unwrap();
+        assert_eq!(scanned, minfo);
+    }
+
+    #[test]
+    fn compose_canonical_vec() {
+        let name = DNameBuf::from_str("Mail.Example.COM.").unwrap();
+        let mx = Mx::new(10, name);
+        let mut buf = b"prefix".to_vec();
+        mx.compose_canonical_vec(&mut buf).unwrap();
+        assert_eq!(&buf[..], &b"prefix\x00\x0a\
+                                \x04mail\x07example\x03com\x00"[..]);
+
+        let txt = Txt::new(b"\x0bHello World".to_vec());
+        let mut buf = Vec::new();
+        txt.compose_canonical_vec(&mut buf).unwrap();
+        assert_eq!(&buf[..], b"\x0bHello World");
+    }
+
+    #[test]
+    fn wks_round_trip() {
+        let text = b"192.0.2.1 tcp smtp 80\n";
+        let scanned = Wks::scan(&mut BufScanner::create(&text[..]),
+                                None).unwrap();
+        assert_eq!(scanned.protocol(), 6);
+        assert!(scanned.has_port(25));
+        assert!(scanned.has_port(80));
+        assert!(!scanned.has_port(24));
+        assert!(!scanned.has_port(81));
+        assert_eq!(scanned.iter().collect::<Vec<_>>(), vec![25, 80]);
+        assert_eq!(format!("{}", scanned), "192.0.2.1 6 25 80");
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(&wire[..5], b"\xc0\x00\x02\x01\x06");
+        assert_eq!(wire.len(), 5 + 11);
+        assert_eq!(wire[5 + 3], 0x40);
+        assert_eq!(wire[5 + 10], 0x80);
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Wks::parse(Rtype::Wks, &mut parser).unwrap().unwrap();
+        assert_eq!(parser.remaining(), 0);
+        assert_eq!(parsed.address(), scanned.address());
+        assert_eq!(parsed.iter().collect::<Vec<_>>(), vec![25, 80]);
+        assert_eq!(format!("{}", parsed), format!("{}", scanned));
+
+        assert!(Wks::scan(&mut BufScanner::create(&b"192.0.2.1 6 bogus\n"[..]),
+                          None).is_err());
+    }
+}
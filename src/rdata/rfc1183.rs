@@ -0,0 +1,260 @@
+//! Record data from [RFC 1183].
+//!
+//! This RFC defines a number of experimental record types. Currently, only
+//! the Afsdb and Rt record types are implemented. Both are rarely used
+//! today but may still be found in older zones.
+//!
+//! [RFC 1183]: https://tools.ietf.org/html/rfc1183
+
+use std::fmt;
+use ::bits::{Composer, ComposeResult, DName, DNameBuf, DNameSlice,
+             ParsedDName, ParsedRecordData, Parser, ParseResult,
+             RecordData};
+use ::iana::Rtype;
+use ::master::{Scanner, ScanResult};
+
+
+//------------ Afsdb ---------------------------------------------------------
+
+/// Afsdb record data.
+///
+/// The Afsdb record provides the location of an AFS database server or a
+/// DCE authenticated name server for the cell or DCE cell given by the
+/// owner name. The subtype distinguishes between these two: a value of 1
+/// denotes an AFS version 3.0 volume location server, a value of 2 a DCE
+/// authenticated name server.
+///
+/// The Afsdb record type is defined in RFC 1183, section 1.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Afsdb<N: DName> {
+    subtype: u16,
+    hostname: N,
+}
+
+impl<N: DName> Afsdb<N> {
+    /// Creates new Afsdb record data from the components.
+    pub fn new(subtype: u16, hostname: N) -> Self {
+        Afsdb { subtype: subtype, hostname: hostname }
+    }
+
+    /// The subtype of the server.
+    pub fn subtype(&self) -> u16 {
+        self.subtype
+    }
+
+    /// The name of the host running the server.
+    pub fn hostname(&self) -> &N {
+        &self.hostname
+    }
+}
+
+impl<'a> Afsdb<ParsedDName<'a>> {
+    fn parse_always(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        Ok(Self::new(try!(parser.parse_u16()),
+                     try!(ParsedDName::parse(parser))))
+    }
+}
+
+impl Afsdb<DNameBuf> {
+    /// Scans Afsdb record data from master data.
+    ///
+    /// A relative hostname is made absolute using `origin`.
+    pub fn scan<S: Scanner>(scanner: &mut S, origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        Ok(Self::new(try!(scanner.scan_u16()),
+                     try!(DNameBuf::scan(scanner, origin))))
+    }
+}
+
+impl<N: DName> RecordData for Afsdb<N> {
+    fn rtype(&self) -> Rtype { Rtype::Afsdb }
+
+    fn compose<C: AsMut<Composer>>(&self, mut target: C) -> ComposeResult<()> {
+        try!(target.as_mut().compose_u16(self.subtype));
+        self.hostname.compose(target)
+    }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, mut target: C)
+                                             -> ComposeResult<()> {
+        try!(target.as_mut().compose_u16(self.subtype));
+        self.hostname.compose_canonical(target)
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Afsdb<ParsedDName<'a>> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Afsdb { Afsdb::parse_always(parser).map(Some) }
+        else { Ok(None) }
+    }
+}
+
+impl<N: DName + fmt::Display> fmt::Display for Afsdb<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.subtype, self.hostname)
+    }
+}
+
+
+//------------ Rt ------------------------------------------------------------
+
+/// Rt record data.
+///
+/// The Rt (route through) record names an intermediate host that can be
+/// used to reach the owner name if it has no direct connection to the
+/// network. If there are several Rt records for an owner, the preference
+/// determines the order in which they should be tried.
+///
+/// The Rt record type is defined in RFC 1183, section 3.3.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Rt<N: DName> {
+    preference: u16,
+    intermediate: N,
+}
+
+impl<N: DName> Rt<N> {
+    /// Creates new Rt record data from the components.
+    pub fn new(preference: u16, intermediate: N) -> Self {
+        Rt { preference: preference, intermediate: intermediate }
+    }
+
+    /// The preference for this record.
+    ///
+    /// Lower values are preferred.
+    pub fn preference(&self) -> u16 {
+        self.preference
+    }
+
+    /// The name of the intermediate host.
+    pub fn intermediate(&self) -> &N {
+        &self.intermediate
+    }
+}
+
+impl<'a> Rt<ParsedDName<'a>> {
+    fn parse_always(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        Ok(Self::new(try!(parser.parse_u16()),
+                     try!(ParsedDName::parse(parser))))
+    }
+}
+
+impl Rt<DNameBuf> {
+    /// Scans Rt record data from master data.
+    ///
+    /// A relative intermediate host name is made absolute using `origin`.
+    pub fn scan<S: Scanner>(scanner: &mut S, origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        Ok(Self::new(try!(scanner.scan_u16()),
+                     try!(DNameBuf::scan(scanner, origin))))
+    }
+}
+
+impl<N: DName> RecordData for Rt<N> {
+    fn rtype(&self) -> Rtype { Rtype::Rt }
+
+    fn compose<C: AsMut<Composer>>(&self, mut target: C) -> ComposeResult<()> {
+        try!(target.as_mut().compose_u16(self.preference));
+        self.intermediate.compose(target)
+    }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, mut target: C)
+                                             -> ComposeResult<()> {
+        try!(target.as_mut().compose_u16(self.preference));
+        self.intermediate.compose_canonical(target)
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Rt<ParsedDName<'a>> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Rt { Rt::parse_always(parser).map(Some) }
+        else { Ok(None) }
+    }
+}
+
+impl<N: DName + fmt::Display> fmt::Display for Rt<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.preference, self.intermediate)
+    }
+}
+
+
+//============ Type Aliases =================================================
+
+pub mod parsed {
+    use ::bits::ParsedDName;
+
+    pub type Afsdb<'a> = super::Afsdb<ParsedDName<'a>>;
+    pub type Rt<'a> = super::Rt<ParsedDName<'a>>;
+}
+
+pub mod owned {
+    use ::bits::DNameBuf;
+
+    pub type Afsdb = super::Afsdb<DNameBuf>;
+    pub type Rt = super::Rt<DNameBuf>;
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::bits::{ComposeMode, Composer, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::Rtype;
+    use ::master::bufscanner::BufScanner;
+
+    #[test]
+    fn afsdb_round_trip() {
+        let scanned = Afsdb::scan(
+            &mut BufScanner::create(&b"1 afs.Example.com.\n"[..]),
+            None
+        ).unwrap();
+        assert_eq!(scanned.subtype(), 1);
+        assert_eq!(format!("{}", scanned), "1 afs.Example.com.");
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(wire, b"\x00\x01\x03afs\x07Example\x03com\x00");
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Afsdb::parse(Rtype::Afsdb, &mut parser)
+                           .unwrap().unwrap();
+        assert_eq!(parsed.subtype(), 1);
+        assert_eq!(parsed.hostname().to_cow().into_owned(),
+                   *scanned.hostname());
+        assert_eq!(parser.remaining(), 0);
+        assert!(Afsdb::parse(Rtype::Mx, &mut Parser::new(&wire))
+                      .unwrap().is_none());
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose_canonical(&mut composer).unwrap();
+        assert_eq!(composer.finish(),
+                   b"\x00\x01\x03afs\x07example\x03com\x00");
+    }
+
+    #[test]
+    fn rt_round_trip() {
+        let scanned = Rt::scan(
+            &mut BufScanner::create(&b"10 relay.example.com.\n"[..]),
+            None
+        ).unwrap();
+        assert_eq!(scanned.preference(), 10);
+        assert_eq!(format!("{}", scanned), "10 relay.example.com.");
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(wire, b"\x00\x0a\x05relay\x07example\x03com\x00");
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Rt::parse(Rtype::Rt, &mut parser).unwrap().unwrap();
+        assert_eq!(parsed.preference(), 10);
+        assert_eq!(parsed.intermediate().to_cow().into_owned(),
+                   *scanned.intermediate());
+        assert_eq!(parser.remaining(), 0);
+    }
+}
@@ -0,0 +1,192 @@
+//! Record data from [RFC 1712].
+//!
+//! This RFC defines the Gpos record type.
+//!
+//! [RFC 1712]: https://tools.ietf.org/html/rfc1712
+
+use std::fmt;
+use std::str::{self, FromStr};
+use ::bits::{CharStr, CharStrBuf, Composer, ComposeResult, DNameSlice,
+             ParsedRecordData, Parser, ParseResult, RecordData};
+use ::iana::Rtype;
+use ::master::{ScanError, Scanner, ScanResult, SyntaxError};
+
+
+//------------ Gpos ----------------------------------------------------------
+
+/// Gpos record data.
+///
+/// Gpos records store the geographical position of a host as its
+/// longitude, latitude, and altitude. Each of these is a character string
+/// containing a decimal number. Longitude and latitude are given in
+/// degrees with positive values east and north, respectively. The altitude
+/// is given in meters above sea level.
+///
+/// The Gpos record type is defined in RFC 1712. It has largely been
+/// replaced by the Loc record type.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Gpos<C: AsRef<CharStr>> {
+    longitude: C,
+    latitude: C,
+    altitude: C,
+}
+
+impl<C: AsRef<CharStr>> Gpos<C> {
+    /// Creates new Gpos record data from its components.
+    ///
+    /// The components are not checked to contain valid numbers.
+    pub fn new(longitude: C, latitude: C, altitude: C) -> Self {
+        Gpos {
+            longitude: longitude,
+            latitude: latitude,
+            altitude: altitude
+        }
+    }
+
+    /// The longitude of the host in degrees.
+    pub fn longitude(&self) -> &C { &self.longitude }
+
+    /// The latitude of the host in degrees.
+    pub fn latitude(&self) -> &C { &self.latitude }
+
+    /// The altitude of the host in meters.
+    pub fn altitude(&self) -> &C { &self.altitude }
+}
+
+impl<'a> Gpos<&'a CharStr> {
+    fn parse_always(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        Ok(Gpos::new(try!(CharStr::parse(parser)),
+                     try!(CharStr::parse(parser)),
+                     try!(CharStr::parse(parser))))
+    }
+}
+
+impl Gpos<CharStrBuf> {
+    /// Scans Gpos record data from master data.
+    ///
+    /// All three components need to be decimal numbers. The longitude has
+    /// to be between -180 and 180, the latitude between -90 and 90.
+    pub fn scan<S: Scanner>(scanner: &mut S, _origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        let longitude = try!(scan_number(scanner, 180.,
+                                         b"longitude between -180 and 180"));
+        let latitude = try!(scan_number(scanner, 90.,
+                                        b"latitude between -90 and 90"));
+        let altitude = try!(scan_number(scanner, ::std::f64::INFINITY,
+                                        b"altitude in meters"));
+        Ok(Gpos::new(longitude, latitude, altitude))
+    }
+}
+
+impl<C: AsRef<CharStr>> RecordData for Gpos<C> {
+    fn rtype(&self) -> Rtype { Rtype::Gpos }
+
+    fn compose<T: AsMut<Composer>>(&self, mut target: T)
+                                   -> ComposeResult<()> {
+        try!(self.longitude.as_ref().compose(target.as_mut()));
+        try!(self.latitude.as_ref().compose(target.as_mut()));
+        self.altitude.as_ref().compose(target.as_mut())
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Gpos<&'a CharStr> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Gpos { Gpos::parse_always(parser).map(Some) }
+        else { Ok(None) }
+    }
+}
+
+impl<C: AsRef<CharStr>> fmt::Display for Gpos<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.longitude.as_ref(),
+               self.latitude.as_ref(), self.altitude.as_ref())
+    }
+}
+
+
+//------------ Helper Functions ----------------------------------------------
+
+/// Scans a character string containing a decimal number.
+///
+/// The absolute value of the number must not be larger than `limit`. If
+/// the string isn’t such a number, the scan fails with an error expecting
+/// `expected`.
+fn scan_number<S: Scanner>(scanner: &mut S, limit: f64, expected: &[u8])
+                           -> ScanResult<CharStrBuf> {
+    let res = try!(CharStrBuf::scan(scanner));
+    let valid = !res.is_empty() && res.iter().all(|&ch| {
+        ch.is_ascii_digit() || ch == b'-' || ch == b'+' || ch == b'.'
+    }) && match str::from_utf8(res.as_ref()).map(f64::from_str) {
+        Ok(Ok(value)) => value.abs() <= limit,
+        _ => false
+    };
+    if valid {
+        Ok(res)
+    }
+    else {
+        Err(ScanError::Syntax(SyntaxError::Expected(expected.to_vec()),
+                              scanner.pos()))
+    }
+}
+
+
+//============ Type Aliases =================================================
+
+pub mod parsed {
+    use ::bits::CharStr;
+
+    pub type Gpos<'a> = super::Gpos<&'a CharStr>;
+}
+
+pub mod owned {
+    use ::bits::CharStrBuf;
+
+    pub type Gpos = super::Gpos<CharStrBuf>;
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::bits::{ComposeMode, Composer, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::Rtype;
+    use ::master::bufscanner::BufScanner;
+
+    #[test]
+    fn round_trip() {
+        let text = b"-122.4194 37.7749 16.0\n";
+        let scanned = Gpos::scan(&mut BufScanner::create(&text[..]),
+                                 None).unwrap();
+        assert_eq!(scanned.longitude().as_bytes(), b"-122.4194");
+        assert_eq!(format!("{}\n", scanned).as_bytes(), &text[..]);
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(&wire[..], &b"\x09-122.4194\x0737.7749\x0416.0"[..]);
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Gpos::parse(Rtype::Gpos, &mut parser).unwrap().unwrap();
+        assert_eq!(parser.remaining(), 0);
+        assert_eq!(parsed.latitude().as_bytes(), b"37.7749");
+        assert_eq!(format!("{}", parsed), format!("{}", scanned));
+    }
+
+    #[test]
+    fn scan_ranges() {
+        fn scan(text: &[u8]) -> ScanResult<owned::Gpos> {
+            Gpos::scan(&mut BufScanner::create(text), None)
+        }
+
+        assert!(scan(b"180 -90 -10\n").is_ok());
+        assert!(scan(b"180.1 0 0\n").is_err());
+        assert!(scan(b"0 -90.5 0\n").is_err());
+        assert!(scan(b"0 0 high\n").is_err());
+        assert!(scan(b"0 0 1e3\n").is_err());
+        assert!(scan(b"0 0 inf\n").is_err());
+    }
+}
@@ -0,0 +1,711 @@
+//! Record data from [RFC 2535].
+//!
+//! This RFC defines the original DNSSEC record types Key and Sig. They have
+//! since been replaced by Dnskey and Rrsig for DNSSEC but are still used
+//! for transaction signatures via SIG(0) as defined in [RFC 2931].
+//!
+//! [RFC 2535]: https://tools.ietf.org/html/rfc2535
+//! [RFC 2931]: https://tools.ietf.org/html/rfc2931
+
+use std::{error, fmt};
+use std::str::{self, FromStr};
+use ::bits::{Composer, ComposeError, ComposeMode, ComposeResult, DName,
+             DNameBuf, DNameSlice, ParsedDName, ParsedRecordData, Parser,
+             ParseResult, Record, RecordData};
+use ::iana::{Rtype, SecAlg};
+use ::rdata::rfc4034::Dnskey;
+use ::master::{Scanner, ScanResult, SyntaxError, SyntaxResult};
+use ::utils::base64;
+use ::utils::clock::Clock;
+
+
+//------------ Key -----------------------------------------------------------
+
+/// Key record data.
+///
+/// Key records store public keys. Their wire format is identical to that
+/// of Dnskey records but the flags have a different meaning. The two most
+/// significant bits state whether the key may be used for authentication
+/// and confidentiality, respectively. If both are set, the record doesn’t
+/// actually contain a key. The next bits give the kind of entity the key
+/// belongs to. The protocol field states what protocol the key is used
+/// for, with 3 being DNSSEC.
+///
+/// The Key record type is defined in RFC 2535, section 3. Its use was
+/// restricted by RFC 3445.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Key<D: AsRef<[u8]>> {
+    /// The record data in its Dnskey shape.
+    key: Dnskey<D>,
+}
+
+impl<D: AsRef<[u8]>> Key<D> {
+    /// Creates new Key record data from its components.
+    pub fn new(flags: u16, protocol: u8, algorithm: SecAlg, public_key: D)
+               -> Self {
+        Key { key: Dnskey::new(flags, protocol, algorithm, public_key) }
+    }
+
+    /// The flags of the key.
+    pub fn flags(&self) -> u16 { self.key.flags() }
+
+    /// The protocol the key is to be used with.
+    pub fn protocol(&self) -> u8 { self.key.protocol() }
+
+    /// The algorithm of the key.
+    pub fn algorithm(&self) -> SecAlg { self.key.algorithm() }
+
+    /// The raw public key data.
+    pub fn public_key(&self) -> &[u8] { self.key.public_key() }
+
+    /// Returns whether the record states that there is no key.
+    ///
+    /// This is the case if both the ‘no authentication’ and the ‘no
+    /// confidentiality’ flags are set.
+    pub fn is_no_key(&self) -> bool { is_no_key(self.flags()) }
+
+    /// Returns the name type of the key.
+    ///
+    /// This is 0 for a user key, 1 for a zone key, 2 for a host or other
+    /// end entity key, with 3 being reserved.
+    pub fn name_type(&self) -> u8 { ((self.flags() >> 8) & 0x03) as u8 }
+}
+
+impl Key<Vec<u8>> {
+    /// Scans Key record data from master data.
+    ///
+    /// The format is that of Dnskey record data except that the public
+    /// key may be missing if the flags state that there is no key.
+    pub fn scan<S: Scanner>(scanner: &mut S, _origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        // A Key record stating that there is no key may well be empty.
+        Dnskey::scan_with(scanner, is_no_key).map(|key| Key { key: key })
+    }
+}
+
+impl<D: AsRef<[u8]>> RecordData for Key<D> {
+    fn rtype(&self) -> Rtype { Rtype::Key }
+
+    fn compose<C: AsMut<Composer>>(&self, target: C)
+                                   -> ComposeResult<()> {
+        self.key.compose(target)
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Key<&'a [u8]> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Key {
+            Dnskey::parse_always(parser).map(|key| Some(Key { key: key }))
+        }
+        else { Ok(None) }
+    }
+}
+
+impl<D: AsRef<[u8]>> fmt::Display for Key<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.key.fmt(f)
+    }
+}
+
+/// Returns whether Key flags state that there is no key.
+fn is_no_key(flags: u16) -> bool { flags & 0xC000 == 0xC000 }
+
+
+//------------ Sig -----------------------------------------------------------
+
+/// Sig record data.
+///
+/// Sig records contain a signature over either an RRset of the record type
+/// given as the type covered or, if the type covered is zero, over an
+/// entire message. The latter use is called SIG(0) and is defined in RFC
+/// 2931. The layout of the record data is identical to that of Rrsig
+/// records.
+///
+/// The signature expiration and inception times are given in seconds since
+/// the Unix epoch. In master files, they are written as `YYYYMMDDHHmmSS`
+/// in UTC.
+///
+/// The Sig record type is defined in RFC 2535, section 4.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Sig<N: DName, D: AsRef<[u8]>> {
+    type_covered: Rtype,
+    algorithm: SecAlg,
+    labels: u8,
+    original_ttl: u32,
+    expiration: u32,
+    inception: u32,
+    key_tag: u16,
+    signer_name: N,
+    signature: D,
+}
+
+impl<N: DName, D: AsRef<[u8]>> Sig<N, D> {
+    /// Creates new Sig record data from its components.
+    pub fn new(type_covered: Rtype, algorithm: SecAlg, labels: u8,
+               original_ttl: u32, expiration: u32, inception: u32,
+               key_tag: u16, signer_name: N, signature: D) -> Self {
+        Sig {
+            type_covered: type_covered,
+            algorithm: algorithm,
+            labels: labels,
+            original_ttl: original_ttl,
+            expiration: expiration,
+            inception: inception,
+            key_tag: key_tag,
+            signer_name: signer_name,
+            signature: signature
+        }
+    }
+
+    /// The record type covered by the signature.
+    ///
+    /// This is zero for a SIG(0) transaction signature.
+    pub fn type_covered(&self) -> Rtype { self.type_covered }
+
+    /// The algorithm of the signature.
+    pub fn algorithm(&self) -> SecAlg { self.algorithm }
+
+    /// The number of labels of the signed owner name.
+    pub fn labels(&self) -> u8 { self.labels }
+
+    /// The TTL of the signed RRset as it appears in the zone.
+    pub fn original_ttl(&self) -> u32 { self.original_ttl }
+
+    /// The time the signature expires in seconds since the Unix epoch.
+    pub fn expiration(&self) -> u32 { self.expiration }
+
+    /// The time the signature becomes valid in seconds since the Unix epoch.
+    pub fn inception(&self) -> u32 { self.inception }
+
+    /// The key tag of the key used to create the signature.
+    pub fn key_tag(&self) -> u16 { self.key_tag }
+
+    /// The name of the signer.
+    pub fn signer_name(&self) -> &N { &self.signer_name }
+
+    /// The raw signature data.
+    pub fn signature(&self) -> &[u8] { self.signature.as_ref() }
+
+    /// Returns whether this is a SIG(0) transaction signature.
+    pub fn is_sig0(&self) -> bool { self.type_covered.to_int() == 0 }
+
+    /// Returns whether the current time is within the validity period.
+    ///
+    /// The current time is taken from `clock`. Since the times are only 32
+    /// bits, they are compared using serial number arithmetic as required
+    /// by RFC 4034, section 3.1.5.
+    pub fn is_current<C: Clock>(&self, clock: C) -> bool {
+        self.check_time(clock).is_ok()
+    }
+
+    /// Checks the validity period before verifying the signature.
+    ///
+    /// This is the first step when validating a signature. If the
+    /// current time as given by `clock` is outside the validity period,
+    /// the signature must not be accepted and an error stating on which
+    /// side of the period the time lies is returned.
+    pub fn check_time<C: Clock>(&self, clock: C)
+                                -> Result<(), ValidityError> {
+        let now = clock.unix_time() as u32;
+        if (now.wrapping_sub(self.inception) as i32) < 0 {
+            Err(ValidityError::NotYetValid)
+        }
+        else if (self.expiration.wrapping_sub(now) as i32) < 0 {
+            Err(ValidityError::Expired)
+        }
+        else { Ok(()) }
+    }
+
+    /// Returns the data a signature over an RRset is calculated over.
+    ///
+    /// The data consists of the record data of `self` without the
+    /// signature followed by the records of the RRset in canonical form
+    /// and order as described in section 3.1.8.1 of [RFC 4034]. All
+    /// records of `rrset` must have the same owner name, class, and
+    /// record type. Their owner name is converted to lowercase and, if it
+    /// has more labels than given by `self`, replaced with the matching
+    /// wildcard name. Their TTL is replaced with the original TTL of
+    /// `self` and duplicate records are dropped.
+    ///
+    /// Since Rrsig records have the same layout as Sig records, this can
+    /// be used for verifying DNSSEC signatures, too.
+    ///
+    /// The method fails if any of the names involved is relative.
+    ///
+    /// [RFC 4034]: https://tools.ietf.org/html/rfc4034
+    pub fn signing_data<'r, M, R, I>(&self, rrset: I)
+                                     -> ComposeResult<Vec<u8>>
+                        where M: DName + 'r,
+                              R: RecordData + 'r,
+                              I: IntoIterator<Item=&'r Record<M, R>> {
+        let mut res = Composer::new(ComposeMode::Unlimited, false);
+        try!(self.compose_fixed(&mut res));
+        try!(self.signer_name.compose_canonical(&mut res));
+
+        let mut rrset = rrset.into_iter();
+        let first = match rrset.next() {
+            Some(first) => first,
+            None => return Ok(res.finish())
+        };
+        let mut owner = first.name().to_cow().to_lowercase();
+        if !owner.is_absolute() {
+            return Err(ComposeError::RelativeName)
+        }
+        // The label count of the owner doesn’t include the root label.
+        let count = owner.len() - 1;
+        if count > self.labels as usize {
+            let wildcard = {
+                let mut name: &DNameSlice = &owner;
+                for _ in 0..(count - self.labels as usize - 1) {
+                    name = name.parent().unwrap();
+                }
+                name.wildcard_parent().unwrap()
+            };
+            owner = wildcard;
+        }
+
+        let mut data = Vec::new();
+        for record in Some(first).into_iter().chain(rrset) {
+            let mut composer = Composer::new(ComposeMode::Unlimited, false);
+            try!(record.data().compose_canonical(&mut composer));
+            data.push(composer.finish());
+        }
+        data.sort();
+        data.dedup();
+        for item in data {
+            try!(owner.compose(&mut res));
+            try!(first.rtype().compose(&mut res));
+            try!(first.class().compose(&mut res));
+            try!(res.compose_u32(self.original_ttl));
+            if item.len() > ::std::u16::MAX as usize {
+                return Err(ComposeError::Overflow)
+            }
+            try!(res.compose_u16(item.len() as u16));
+            try!(res.compose_bytes(&item));
+        }
+        Ok(res.finish())
+    }
+
+    /// Composes everything but the signer name and signature.
+    fn compose_fixed(&self, target: &mut Composer) -> ComposeResult<()> {
+        try!(target.compose_u16(self.type_covered.to_int()));
+        try!(target.compose_u8(self.algorithm.to_int()));
+        try!(target.compose_u8(self.labels));
+        try!(target.compose_u32(self.original_ttl));
+        try!(target.compose_u32(self.expiration));
+        try!(target.compose_u32(self.inception));
+        target.compose_u16(self.key_tag)
+    }
+}
+
+impl<'a> Sig<ParsedDName<'a>, &'a [u8]> {
+    fn parse_always(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        let type_covered = try!(Rtype::parse(parser));
+        let algorithm = SecAlg::from_int(try!(parser.parse_u8()));
+        let labels = try!(parser.parse_u8());
+        let original_ttl = try!(parser.parse_u32());
+        let expiration = try!(parser.parse_u32());
+        let inception = try!(parser.parse_u32());
+        let key_tag = try!(parser.parse_u16());
+        let signer_name = try!(ParsedDName::parse(parser));
+        let len = parser.remaining();
+        let signature = try!(parser.parse_bytes(len));
+        Ok(Sig::new(type_covered, algorithm, labels, original_ttl,
+                    expiration, inception, key_tag, signer_name, signature))
+    }
+}
+
+impl Sig<DNameBuf, Vec<u8>> {
+    /// Scans Sig record data from master data.
+    ///
+    /// The expiration and inception times are given either in the
+    /// `YYYYMMDDHHmmSS` format or as seconds since the epoch. A relative
+    /// signer name is made absolute using `origin`.
+    pub fn scan<S: Scanner>(scanner: &mut S, origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        // SIG(0) records cover type 0 which isn’t a valid record type
+        // mnemonic, so we allow a plain zero here.
+        let type_covered = try!(scanner.scan_word(|slice| {
+            if slice == b"0" {
+                Ok(Rtype::Int(0))
+            }
+            else {
+                Rtype::from_bytes(slice).ok_or_else(|| {
+                    SyntaxError::Expected(b"record type".to_vec())
+                })
+            }
+        }));
+        let algorithm = try!(scan_secalg(scanner));
+        let labels = try!(scanner.scan_str_phrase(|s| {
+            u8::from_str(s).map_err(Into::into)
+        }));
+        let original_ttl = try!(scanner.scan_u32());
+        let expiration = try!(scanner.scan_phrase(scan_time));
+        let inception = try!(scanner.scan_phrase(scan_time));
+        let key_tag = try!(scanner.scan_u16());
+        let signer_name = try!(DNameBuf::scan(scanner, origin));
+        let signature = try!(scanner.scan_base64_words());
+        Ok(Sig::new(type_covered, algorithm, labels, original_ttl,
+                    expiration, inception, key_tag, signer_name, signature))
+    }
+}
+
+impl<N: DName, D: AsRef<[u8]>> RecordData for Sig<N, D> {
+    fn rtype(&self) -> Rtype { Rtype::Sig }
+
+    fn compose<C: AsMut<Composer>>(&self, mut target: C)
+                                   -> ComposeResult<()> {
+        try!(self.compose_fixed(target.as_mut()));
+        try!(self.signer_name.compose(target.as_mut()));
+        target.as_mut().compose_bytes(self.signature())
+    }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, mut target: C)
+                                             -> ComposeResult<()> {
+        try!(self.compose_fixed(target.as_mut()));
+        try!(self.signer_name.compose_canonical(target.as_mut()));
+        target.as_mut().compose_bytes(self.signature())
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Sig<ParsedDName<'a>, &'a [u8]> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Sig { Sig::parse_always(parser).map(Some) }
+        else { Ok(None) }
+    }
+}
+
+impl<N, D> fmt::Display for Sig<N, D>
+     where N: DName + fmt::Display, D: AsRef<[u8]> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_sig0() {
+            try!(f.write_str("0"));
+        }
+        else {
+            try!(write!(f, "{}", self.type_covered));
+        }
+        try!(write!(f, " {} {} {} ", self.algorithm, self.labels,
+                    self.original_ttl));
+        try!(fmt_time(self.expiration, f));
+        try!(f.write_str(" "));
+        try!(fmt_time(self.inception, f));
+        try!(write!(f, " {} {} ", self.key_tag, self.signer_name));
+        base64::display(self.signature(), f)
+    }
+}
+
+
+//------------ ValidityError -------------------------------------------------
+
+/// The current time is outside the validity period of a signature.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidityError {
+    /// The inception time of the signature lies in the future.
+    NotYetValid,
+
+    /// The expiration time of the signature has passed.
+    Expired,
+}
+
+
+//--- Error
+
+impl error::Error for ValidityError {
+    fn description(&self) -> &str {
+        match *self {
+            ValidityError::NotYetValid => "signature not yet valid",
+            ValidityError::Expired => "signature expired",
+        }
+    }
+}
+
+
+//--- Display
+
+impl fmt::Display for ValidityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        error::Error::description(self).fmt(f)
+    }
+}
+
+
+//------------ Helper Functions ----------------------------------------------
+
+/// Scans a security algorithm.
+fn scan_secalg<S: Scanner>(scanner: &mut S) -> ScanResult<SecAlg> {
+    scanner.scan_str_phrase(|s| {
+        SecAlg::from_str(s).map_err(|_| {
+            SyntaxError::UnknownSecAlg(s.as_bytes().into())
+        })
+    })
+}
+
+/// Scans a signature time.
+///
+/// The time can either be given as a `YYYYMMDDHHmmSS` timestamp in UTC or
+/// as the number of seconds since the Unix epoch (RFC 4034, section 3.2).
+fn scan_time(slice: &[u8]) -> SyntaxResult<u32> {
+    let s = try!(str::from_utf8(slice));
+    if s.len() != 14 {
+        return Ok(try!(u32::from_str(s)))
+    }
+    if !s.bytes().all(|ch| ch.is_ascii_digit()) {
+        return Err(SyntaxError::IllegalInteger)
+    }
+    let field = |range: ::std::ops::Range<usize>| u32::from_str(&s[range]);
+    let (year, month, day) = (try!(field(0..4)), try!(field(4..6)),
+                              try!(field(6..8)));
+    let (hour, minute, second) = (try!(field(8..10)), try!(field(10..12)),
+                                  try!(field(12..14)));
+    if year < 1970 || month < 1 || month > 12 || day < 1
+            || day > days_in_month(year, month)
+            || hour > 23 || minute > 59 || second > 59 {
+        return Err(SyntaxError::IllegalInteger)
+    }
+    let secs = days_from_civil(year, month, day) * 86400
+             + u64::from(hour * 3600 + minute * 60 + second);
+    if secs > u64::from(::std::u32::MAX) {
+        return Err(SyntaxError::IllegalInteger)
+    }
+    Ok(secs as u32)
+}
+
+/// Formats a signature time as `YYYYMMDDHHmmSS`.
+fn fmt_time(time: u32, f: &mut fmt::Formatter) -> fmt::Result {
+    let (year, month, day) = civil_from_days(time / 86400);
+    let secs = time % 86400;
+    write!(f, "{:04}{:02}{:02}{:02}{:02}{:02}", year, month, day,
+           secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+/// Returns the number of days in a month of a year.
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// Returns the number of days since the epoch for a date.
+///
+/// The date must not be before 1970.
+fn days_from_civil(year: u32, month: u32, day: u32) -> u64 {
+    // This is Howard Hinnant’s algorithm, shifting the year to start in
+    // March so that the leap day is at the end.
+    let year = u64::from(if month <= 2 { year - 1 } else { year });
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let month = u64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + u64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Returns year, month, and day for a number of days since the epoch.
+fn civil_from_days(days: u32) -> (u32, u32, u32) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+
+//============ Type Aliases =================================================
+
+pub mod parsed {
+    use ::bits::ParsedDName;
+
+    pub type Key<'a> = super::Key<&'a [u8]>;
+    pub type Sig<'a> = super::Sig<ParsedDName<'a>, &'a [u8]>;
+}
+
+pub mod owned {
+    use ::bits::DNameBuf;
+
+    pub type Key = super::Key<Vec<u8>>;
+    pub type Sig = super::Sig<DNameBuf, Vec<u8>>;
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::bits::{ComposeMode, Composer, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::{Class, Rtype, SecAlg};
+    use ::master::bufscanner::BufScanner;
+    use ::utils::clock::FixedClock;
+
+    #[test]
+    fn key_round_trip() {
+        let scanned = Key::scan(
+            &mut BufScanner::create(&b"512 3 8 AwEAAcFc Zm9v\n"[..]),
+            None
+        ).unwrap();
+        assert_eq!(scanned.flags(), 512);
+        assert_eq!(scanned.name_type(), 2);
+        assert!(!scanned.is_no_key());
+        assert_eq!(scanned.protocol(), 3);
+        assert_eq!(scanned.algorithm(), SecAlg::RsaSha256);
+        assert_eq!(scanned.public_key(), b"\x03\x01\x00\x01\xc1\x5cfoo");
+        assert_eq!(format!("{}", scanned), "512 3 RSASHA256 AwEAAcFcZm9v");
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(&wire[..4], b"\x02\x00\x03\x08");
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Key::parse(Rtype::Key, &mut parser).unwrap().unwrap();
+        assert_eq!(parsed.flags(), scanned.flags());
+        assert_eq!(parsed.public_key(), scanned.public_key());
+        assert!(Key::parse(Rtype::Dnskey, &mut Parser::new(&wire))
+                    .unwrap().is_none());
+
+        let no_key = Key::scan(
+            &mut BufScanner::create(&b"49152 3 8\n"[..]), None
+        ).unwrap();
+        assert!(no_key.is_no_key());
+        assert_eq!(format!("{}", no_key), "49152 3 RSASHA256");
+
+        // Broken key data is an error even if there is no key.
+        assert!(Key::scan(
+            &mut BufScanner::create(&b"49152 3 8 !!!\n"[..]), None
+        ).is_err());
+        assert!(Key::scan(
+            &mut BufScanner::create(&b"512 3 8\n"[..]), None
+        ).is_err());
+    }
+
+    #[test]
+    fn sig_round_trip() {
+        let scanned = Sig::scan(
+            &mut BufScanner::create(
+                &b"A 8 3 3600 20170301000000 1488326400 12345 \
+                   Example.com. Zm9vYmFy\n"[..]
+            ),
+            None
+        ).unwrap();
+        assert_eq!(scanned.type_covered(), Rtype::A);
+        assert_eq!(scanned.labels(), 3);
+        assert_eq!(scanned.expiration(), 1488326400);
+        assert_eq!(scanned.inception(), 1488326400);
+        assert_eq!(scanned.key_tag(), 12345);
+        assert_eq!(scanned.signature(), b"foobar");
+        assert!(!scanned.is_sig0());
+        assert!(scanned.is_current(FixedClock(1488326400)));
+        assert!(!scanned.is_current(FixedClock(1488326399)));
+        assert!(!scanned.is_current(FixedClock(1488326401)));
+        assert!(scanned.check_time(FixedClock(1488326400)).is_ok());
+        assert_eq!(scanned.check_time(FixedClock(1488326399)),
+                   Err(ValidityError::NotYetValid));
+        assert_eq!(scanned.check_time(FixedClock(1488326401)),
+                   Err(ValidityError::Expired));
+        assert_eq!(format!("{}", scanned),
+                   "A RSASHA256 3 3600 20170301000000 20170301000000 \
+                    12345 Example.com. Zm9vYmFy");
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(&wire[..18],
+                   b"\x00\x01\x08\x03\x00\x00\x0e\x10\x58\xb6\x0f\x00\
+                     \x58\xb6\x0f\x00\x30\x39");
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Sig::parse(Rtype::Sig, &mut parser).unwrap().unwrap();
+        assert_eq!(parsed.type_covered(), scanned.type_covered());
+        assert_eq!(parsed.expiration(), scanned.expiration());
+        assert_eq!(parsed.signer_name().to_cow().into_owned(),
+                   *scanned.signer_name());
+        assert_eq!(parsed.signature(), scanned.signature());
+        assert_eq!(parser.remaining(), 0);
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose_canonical(&mut composer).unwrap();
+        assert_eq!(&composer.finish()[18..31], b"\x07example\x03com\x00");
+    }
+
+    #[test]
+    fn sig0() {
+        let scanned = Sig::scan(
+            &mut BufScanner::create(
+                &b"0 8 0 0 20380119031407 19700101000000 4711 \
+                   key.example. Zm9v\n"[..]
+            ),
+            None
+        ).unwrap();
+        assert!(scanned.is_sig0());
+        assert_eq!(scanned.expiration(), 0x7FFF_FFFF);
+        assert!(scanned.is_current(FixedClock(1_500_000_000)));
+        assert_eq!(scanned.inception(), 0);
+        assert_eq!(format!("{}", scanned),
+                   "0 RSASHA256 0 0 20380119031407 19700101000000 4711 \
+                    key.example. Zm9v");
+    }
+
+    #[test]
+    fn signing_data() {
+        use ::bits::Record;
+        use ::rdata::A;
+
+        let name = |s: &str| DNameBuf::from_str(s).unwrap();
+        let sig = Sig::new(Rtype::A, SecAlg::RsaSha256, 3, 3600,
+                           1488326400, 1485648000, 12345,
+                           name("Example.com."), b"sig".to_vec());
+        let rrset = vec![
+            Record::new(name("WWW.example.com."), Class::In, 300,
+                        A::from_octets(192, 0, 2, 2)),
+            Record::new(name("www.Example.com."), Class::In, 300,
+                        A::from_octets(192, 0, 2, 1)),
+            Record::new(name("www.example.com."), Class::In, 300,
+                        A::from_octets(192, 0, 2, 2)),
+        ];
+        let rr = |last: u8| {
+            let mut res = b"\x03www\x07example\x03com\x00\x00\x01\x00\x01\
+                            \x00\x00\x0e\x10\x00\x04\xc0\x00\x02".to_vec();
+            res.push(last);
+            res
+        };
+        let mut expected = b"\x00\x01\x08\x03\x00\x00\x0e\x10\
+                             \x58\xb6\x0f\x00\x58\x8d\x30\x80\x30\x39\
+                             \x07example\x03com\x00".to_vec();
+        expected.extend_from_slice(&rr(1));
+        expected.extend_from_slice(&rr(2));
+        assert_eq!(sig.signing_data(&rrset).unwrap(), expected);
+
+        // Wildcard expansion.
+        let sig = Sig::new(Rtype::A, SecAlg::RsaSha256, 2, 3600,
+                           1488326400, 1485648000, 12345,
+                           name("example.com."), b"sig".to_vec());
+        let rrset = vec![Record::new(name("a.b.example.com."), Class::In,
+                                     300, A::from_octets(192, 0, 2, 1))];
+        let data = sig.signing_data(&rrset).unwrap();
+        assert_eq!(&data[31..46], b"\x01*\x07example\x03com\x00");
+    }
+
+    #[test]
+    fn time_dates() {
+        assert_eq!(scan_time(b"20160229000000").unwrap(), 1456704000);
+        assert!(scan_time(b"20170229000000").is_err());
+        assert!(scan_time(b"20170231000000").is_err());
+        assert!(scan_time(b"20170431000000").is_err());
+        assert!(scan_time(b"21000229000000").is_err());
+        assert_eq!(scan_time(b"20170131235959").unwrap(), 1485907199);
+        assert!(scan_time("201ä301000000".as_bytes()).is_err());
+        assert!(scan_time(b"2017+301000000").is_err());
+    }
+}
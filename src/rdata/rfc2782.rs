@@ -61,6 +61,14 @@ impl<N: DName> RecordData for Srv<N> {
         target.as_mut().compose_u16(self.port)?;
         self.target.compose(target)
     }
+
+    fn compose_canonical<C: AsMut<Composer>>(&self, mut target: C)
+                                             -> ComposeResult<()> {
+        target.as_mut().compose_u16(self.priority)?;
+        target.as_mut().compose_u16(self.weight)?;
+        target.as_mut().compose_u16(self.port)?;
+        self.target.compose_canonical(target)
+    }
 }
 
 impl<'a> ParsedRecordData<'a> for Srv<ParsedDName<'a>> {
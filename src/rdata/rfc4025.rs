@@ -0,0 +1,311 @@
+//! Record data from [RFC 4025].
+//!
+//! This RFC defines the Ipseckey record type.
+//!
+//! [RFC 4025]: https://tools.ietf.org/html/rfc4025
+
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use ::bits::{Composer, ComposeResult, DName, DNameBuf, DNameSlice,
+             ParsedDName, ParsedRecordData, Parser, ParseError, ParseResult,
+             RecordData};
+use ::iana::Rtype;
+use ::master::{ScanError, Scanner, ScanResult, SyntaxError};
+use ::utils::base64;
+
+
+//------------ Ipseckey ------------------------------------------------------
+
+/// Ipseckey record data.
+///
+/// Ipseckey records store public keys for use with IPsec together with the
+/// security gateway to contact for establishing a connection with the host
+/// the record belongs to. The precedence orders several records for the
+/// same host with lower values being preferred.
+///
+/// The type of the gateway is encoded in the record data and determines
+/// how the gateway field is to be interpreted. It is represented by the
+/// variants of [`Gateway`].
+///
+/// The Ipseckey record type is defined in RFC 4025, section 2.
+///
+/// [`Gateway`]: enum.Gateway.html
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Ipseckey<N: DName, D: AsRef<[u8]>> {
+    precedence: u8,
+    algorithm: u8,
+    gateway: Gateway<N>,
+    public_key: D,
+}
+
+impl<N: DName, D: AsRef<[u8]>> Ipseckey<N, D> {
+    /// Creates new Ipseckey record data from its components.
+    pub fn new(precedence: u8, algorithm: u8, gateway: Gateway<N>,
+               public_key: D) -> Self {
+        Ipseckey {
+            precedence: precedence,
+            algorithm: algorithm,
+            gateway: gateway,
+            public_key: public_key
+        }
+    }
+
+    /// The precedence of the record.
+    pub fn precedence(&self) -> u8 { self.precedence }
+
+    /// The algorithm of the public key.
+    ///
+    /// The value 0 means that there is no key, 1 is a DSA key, and 2 an
+    /// RSA key.
+    pub fn algorithm(&self) -> u8 { self.algorithm }
+
+    /// The security gateway.
+    pub fn gateway(&self) -> &Gateway<N> { &self.gateway }
+
+    /// The raw public key data.
+    ///
+    /// This is empty if there is no key.
+    pub fn public_key(&self) -> &[u8] { self.public_key.as_ref() }
+}
+
+impl<'a> Ipseckey<ParsedDName<'a>, &'a [u8]> {
+    fn parse_always(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        let precedence = try!(parser.parse_u8());
+        let gateway_type = try!(parser.parse_u8());
+        let algorithm = try!(parser.parse_u8());
+        let gateway = match gateway_type {
+            0 => Gateway::None,
+            1 => {
+                let bytes = try!(parser.parse_bytes(4));
+                Gateway::Ipv4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2],
+                                            bytes[3]))
+            }
+            2 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(try!(parser.parse_bytes(16)));
+                Gateway::Ipv6(Ipv6Addr::from(octets))
+            }
+            3 => Gateway::Name(try!(ParsedDName::parse(parser))),
+            _ => return Err(ParseError::FormErr)
+        };
+        let len = parser.remaining();
+        let public_key = try!(parser.parse_bytes(len));
+        Ok(Ipseckey::new(precedence, algorithm, gateway, public_key))
+    }
+}
+
+impl Ipseckey<DNameBuf, Vec<u8>> {
+    /// Scans Ipseckey record data from master data.
+    ///
+    /// The format of the gateway depends on the gateway type. The public
+    /// key is optional.
+    pub fn scan<S: Scanner>(scanner: &mut S, origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        let precedence = try!(scan_u8(scanner));
+        let gateway_type = try!(scan_u8(scanner));
+        let algorithm = try!(scan_u8(scanner));
+        let gateway = match gateway_type {
+            0 => {
+                try!(scanner.skip_literal(b"."));
+                Gateway::None
+            }
+            1 => {
+                try!(scanner.scan_str_phrase(|s| {
+                    Ok(Gateway::Ipv4(try!(Ipv4Addr::from_str(s))))
+                }))
+            }
+            2 => {
+                try!(scanner.scan_str_phrase(|s| {
+                    Ok(Gateway::Ipv6(try!(Ipv6Addr::from_str(s))))
+                }))
+            }
+            3 => Gateway::Name(try!(DNameBuf::scan(scanner, origin))),
+            _ => {
+                return Err(ScanError::Syntax(
+                    SyntaxError::Expected(b"gateway type 0 to 3".to_vec()),
+                    scanner.pos()
+                ))
+            }
+        };
+        // The public key is optional.
+        let public_key = match scanner.scan_base64_words() {
+            Ok(key) => key,
+            Err(ScanError::Syntax(SyntaxError::Unexpected(_), _)) |
+            Err(ScanError::Syntax(SyntaxError::UnexpectedEof, _)) => {
+                Vec::new()
+            }
+            Err(err) => return Err(err)
+        };
+        Ok(Ipseckey::new(precedence, algorithm, gateway, public_key))
+    }
+}
+
+impl<N: DName, D: AsRef<[u8]>> RecordData for Ipseckey<N, D> {
+    fn rtype(&self) -> Rtype { Rtype::Ipseckey }
+
+    fn compose<C: AsMut<Composer>>(&self, mut target: C)
+                                   -> ComposeResult<()> {
+        try!(target.as_mut().compose_u8(self.precedence));
+        try!(target.as_mut().compose_u8(self.gateway.gateway_type()));
+        try!(target.as_mut().compose_u8(self.algorithm));
+        match self.gateway {
+            Gateway::None => { }
+            Gateway::Ipv4(addr) => {
+                try!(target.as_mut().compose_bytes(&addr.octets()))
+            }
+            Gateway::Ipv6(addr) => {
+                try!(target.as_mut().compose_bytes(&addr.octets()))
+            }
+            Gateway::Name(ref name) => try!(name.compose(target.as_mut()))
+        }
+        target.as_mut().compose_bytes(self.public_key())
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Ipseckey<ParsedDName<'a>, &'a [u8]> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Ipseckey {
+            Ipseckey::parse_always(parser).map(Some)
+        }
+        else { Ok(None) }
+    }
+}
+
+impl<N, D> fmt::Display for Ipseckey<N, D>
+     where N: DName + fmt::Display, D: AsRef<[u8]> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{} {} {} {}", self.precedence,
+                    self.gateway.gateway_type(), self.algorithm,
+                    self.gateway));
+        if !self.public_key().is_empty() {
+            try!(f.write_str(" "));
+            try!(base64::display(self.public_key(), f));
+        }
+        Ok(())
+    }
+}
+
+
+//------------ Gateway -------------------------------------------------------
+
+/// The security gateway of an Ipseckey record.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Gateway<N: DName> {
+    /// There is no gateway.
+    None,
+
+    /// The gateway is given by its IPv4 address.
+    Ipv4(Ipv4Addr),
+
+    /// The gateway is given by its IPv6 address.
+    Ipv6(Ipv6Addr),
+
+    /// The gateway is given by its domain name.
+    Name(N),
+}
+
+impl<N: DName> Gateway<N> {
+    /// Returns the value of the gateway type field for the gateway.
+    pub fn gateway_type(&self) -> u8 {
+        match *self {
+            Gateway::None => 0,
+            Gateway::Ipv4(_) => 1,
+            Gateway::Ipv6(_) => 2,
+            Gateway::Name(_) => 3,
+        }
+    }
+}
+
+impl<N: DName + fmt::Display> fmt::Display for Gateway<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Gateway::None => f.write_str("."),
+            Gateway::Ipv4(addr) => addr.fmt(f),
+            Gateway::Ipv6(addr) => addr.fmt(f),
+            Gateway::Name(ref name) => name.fmt(f),
+        }
+    }
+}
+
+
+//------------ Helper Functions ----------------------------------------------
+
+/// Scans a decimal octet.
+fn scan_u8<S: Scanner>(scanner: &mut S) -> ScanResult<u8> {
+    scanner.scan_str_phrase(|s| u8::from_str(s).map_err(Into::into))
+}
+
+
+//============ Type Aliases =================================================
+
+pub mod parsed {
+    use ::bits::ParsedDName;
+
+    pub type Ipseckey<'a> = super::Ipseckey<ParsedDName<'a>, &'a [u8]>;
+}
+
+pub mod owned {
+    use ::bits::DNameBuf;
+
+    pub type Ipseckey = super::Ipseckey<DNameBuf, Vec<u8>>;
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::bits::{ComposeMode, Composer, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::Rtype;
+    use ::master::bufscanner::BufScanner;
+
+    fn round_trip(text: &[u8]) -> Vec<u8> {
+        let scanned = Ipseckey::scan(&mut BufScanner::create(text),
+                                     None).unwrap();
+        assert_eq!(format!("{}\n", scanned).as_bytes(), text);
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Ipseckey::parse(Rtype::Ipseckey, &mut parser).unwrap()
+                              .unwrap();
+        assert_eq!(parser.remaining(), 0);
+        assert_eq!(parsed.precedence(), scanned.precedence());
+        assert_eq!(parsed.algorithm(), scanned.algorithm());
+        assert_eq!(parsed.public_key(), scanned.public_key());
+        assert_eq!(format!("{}", parsed), format!("{}", scanned));
+        wire
+    }
+
+    #[test]
+    fn ipv4_round_trip() {
+        let wire = round_trip(b"10 1 2 192.0.2.38 AQNRU3mG7TVTO2BkR47usntb\n");
+        assert_eq!(&wire[..7], b"\x0a\x01\x02\xc0\x00\x02\x26");
+        assert_eq!(wire.len(), 7 + 18);
+    }
+
+    #[test]
+    fn name_round_trip() {
+        let wire = round_trip(b"10 3 2 mygateway.example.com. \
+                                AQNRU3mG7TVTO2BkR47usntb\n");
+        assert_eq!(&wire[..3], b"\x0a\x03\x02");
+        assert_eq!(&wire[3..26], b"\x09mygateway\x07example\x03com\x00");
+        assert_eq!(wire.len(), 26 + 18);
+    }
+
+    #[test]
+    fn gateway_types() {
+        round_trip(b"10 0 2 . AQNRU3mG7TVTO2BkR47usntb\n");
+        round_trip(b"10 2 2 2001:db8::1 AQNRU3mG7TVTO2BkR47usntb\n");
+        round_trip(b"10 1 0 192.0.2.3\n");
+        assert!(Ipseckey::parse(Rtype::Ipseckey,
+                                &mut Parser::new(b"\x0a\x04\x02"))
+                         .is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! Record data from [RFC 4034].
+//!
+//! This RFC defines the record types for DNSSEC. Currently, only the
+//! Dnskey record type is implemented.
+//!
+//! [RFC 4034]: https://tools.ietf.org/html/rfc4034
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
+use std::slice;
+use ::bits::{Composer, ComposeResult, DNameSlice, ParsedRecordData,
+             Parser, ParseResult, RecordData};
+use ::iana::{Rtype, SecAlg};
+use ::master::{Scanner, ScanError, ScanResult, SyntaxError};
+use ::utils::base64;
+
+
+//------------ Dnskey --------------------------------------------------------
+
+/// Dnskey record data.
+///
+/// Dnskey records store the public keys a zone uses for DNSSEC. The flags
+/// field marks keys as zone keys and secure entry points, the protocol
+/// field is always 3, and the algorithm field determines the format of
+/// the public key.
+///
+/// The Dnskey record type is defined in RFC 4034, section 2.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Dnskey<D: AsRef<[u8]>> {
+    flags: u16,
+    protocol: u8,
+    algorithm: SecAlg,
+    public_key: D,
+}
+
+impl<D: AsRef<[u8]>> Dnskey<D> {
+    /// Creates new Dnskey record data from its components.
+    pub fn new(flags: u16, protocol: u8, algorithm: SecAlg, public_key: D)
+               -> Self {
+        Dnskey {
+            flags: flags,
+            protocol: protocol,
+            algorithm: algorithm,
+            public_key: public_key
+        }
+    }
+
+    /// The flags of the key.
+    pub fn flags(&self) -> u16 { self.flags }
+
+    /// The protocol of the key. This should always be 3.
+    pub fn protocol(&self) -> u8 { self.protocol }
+
+    /// The algorithm of the key.
+    pub fn algorithm(&self) -> SecAlg { self.algorithm }
+
+    /// The raw public key data.
+    pub fn public_key(&self) -> &[u8] { self.public_key.as_ref() }
+
+    /// Returns whether the Zone Key flag is set.
+    pub fn is_zone_key(&self) -> bool { self.flags & 0x0100 != 0 }
+
+    /// Returns whether the Secure Entry Point flag is set.
+    pub fn is_secure_entry_point(&self) -> bool { self.flags & 0x0001 != 0 }
+
+    /// Calculates the key tag of the key.
+    ///
+    /// The key tag is a checksum over the record data used to quickly
+    /// select candidate keys for a signature. It is defined in RFC 4034,
+    /// appendix B. Note that different keys can have the same key tag.
+    pub fn key_tag(&self) -> u16 {
+        if self.algorithm == SecAlg::RsaMd5 {
+            // The key tag for the obsolete RSA/MD5 algorithm is the most
+            // significant 16 bits of the least significant 24 bits of the
+            // modulus, ie., the third-to-last and second-to-last octet of
+            // the public key.
+            let key = self.public_key();
+            let len = key.len();
+            if len < 3 { return 0 }
+            return (key[len - 3] as u16) << 8 | key[len - 2] as u16
+        }
+        let mut res = u32::from(self.flags);
+        res += u32::from(self.protocol) << 8;
+        res += u32::from(self.algorithm.to_int());
+        for (i, &octet) in self.public_key().iter().enumerate() {
+            if i % 2 == 0 { res += u32::from(octet) << 8 }
+            else { res += u32::from(octet) }
+        }
+        res += (res >> 16) & 0xFFFF;
+        (res & 0xFFFF) as u16
+    }
+}
+
+impl<'a> Dnskey<&'a [u8]> {
+    pub(crate) fn parse_always(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        let flags = try!(parser.parse_u16());
+        let protocol = try!(parser.parse_u8());
+        let algorithm = SecAlg::from_int(try!(parser.parse_u8()));
+        let len = parser.remaining();
+        let public_key = try!(parser.parse_bytes(len));
+        Ok(Dnskey::new(flags, protocol, algorithm, public_key))
+    }
+}
+
+impl Dnskey<Vec<u8>> {
+    /// Scans Dnskey record data from master data.
+    pub fn scan<S: Scanner>(scanner: &mut S, _origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        Self::scan_with(scanner, |_| false)
+    }
+
+    /// Scans the record data, possibly allowing an empty public key.
+    ///
+    /// The closure receives the flags and returns whether the key may be
+    /// missing. This is shared with the Key record which uses the same
+    /// format.
+    pub(crate) fn scan_with<S, F>(scanner: &mut S, allow_empty: F)
+                                  -> ScanResult<Self>
+                        where S: Scanner, F: Fn(u16) -> bool {
+        let flags = try!(scanner.scan_u16());
+        let protocol = try!(scanner.scan_str_phrase(|s| {
+            u8::from_str(s).map_err(Into::into)
+        }));
+        let algorithm = try!(scanner.scan_str_phrase(|s| {
+            SecAlg::from_str(s).map_err(|_| {
+                SyntaxError::UnknownSecAlg(s.as_bytes().into())
+            })
+        }));
+        let public_key = match scanner.scan_base64_words() {
+            // Only accept a missing key if there are no more words at all.
+            // Broken key data is still an error.
+            Err(ScanError::Syntax(SyntaxError::Unexpected(_), _)) |
+            Err(ScanError::Syntax(SyntaxError::UnexpectedEof, _))
+                    if allow_empty(flags) => Vec::new(),
+            res => try!(res)
+        };
+        Ok(Dnskey::new(flags, protocol, algorithm, public_key))
+    }
+}
+
+impl<D: AsRef<[u8]>> RecordData for Dnskey<D> {
+    fn rtype(&self) -> Rtype { Rtype::Dnskey }
+
+    fn compose<C: AsMut<Composer>>(&self, mut target: C)
+                                   -> ComposeResult<()> {
+        try!(target.as_mut().compose_u16(self.flags));
+        try!(target.as_mut().compose_u8(self.protocol));
+        try!(target.as_mut().compose_u8(self.algorithm.to_int()));
+        target.as_mut().compose_bytes(self.public_key())
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Dnskey<&'a [u8]> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Dnskey { Dnskey::parse_always(parser).map(Some) }
+        else { Ok(None) }
+    }
+}
+
+impl<D: AsRef<[u8]>> fmt::Display for Dnskey<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{} {} {}", self.flags, self.protocol, self.algorithm));
+        if !self.public_key().is_empty() {
+            try!(f.write_str(" "));
+            try!(base64::display(self.public_key(), f));
+        }
+        Ok(())
+    }
+}
+
+
+//------------ DnskeySet -----------------------------------------------------
+
+/// The set of Dnskey records at the apex of a zone.
+///
+/// Since key tags are only a checksum, several keys of a zone may share
+/// the same tag. A validator therefore has to try all keys with the tag
+/// given in a signature. The `keys_for_tag()` method provides this
+/// lookup.
+#[derive(Clone, Debug)]
+pub struct DnskeySet<D: AsRef<[u8]>> {
+    keys: Vec<Dnskey<D>>,
+}
+
+impl<D: AsRef<[u8]>> DnskeySet<D> {
+    /// Creates a new, empty key set.
+    pub fn new() -> Self {
+        DnskeySet { keys: Vec::new() }
+    }
+
+    /// Adds a key to the set.
+    pub fn push(&mut self, key: Dnskey<D>) {
+        self.keys.push(key)
+    }
+
+    /// Returns the number of keys in the set.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns an iterator over all keys in the set.
+    pub fn iter(&self) -> slice::Iter<Dnskey<D>> {
+        self.keys.iter()
+    }
+
+    /// Returns all keys in the set with the given key tag.
+    pub fn keys_for_tag(&self, tag: u16) -> Vec<&Dnskey<D>> {
+        self.keys.iter().filter(|key| key.key_tag() == tag).collect()
+    }
+}
+
+impl<D: AsRef<[u8]>> Default for DnskeySet<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: AsRef<[u8]>> FromIterator<Dnskey<D>> for DnskeySet<D> {
+    fn from_iter<I>(iter: I) -> Self
+                 where I: IntoIterator<Item=Dnskey<D>> {
+        DnskeySet { keys: Vec::from_iter(iter) }
+    }
+}
+
+impl<'a, D: AsRef<[u8]>> IntoIterator for &'a DnskeySet<D> {
+    type Item = &'a Dnskey<D>;
+    type IntoIter = slice::Iter<'a, Dnskey<D>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+
+//============ Type Aliases =================================================
+
+pub mod parsed {
+    pub type Dnskey<'a> = super::Dnskey<&'a [u8]>;
+}
+
+pub mod owned {
+    pub type Dnskey = super::Dnskey<Vec<u8>>;
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::bits::{ComposeMode, Composer, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::{Rtype, SecAlg};
+    use ::master::bufscanner::BufScanner;
+
+    #[test]
+    fn key_tag() {
+        // Example from RFC 4034, section 2.3. Its RRSIG uses key tag 2642.
+        let key = Dnskey::scan(
+            &mut BufScanner::create(&b"256 3 5 AQPSKmynfzW4kyBv015MUG2DeIQ3\
+                Cbl+BBZH4b/0PY1kxkmvHjcZc8no kfzj31GajIQKY+5CptLr3buXA10h\
+                WqTkF7H6RfoRqXQeogmMHfpftf6z Mv1LyBUgia7za6ZEzOJBOztyvhjL\
+                742iU/TpPSEDhm2SNKLijfUppn1U aNvv4w==\n"[..]),
+            None
+        ).unwrap();
+        assert_eq!(key.flags(), 256);
+        assert!(key.is_zone_key());
+        assert!(!key.is_secure_entry_point());
+        assert_eq!(key.algorithm(), SecAlg::RsaSha1);
+        assert_eq!(key.key_tag(), 2642);
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        key.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        let mut parser = Parser::new(&wire);
+        let parsed = Dnskey::parse(Rtype::Dnskey, &mut parser)
+                            .unwrap().unwrap();
+        assert_eq!(parsed.public_key(), key.public_key());
+        assert_eq!(parsed.key_tag(), 2642);
+    }
+
+    #[test]
+    fn keys_for_tag() {
+        // Adding 0x0100 to one 16 bit word and subtracting it from
+        // another keeps the checksum and thus the key tag the same.
+        let one = Dnskey::new(256, 3, SecAlg::RsaSha256,
+                              vec![0x01, 0x02, 0x03, 0x04]);
+        let two = Dnskey::new(256, 3, SecAlg::RsaSha256,
+                              vec![0x02, 0x02, 0x02, 0x04]);
+        let three = Dnskey::new(257, 3, SecAlg::RsaSha256,
+                                vec![0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(one.key_tag(), two.key_tag());
+        assert!(one.key_tag() != three.key_tag());
+
+        let set: DnskeySet<_> = vec![one.clone(), three.clone(), two.clone()]
+                                    .into_iter().collect();
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.keys_for_tag(one.key_tag()), vec![&one, &two]);
+        assert_eq!(set.keys_for_tag(three.key_tag()), vec![&three]);
+        assert!(set.keys_for_tag(one.key_tag().wrapping_add(7)).is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+//! Record data from [RFC 4398].
+//!
+//! This RFC defines the Cert record type.
+//!
+//! [RFC 4398]: https://tools.ietf.org/html/rfc4398
+
+use std::fmt;
+use std::str::FromStr;
+use ::bits::{Composer, ComposeResult, DNameSlice, ParsedRecordData,
+             Parser, ParseResult, RecordData};
+use ::iana::{CertType, Rtype, SecAlg};
+use ::master::{Scanner, ScanResult, SyntaxError};
+use ::utils::base64;
+
+
+//------------ Cert ----------------------------------------------------------
+
+/// Cert record data.
+///
+/// Cert records store certificates and related certificate revocation
+/// lists. The certificate type determines the format of the certificate
+/// data. The key tag and algorithm fields allow for quick selection of
+/// a certificate if it is a signature key certificate.
+///
+/// The Cert record type is defined in RFC 4398, section 2.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Cert<D: AsRef<[u8]>> {
+    cert_type: CertType,
+    key_tag: u16,
+    algorithm: SecAlg,
+    certificate: D,
+}
+
+impl<D: AsRef<[u8]>> Cert<D> {
+    /// Creates new Cert record data from its components.
+    pub fn new(cert_type: CertType, key_tag: u16, algorithm: SecAlg,
+               certificate: D) -> Self {
+        Cert {
+            cert_type: cert_type,
+            key_tag: key_tag,
+            algorithm: algorithm,
+            certificate: certificate
+        }
+    }
+
+    /// The type of the certificate.
+    pub fn cert_type(&self) -> CertType { self.cert_type }
+
+    /// The key tag of the key in the certificate or zero.
+    pub fn key_tag(&self) -> u16 { self.key_tag }
+
+    /// The algorithm of the key in the certificate or zero.
+    pub fn algorithm(&self) -> SecAlg { self.algorithm }
+
+    /// The raw certificate data.
+    pub fn certificate(&self) -> &[u8] { self.certificate.as_ref() }
+}
+
+impl<'a> Cert<&'a [u8]> {
+    fn parse_always(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        let cert_type = try!(CertType::parse(parser));
+        let key_tag = try!(parser.parse_u16());
+        let algorithm = SecAlg::from_int(try!(parser.parse_u8()));
+        let len = parser.remaining();
+        let certificate = try!(parser.parse_bytes(len));
+        Ok(Cert::new(cert_type, key_tag, algorithm, certificate))
+    }
+}
+
+impl Cert<Vec<u8>> {
+    /// Scans Cert record data from master data.
+    pub fn scan<S: Scanner>(scanner: &mut S, _origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        let cert_type = try!(CertType::scan(scanner));
+        let key_tag = try!(scanner.scan_u16());
+        let algorithm = try!(scanner.scan_str_phrase(|s| {
+            SecAlg::from_str(s).map_err(|_| {
+                SyntaxError::UnknownSecAlg(s.as_bytes().into())
+            })
+        }));
+        let certificate = try!(scanner.scan_base64_words());
+        Ok(Cert::new(cert_type, key_tag, algorithm, certificate))
+    }
+}
+
+impl<D: AsRef<[u8]>> RecordData for Cert<D> {
+    fn rtype(&self) -> Rtype { Rtype::Cert }
+
+    fn compose<C: AsMut<Composer>>(&self, mut target: C)
+                                   -> ComposeResult<()> {
+        try!(self.cert_type.compose(target.as_mut()));
+        try!(target.as_mut().compose_u16(self.key_tag));
+        try!(target.as_mut().compose_u8(self.algorithm.to_int()));
+        target.as_mut().compose_bytes(self.certificate())
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Cert<&'a [u8]> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Cert { Cert::parse_always(parser).map(Some) }
+        else { Ok(None) }
+    }
+}
+
+impl<D: AsRef<[u8]>> fmt::Display for Cert<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{} {} {} ", self.cert_type, self.key_tag,
+                    self.algorithm));
+        base64::display(self.certificate(), f)
+    }
+}
+
+
+//============ Type Aliases =================================================
+
+pub mod parsed {
+    pub type Cert<'a> = super::Cert<&'a [u8]>;
+}
+
+pub mod owned {
+    pub type Cert = super::Cert<Vec<u8>>;
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::bits::{ComposeMode, Composer, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::{CertType, Rtype, SecAlg};
+    use ::master::bufscanner::BufScanner;
+
+    #[test]
+    fn pkix_round_trip() {
+        let scanned = Cert::scan(
+            &mut BufScanner::create(&b"PKIX 12345 RSASHA256 MIIBCgKCAQEA \
+                                       wUgoZ3+M\n"[..]),
+            None
+        ).unwrap();
+        assert_eq!(scanned.cert_type(), CertType::Pkix);
+        assert_eq!(scanned.key_tag(), 12345);
+        assert_eq!(scanned.algorithm(), SecAlg::RsaSha256);
+        assert_eq!(scanned.certificate(),
+                   b"\x30\x82\x01\x0a\x02\x82\x01\x01\x00\
+                     \xc1\x48\x28\x67\x7f\x8c");
+        assert_eq!(format!("{}", scanned),
+                   "PKIX 12345 RSASHA256 MIIBCgKCAQEAwUgoZ3+M");
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        assert_eq!(&wire[..5], b"\x00\x01\x30\x39\x08");
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Cert::parse(Rtype::Cert, &mut parser).unwrap().unwrap();
+        assert_eq!(parsed.cert_type(), scanned.cert_type());
+        assert_eq!(parsed.key_tag(), scanned.key_tag());
+        assert_eq!(parsed.algorithm(), scanned.algorithm());
+        assert_eq!(parsed.certificate(), scanned.certificate());
+        assert_eq!(parser.remaining(), 0);
+    }
+}
@@ -0,0 +1,236 @@
+//! Record data from [RFC 8005].
+//!
+//! This RFC defines the Hip record type.
+//!
+//! [RFC 8005]: https://tools.ietf.org/html/rfc8005
+
+use std::fmt;
+use std::str::FromStr;
+use ::bits::{Composer, ComposeError, ComposeResult, DName, DNameBuf,
+             DNameSlice, ParsedDName, ParsedRecordData, Parser, ParseResult,
+             RecordData};
+use ::iana::Rtype;
+use ::master::{ScanError, Scanner, ScanResult, SyntaxError};
+use ::utils::base64;
+
+
+//------------ Hip -----------------------------------------------------------
+
+/// Hip record data.
+///
+/// Hip records store the Host Identity of a host using the Host Identity
+/// Protocol (HIP) in the form of its public key together with the Host
+/// Identity Tag (HIT), a hash of the key. Optionally, they list the
+/// domain names of rendezvous servers through which the host can be
+/// reached.
+///
+/// In the wire format, the HIT and the public key are preceded by their
+/// lengths. The rendezvous servers fill the remainder of the record data
+/// and are never compressed.
+///
+/// The Hip record type is defined in RFC 8005, section 5.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Hip<N: DName, D: AsRef<[u8]>> {
+    pk_algorithm: u8,
+    hit: D,
+    public_key: D,
+    rendezvous_servers: Vec<N>,
+}
+
+impl<N: DName, D: AsRef<[u8]>> Hip<N, D> {
+    /// Creates new Hip record data from its components.
+    pub fn new(pk_algorithm: u8, hit: D, public_key: D,
+               rendezvous_servers: Vec<N>) -> Self {
+        Hip {
+            pk_algorithm: pk_algorithm,
+            hit: hit,
+            public_key: public_key,
+            rendezvous_servers: rendezvous_servers
+        }
+    }
+
+    /// The algorithm of the public key.
+    ///
+    /// The values are those used by Ipseckey records, i.e., 1 is a DSA
+    /// key and 2 an RSA key.
+    pub fn pk_algorithm(&self) -> u8 { self.pk_algorithm }
+
+    /// The raw Host Identity Tag.
+    pub fn hit(&self) -> &[u8] { self.hit.as_ref() }
+
+    /// The raw public key data.
+    pub fn public_key(&self) -> &[u8] { self.public_key.as_ref() }
+
+    /// The domain names of the rendezvous servers.
+    pub fn rendezvous_servers(&self) -> &[N] { &self.rendezvous_servers }
+}
+
+impl<'a> Hip<ParsedDName<'a>, &'a [u8]> {
+    fn parse_always(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        let hit_len = try!(parser.parse_u8()) as usize;
+        let pk_algorithm = try!(parser.parse_u8());
+        let pk_len = try!(parser.parse_u16()) as usize;
+        let hit = try!(parser.parse_bytes(hit_len));
+        let public_key = try!(parser.parse_bytes(pk_len));
+        let mut rendezvous_servers = Vec::new();
+        while parser.remaining() > 0 {
+            rendezvous_servers.push(try!(ParsedDName::parse(parser)));
+        }
+        Ok(Hip::new(pk_algorithm, hit, public_key, rendezvous_servers))
+    }
+}
+
+impl Hip<DNameBuf, Vec<u8>> {
+    /// Scans Hip record data from master data.
+    ///
+    /// The HIT is given in hex and the public key in base64, both as a
+    /// single word. Relative rendezvous server names are made absolute
+    /// using `origin`.
+    pub fn scan<S: Scanner>(scanner: &mut S, origin: Option<&DNameSlice>)
+                            -> ScanResult<Self> {
+        let pk_algorithm = try!(scanner.scan_str_phrase(|s| {
+            u8::from_str(s).map_err(Into::into)
+        }));
+        let mut hit = Vec::new();
+        try!(scanner.scan_hex_word(|v| {
+            if hit.len() == 255 {
+                Err(SyntaxError::Expected(b"HIT of at most 255 octets"
+                                            .to_vec()))
+            }
+            else {
+                hit.push(v);
+                Ok(())
+            }
+        }));
+        // The public key is a single word so that it doesn’t run into the
+        // rendezvous servers.
+        let public_key = try!(scanner.scan_word(|slice| {
+            let mut decoder = base64::Decoder::new();
+            for &ch in slice {
+                try!(decoder.push(ch))
+            }
+            decoder.finalize().map_err(Into::into)
+        }));
+        if public_key.len() > 0xFFFF {
+            return Err(ScanError::Syntax(
+                SyntaxError::Expected(b"public key of at most 65535 octets"
+                                        .to_vec()),
+                scanner.pos()
+            ))
+        }
+        let mut rendezvous_servers = Vec::new();
+        loop {
+            match DNameBuf::scan(scanner, origin) {
+                Ok(name) => rendezvous_servers.push(name),
+                Err(ScanError::Syntax(SyntaxError::Unexpected(_), _)) |
+                Err(ScanError::Syntax(SyntaxError::UnexpectedEof, _)) => {
+                    break
+                }
+                Err(err) => return Err(err)
+            }
+        }
+        Ok(Hip::new(pk_algorithm, hit, public_key, rendezvous_servers))
+    }
+}
+
+impl<N: DName, D: AsRef<[u8]>> RecordData for Hip<N, D> {
+    fn rtype(&self) -> Rtype { Rtype::Hip }
+
+    fn compose<C: AsMut<Composer>>(&self, mut target: C)
+                                   -> ComposeResult<()> {
+        if self.hit().len() > 0xFF || self.public_key().len() > 0xFFFF {
+            return Err(ComposeError::Overflow)
+        }
+        try!(target.as_mut().compose_u8(self.hit().len() as u8));
+        try!(target.as_mut().compose_u8(self.pk_algorithm));
+        try!(target.as_mut().compose_u16(self.public_key().len() as u16));
+        try!(target.as_mut().compose_bytes(self.hit()));
+        try!(target.as_mut().compose_bytes(self.public_key()));
+        for name in &self.rendezvous_servers {
+            try!(name.compose(target.as_mut()))
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ParsedRecordData<'a> for Hip<ParsedDName<'a>, &'a [u8]> {
+    fn parse(rtype: Rtype, parser: &mut Parser<'a>)
+             -> ParseResult<Option<Self>> {
+        if rtype == Rtype::Hip {
+            Hip::parse_always(parser).map(Some)
+        }
+        else { Ok(None) }
+    }
+}
+
+impl<N, D> fmt::Display for Hip<N, D>
+     where N: DName + fmt::Display, D: AsRef<[u8]> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{} ", self.pk_algorithm));
+        for ch in self.hit() {
+            try!(write!(f, "{:02X}", ch));
+        }
+        try!(f.write_str(" "));
+        try!(base64::display(self.public_key(), f));
+        for name in &self.rendezvous_servers {
+            try!(write!(f, " {}", name));
+        }
+        Ok(())
+    }
+}
+
+
+//============ Type Aliases =================================================
+
+pub mod parsed {
+    use ::bits::ParsedDName;
+
+    pub type Hip<'a> = super::Hip<ParsedDName<'a>, &'a [u8]>;
+}
+
+pub mod owned {
+    use ::bits::DNameBuf;
+
+    pub type Hip = super::Hip<DNameBuf, Vec<u8>>;
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::bits::{ComposeMode, Composer, Parser, ParsedRecordData,
+                 RecordData};
+    use ::iana::Rtype;
+    use ::master::bufscanner::BufScanner;
+
+    #[test]
+    fn round_trip() {
+        let text = b"2 200100107B1A74DF365639CC39F1D578 \
+                     AwEAAbdxyhNuSutc5EMzxTs9LBPCIkOFH8cIvM4p9+LrV4e19Wzk\
+                     ZM7TMSjn0xU7aqwaSPyVU9b6jR9vFD7ltuKHDOo= \
+                     rvs.example.com.\n";
+        let scanned = Hip::scan(&mut BufScanner::create(&text[..]),
+                                None).unwrap();
+        assert_eq!(scanned.hit().len(), 16);
+        assert_eq!(scanned.rendezvous_servers().len(), 1);
+        assert_eq!(format!("{}\n", scanned).as_bytes(), &text[..]);
+
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        scanned.compose(&mut composer).unwrap();
+        let wire = composer.finish();
+        let pk_len = scanned.public_key().len();
+        assert_eq!(&wire[..4], &[16, 2, (pk_len >> 8) as u8, pk_len as u8]);
+        assert_eq!(&wire[4 + 16 + pk_len..], b"\x03rvs\x07example\x03com\x00");
+
+        let mut parser = Parser::new(&wire);
+        let parsed = Hip::parse(Rtype::Hip, &mut parser).unwrap().unwrap();
+        assert_eq!(parser.remaining(), 0);
+        assert_eq!(parsed.pk_algorithm(), 2);
+        assert_eq!(parsed.hit(), scanned.hit());
+        assert_eq!(parsed.public_key(), scanned.public_key());
+        assert_eq!(parsed.rendezvous_servers().len(), 1);
+        assert_eq!(format!("{}", parsed), format!("{}", scanned));
+    }
+}
@@ -0,0 +1,169 @@
+//! Synchronous lookups.
+//!
+//! The functions in this module perform a lookup on a resolver of their own
+//! and block until it is done. They are meant for applications that simply
+//! want an answer and don’t care about futures.
+
+use std::{io, result};
+use std::net::IpAddr;
+use std::str::FromStr;
+use tokio_core::reactor;
+use ::bits::{DName, DNameBuf};
+use ::iana::Rcode;
+use super::conf::ResolvConf;
+use super::error::ResolvError;
+use super::lookup;
+use super::public::Resolver;
+
+
+//------------ lookup_host ---------------------------------------------------
+
+/// Resolves a host name into its IP addresses.
+///
+/// This is the equivalent of `getaddrinfo()`. It uses the system’s resolver
+/// configuration and fails if the name doesn’t exist. See
+/// [`lookup_host_with_conf()`] for the details.
+///
+/// [`lookup_host_with_conf()`]: fn.lookup_host_with_conf.html
+pub fn lookup_host(name: &str) -> result::Result<Vec<IpAddr>, ResolvError> {
+    lookup_host_with_conf(name, ResolvConf::default(), true)
+}
+
+/// Resolves a host name into its IP addresses using the given configuration.
+///
+/// This runs the future returned by [`lookup::lookup_host()`] on a reactor
+/// of its own and blocks until it resolves. The IPv4 addresses of the
+/// canonical name of `name` are returned followed by its IPv6 addresses.
+/// If the responses only contain the start of a CNAME chain, the lookup is
+/// repeated for the end of the chain until addresses are found. A relative
+/// `name` is turned into absolute names via the search list of `conf`.
+///
+/// If the name doesn’t exist, the function fails with
+/// `ResolvError::Rcode(Rcode::NXDomain)` if `nxdomain_is_error` is `true`
+/// or returns an empty list otherwise. A name that exists but has no
+/// addresses always results in an empty list.
+///
+/// [`lookup::lookup_host()`]: lookup/fn.lookup_host.html
+pub fn lookup_host_with_conf(name: &str, conf: ResolvConf,
+                             nxdomain_is_error: bool)
+                             -> result::Result<Vec<IpAddr>, ResolvError> {
+    let name = DNameBuf::from_str(name).map_err(|err| {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    })?;
+    let mut core = reactor::Core::new()?;
+    let resolver = Resolver::from_conf(&core.handle(), conf);
+    let mut name = name;
+    for _ in 0..MAX_CNAME_QUERIES {
+        let found = match core.run(lookup::lookup_host(resolver.clone(),
+                                                       &name)) {
+            Ok(found) => found,
+            Err(err) => {
+                return match err.into() {
+                    ResolvError::Rcode(Rcode::NXDomain)
+                        if !nxdomain_is_error => Ok(Vec::new()),
+                    err => Err(err)
+                }
+            }
+        };
+        let addrs: Vec<IpAddr> = found.iter().collect();
+        if !addrs.is_empty() || found.canonical_name() == &*name {
+            return Ok(addrs)
+        }
+        name = found.canonical_name().to_cow().into_owned();
+    }
+    Err(ResolvError::Rcode(Rcode::ServFail))
+}
+
+
+//------------ Constants -----------------------------------------------------
+
+/// The maximum number of lookups made for following a CNAME chain.
+const MAX_CNAME_QUERIES: usize = 8;
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, UdpSocket};
+    use std::str::FromStr;
+    use std::thread;
+    use ::bits::{ComposeMode, DNameBuf, Message, MessageBuilder};
+    use ::iana::{Rcode, Rtype};
+    use ::rdata::owned::{A, Aaaa, Cname};
+    use ::resolv::conf::{ResolvConf, ServerConf};
+    use ::resolv::error::ResolvError;
+    use super::*;
+
+    fn name(s: &str) -> DNameBuf {
+        DNameBuf::from_str(s).unwrap()
+    }
+
+    /// Answers queries from a tiny zone.
+    ///
+    /// The CNAME for alias.example. is returned without the addresses of
+    /// its target so that the lookup has to follow it.
+    fn respond(query: &Message) -> Vec<u8> {
+        let question = query.first_question().unwrap();
+        let qname = question.qname().to_cow().into_owned();
+        let mut res = MessageBuilder::respond_to(query,
+                                                 ComposeMode::Limited(512))
+                                     .unwrap();
+        res.header_mut().set_aa(true);
+        if qname == name("nx.example.") {
+            res.header_mut().set_rcode(Rcode::NXDomain);
+            return res.finish()
+        }
+        let mut res = res.answer();
+        if qname == name("alias.example.") {
+            res.push((qname.clone(), 3600,
+                      Cname::new(name("host.example.")))).unwrap();
+        }
+        else if qname == name("host.example.") {
+            if question.qtype() == Rtype::A {
+                res.push((qname.clone(), 3600,
+                          A::from_octets(192, 0, 2, 1))).unwrap();
+            }
+            else if question.qtype() == Rtype::Aaaa {
+                res.push((qname.clone(), 3600,
+                          Aaaa::new("2001:db8::1".parse().unwrap())))
+                   .unwrap();
+            }
+        }
+        res.finish()
+    }
+
+    fn conf() -> ResolvConf {
+        let sock = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut conf = ResolvConf::new();
+        conf.servers.push(ServerConf::new(sock.local_addr().unwrap()));
+        conf.finalize();
+        thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            loop {
+                let (len, addr) = sock.recv_from(&mut buf).unwrap();
+                let query = Message::from_bytes(&buf[..len]).unwrap();
+                sock.send_to(&respond(query), addr).unwrap();
+            }
+        });
+        conf
+    }
+
+    #[test]
+    fn lookup_host() {
+        let addrs = vec![IpAddr::from_str("192.0.2.1").unwrap(),
+                         IpAddr::from_str("2001:db8::1").unwrap()];
+        assert_eq!(lookup_host_with_conf("host.example.", conf(), true)
+                       .unwrap(),
+                   addrs);
+        assert_eq!(lookup_host_with_conf("alias.example.", conf(), true)
+                       .unwrap(),
+                   addrs);
+        assert!(lookup_host_with_conf("nx.example.", conf(), false)
+                    .unwrap().is_empty());
+        match lookup_host_with_conf("nx.example.", conf(), true) {
+            Err(ResolvError::Rcode(Rcode::NXDomain)) => { }
+            res => panic!("unexpected result {:?}", res)
+        }
+    }
+}
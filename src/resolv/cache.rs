@@ -0,0 +1,450 @@
+//! A simple cache for answers.
+//!
+//! The `Cache` type defined here stores the RRsets found in the answer
+//! section of responses as well as negative answers. Entries are keyed by
+//! the lowercased owner name, record type, and class and expire once their
+//! TTL has run out.
+//!
+//! The cache learns the current time through a `Clock`. Normally, this is
+//! the system clock but a different clock can be provided for testing.
+
+use std::cmp::min;
+use std::collections::HashMap;
+use ::bits::{Composer, ComposeMode, DName, DNameBuf, DNameSlice, Message,
+             ParseError, ParseResult, Question, Record, RecordData};
+use ::bits::message::RecordSection;
+use ::iana::{Class, Rcode, Rtype};
+use ::rdata::MasterRecordData;
+use ::rdata::parsed::Soa;
+use ::utils::clock::{Clock, SystemClock};
+
+
+//------------ Cache ---------------------------------------------------------
+
+/// A cache for RRsets and negative answers.
+///
+/// RRsets are added by handing complete responses to [`insert()`]. Each
+/// RRset in the answer section that is owned by the question name or a
+/// name in the chain of CNAME records starting at it is stored with the
+/// smallest TTL of its records. All other RRsets are ignored since the
+/// server answering the question has no business telling us about them.
+/// If the response is negative, i.e., it is either a name error
+/// or there is no answer for the question, this fact is stored for the
+/// question for the time given by the SOA record in the authority section
+/// as described in [RFC 2308]. Negative answers without an SOA record are
+/// not cached.
+///
+/// Stored data can be retrieved via [`get()`] until it expires.
+///
+/// [`insert()`]: #method.insert
+/// [`get()`]: #method.get
+/// [RFC 2308]: https://tools.ietf.org/html/rfc2308
+#[derive(Clone, Debug)]
+pub struct Cache<C: Clock = SystemClock> {
+    /// The clock providing the current time.
+    clock: C,
+
+    /// The entries by lowercase owner name, record type, and class.
+    entries: HashMap<(DNameBuf, Rtype, Class), Entry>,
+}
+
+/// A cache entry.
+#[derive(Clone, Debug)]
+struct Entry {
+    /// The time the entry expires in seconds since the Unix epoch.
+    expires: u64,
+
+    /// The RRset with its TTL at insertion time.
+    rrset: CachedRrset,
+}
+
+impl Cache<SystemClock> {
+    /// Creates a new, empty cache using the system clock.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+
+impl Default for Cache<SystemClock> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Clock> Cache<C> {
+    /// Creates a new, empty cache using the given clock.
+    pub fn with_clock(clock: C) -> Self {
+        Cache { clock: clock, entries: HashMap::new() }
+    }
+
+    /// Returns a reference to the cache’s clock.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns a mutable reference to the cache’s clock.
+    pub fn clock_mut(&mut self) -> &mut C {
+        &mut self.clock
+    }
+
+    /// Returns the number of entries including expired ones.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the cache has no entries at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Stores the content of a response.
+    ///
+    /// Messages that aren’t responses, that don’t have a question, or
+    /// whose rcode is neither NoError nor NXDomain are ignored. Existing
+    /// entries for the same RRsets are replaced.
+    ///
+    /// Fails if the message cannot be parsed. In this case, nothing is
+    /// stored.
+    pub fn insert(&mut self, msg: &Message) -> ParseResult<()> {
+        let rcode = msg.header().rcode();
+        if !msg.header().qr() ||
+                (rcode != Rcode::NoError && rcode != Rcode::NXDomain) {
+            return Ok(())
+        }
+        let question = match msg.first_question() {
+            Some(question) => question,
+            None => return Ok(())
+        };
+        let (_, answer, authority, _) = msg.sections()?;
+        let mut rrsets = collect_rrsets(answer)?;
+        let chain = cname_chain(&question.qname().to_cow().to_lowercase(),
+                                question.qclass(), &rrsets);
+        rrsets.retain(|rrset| {
+            rrset.class == question.qclass() && chain.contains(&rrset.name)
+        });
+        if let Some(qname) = msg.canonical_name() {
+            let qname = qname.to_cow().to_lowercase();
+            let answered = rrsets.iter().any(|rrset| {
+                rrset.name == qname && rrset.rtype == question.qtype()
+                    && rrset.class == question.qclass()
+            });
+            if rcode == Rcode::NXDomain || !answered {
+                if let Some(ttl) = negative_ttl(authority)? {
+                    rrsets.push(CachedRrset {
+                        name: qname,
+                        class: question.qclass(),
+                        rtype: question.qtype(),
+                        ttl: ttl,
+                        nxdomain: rcode == Rcode::NXDomain,
+                        data: Vec::new()
+                    })
+                }
+            }
+        }
+        let now = self.clock.unix_time();
+        for rrset in rrsets {
+            let key = (rrset.name.clone(), rrset.rtype, rrset.class);
+            let entry = Entry {
+                expires: now + u64::from(rrset.ttl),
+                rrset: rrset
+            };
+            self.entries.insert(key, entry);
+        }
+        Ok(())
+    }
+
+    /// Returns the unexpired RRset for a question.
+    ///
+    /// The TTL of the returned RRset is reduced by the time that has passed
+    /// since it was inserted. If the cache has stored a negative answer for
+    /// the question, the returned RRset is empty.
+    pub fn get<N: DName>(&self, question: &Question<N>)
+                         -> Option<CachedRrset> {
+        let key = (question.qname().to_cow().to_lowercase(),
+                   question.qtype(), question.qclass());
+        let entry = match self.entries.get(&key) {
+            Some(entry) => entry,
+            None => return None
+        };
+        let now = self.clock.unix_time();
+        if now >= entry.expires {
+            return None
+        }
+        let mut res = entry.rrset.clone();
+        res.ttl = min(u64::from(res.ttl), entry.expires - now) as u32;
+        Some(res)
+    }
+
+    /// Removes all expired entries.
+    pub fn purge(&mut self) {
+        let now = self.clock.unix_time();
+        self.entries.retain(|_, entry| now < entry.expires)
+    }
+}
+
+
+//------------ CachedRrset ---------------------------------------------------
+
+/// An RRset retrieved from a cache.
+///
+/// The record data is kept in its uncompressed wire format. Since negative
+/// answers are cached, too, an RRset can be empty. In this case,
+/// [`is_nxdomain()`] tells whether the name doesn’t exist at all or only
+/// has no records of the type.
+///
+/// [`is_nxdomain()`]: #method.is_nxdomain
+#[derive(Clone, Debug)]
+pub struct CachedRrset {
+    name: DNameBuf,
+    class: Class,
+    rtype: Rtype,
+    ttl: u32,
+    nxdomain: bool,
+    data: Vec<Vec<u8>>,
+}
+
+impl CachedRrset {
+    /// Returns the lowercase owner name of the RRset.
+    pub fn name(&self) -> &DNameBuf {
+        &self.name
+    }
+
+    /// Returns the class of the RRset.
+    pub fn class(&self) -> Class {
+        self.class
+    }
+
+    /// Returns the record type of the RRset.
+    pub fn rtype(&self) -> Rtype {
+        self.rtype
+    }
+
+    /// Returns the remaining TTL of the RRset.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// Returns the number of records in the RRset.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns whether the RRset is empty, i.e., a negative answer.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns whether the RRset stems from a name error.
+    pub fn is_nxdomain(&self) -> bool {
+        self.nxdomain
+    }
+
+    /// Returns the record data of the records in wire format.
+    pub fn data(&self) -> &[Vec<u8>] {
+        &self.data
+    }
+
+    /// Returns the records of the RRset.
+    ///
+    /// The records use the remaining TTL and can be added to a message
+    /// directly.
+    pub fn to_records(&self) -> Vec<Record<DNameBuf, MasterRecordData>> {
+        self.data.iter().map(|data| {
+            Record::new(self.name.clone(), self.class, self.ttl,
+                        MasterRecordData::Generic(self.rtype, data.clone()))
+        }).collect()
+    }
+}
+
+
+//------------ Helper Functions ----------------------------------------------
+
+/// Collects the RRsets of a section.
+///
+/// Each RRset receives the smallest TTL of its records. Duplicate records
+/// are dropped.
+fn collect_rrsets(section: RecordSection)
+                  -> ParseResult<Vec<CachedRrset>> {
+    let mut res: Vec<CachedRrset> = Vec::new();
+    for record in section {
+        let record = record?;
+        let name = record.name().to_cow().to_lowercase();
+        // Compose without compression to get rid of any compressed names
+        // pointing into the message.
+        let mut composer = Composer::new(ComposeMode::Unlimited, false);
+        if record.data().compose(&mut composer).is_err() {
+            return Err(ParseError::FormErr)
+        }
+        let data = composer.finish();
+        let pos = res.iter().position(|rrset| {
+            rrset.name == name && rrset.rtype == record.rtype()
+                && rrset.class == record.class()
+        });
+        match pos {
+            Some(pos) => {
+                let rrset = &mut res[pos];
+                rrset.ttl = min(rrset.ttl, record.ttl());
+                if !rrset.data.contains(&data) {
+                    rrset.data.push(data)
+                }
+            }
+            None => {
+                res.push(CachedRrset {
+                    name: name,
+                    class: record.class(),
+                    rtype: record.rtype(),
+                    ttl: record.ttl(),
+                    nxdomain: false,
+                    data: vec![data]
+                })
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Returns the names in the CNAME chain starting at `qname`.
+///
+/// The chain is followed through the CNAME RRsets in `rrsets`. The
+/// returned names are lowercase and include `qname` itself. Should the
+/// chain loop, it ends before the first repeated name.
+fn cname_chain(qname: &DNameBuf, qclass: Class,
+               rrsets: &[CachedRrset]) -> Vec<DNameBuf> {
+    let mut res = vec![qname.clone()];
+    loop {
+        let target = rrsets.iter().find(|rrset| {
+            rrset.rtype == Rtype::Cname && rrset.class == qclass
+                && Some(&rrset.name) == res.last()
+        }).and_then(|rrset| rrset.data.first())
+          .and_then(|data| DNameSlice::from_bytes(data));
+        match target {
+            Some(target) => {
+                let target = target.to_lowercase();
+                if res.contains(&target) {
+                    return res
+                }
+                res.push(target)
+            }
+            None => return res
+        }
+    }
+}
+
+/// Returns the TTL for a negative answer.
+///
+/// This is the smaller of the TTL of the SOA record in the authority
+/// section and its minimum field as defined in RFC 2308, section 5. If
+/// there is no SOA record, returns `None`.
+fn negative_ttl(section: RecordSection) -> ParseResult<Option<u32>> {
+    match section.limit_to::<Soa>().next() {
+        Some(record) => {
+            let record = record?;
+            Ok(Some(min(record.ttl(), record.data().minimum())))
+        }
+        None => Ok(None)
+    }
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use ::bits::{ComposeMode, DNameBuf, MessageBuf, MessageBuilder,
+                 Question};
+    use ::iana::{Class, Rcode, Rtype};
+    use ::rdata::owned::{A, Cname, Soa};
+    use ::utils::clock::FixedClock;
+    use super::*;
+
+    fn name(s: &str) -> DNameBuf {
+        DNameBuf::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn positive() {
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.push((name("www.example.com."), Rtype::A)).unwrap();
+        let mut msg = msg.answer();
+        msg.push((name("www.example.com."), 3600,
+                  A::from_octets(192, 0, 2, 1))).unwrap();
+        msg.push((name("WWW.example.com."), 300,
+                  A::from_octets(192, 0, 2, 2))).unwrap();
+        let msg = MessageBuf::from_vec(msg.finish()).unwrap();
+
+        let mut cache = Cache::with_clock(FixedClock(1_000_000));
+        cache.insert(&msg).unwrap();
+        cache.clock_mut().0 += 100;
+
+        let question = Question::new(name("www.Example.com."), Rtype::A,
+                                     Class::In);
+        let rrset = cache.get(&question).unwrap();
+        assert_eq!(rrset.ttl(), 200);
+        assert_eq!(rrset.len(), 2);
+        assert_eq!(rrset.data()[1], b"\xc0\x00\x02\x02");
+        assert!(cache.get(&Question::new(name("www.example.com."),
+                                         Rtype::Aaaa, Class::In))
+                     .is_none());
+
+        cache.clock_mut().0 += 200;
+        assert!(cache.get(&question).is_none());
+        cache.purge();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn cname_chain_only() {
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.push((name("www.example.com."), Rtype::A)).unwrap();
+        let mut msg = msg.answer();
+        msg.push((name("www.example.com."), 3600,
+                  Cname::new(name("Web.example.net.")))).unwrap();
+        msg.push((name("web.example.net."), 3600,
+                  A::from_octets(192, 0, 2, 1))).unwrap();
+        msg.push((name("bank.example.org."), 3600,
+                  A::from_octets(198, 51, 100, 1))).unwrap();
+        let msg = MessageBuf::from_vec(msg.finish()).unwrap();
+
+        let mut cache = Cache::with_clock(FixedClock(1_000_000));
+        cache.insert(&msg).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&Question::new(name("www.example.com."),
+                                         Rtype::Cname, Class::In))
+                     .is_some());
+        assert!(cache.get(&Question::new(name("web.example.net."),
+                                         Rtype::A, Class::In))
+                     .is_some());
+        assert!(cache.get(&Question::new(name("bank.example.org."),
+                                         Rtype::A, Class::In))
+                     .is_none());
+    }
+
+    #[test]
+    fn negative() {
+        let mut msg = MessageBuilder::new(ComposeMode::Unlimited,
+                                          true).unwrap();
+        msg.header_mut().set_qr(true);
+        msg.header_mut().set_rcode(Rcode::NXDomain);
+        msg.push((name("nope.example.com."), Rtype::A)).unwrap();
+        let mut msg = msg.authority();
+        msg.push((name("example.com."), 3600,
+                  Soa::new(name("ns.example.com."),
+                           name("hostmaster.example.com."),
+                           1, 7200, 600, 3600000, 60))).unwrap();
+        let msg = MessageBuf::from_vec(msg.finish()).unwrap();
+
+        let mut cache = Cache::with_clock(FixedClock(1_000_000));
+        cache.insert(&msg).unwrap();
+        cache.clock_mut().0 += 10;
+        let rrset = cache.get(&Question::new(name("nope.example.com."),
+                                             Rtype::A, Class::In)).unwrap();
+        assert!(rrset.is_empty());
+        assert!(rrset.is_nxdomain());
+        assert_eq!(rrset.ttl(), 50);
+    }
+}
@@ -17,7 +17,7 @@ use std::error;
 use std::fmt;
 use std::fs; 
 use std::io::{self, Read};
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::net::{AddrParseError, IpAddr, Ipv4Addr, SocketAddr};
 use std::path::Path;
 use std::str::{self, FromStr, SplitWhitespace};
 use std::result;
@@ -135,7 +135,11 @@ pub struct ResolvOptions {
 
     /// Use EDNS0.
     ///
-    /// EDNS is not yet supported.
+    /// If enabled, requests include an OPT record announcing the size of
+    /// the receive buffer of the server config. This can be disabled for
+    /// individual servers through their `edns` field.
+    ///
+    /// This option is implemented by the request.
     pub use_edns0: bool,
 
     /// Perform IPv4 and IPv6 lookups sequentially instead of in parallel.
@@ -204,6 +208,13 @@ pub struct ServerConf {
 
     /// Size of the message receive buffer in bytes.
     pub recv_size: usize,
+
+    /// Whether to use EDNS with this server.
+    ///
+    /// Some legacy servers fail to answer any request containing an OPT
+    /// record. If this is `false`, requests to this server will never
+    /// include one, even if the `use_edns0` option is enabled.
+    pub edns: bool,
 }
 
 
@@ -236,12 +247,34 @@ impl ServerConf {
             request_timeout: Duration::from_secs(2),
             keep_alive: Duration::from_secs(10),
             recv_size: 4096,
+            edns: true,
         }
     }
 
 }
 
 
+//------------ parse_server --------------------------------------------------
+
+/// Parses a server specification into a socket address.
+///
+/// The specification is either a socket address, i.e., an IPv4 address or
+/// an IPv6 address in square brackets followed by a colon and a port, or
+/// a bare IPv4 or IPv6 address. In the latter case, the default port 53
+/// is used.
+pub fn parse_server(s: &str) -> result::Result<SocketAddr, AddrParseError> {
+    match SocketAddr::from_str(s) {
+        Ok(addr) => Ok(addr),
+        Err(err) => {
+            match IpAddr::from_str(s) {
+                Ok(addr) => Ok(SocketAddr::new(addr, 53)),
+                Err(_) => Err(err)
+            }
+        }
+    }
+}
+
+
 //------------ ResolvConf ---------------------------------------------------
 
 /// Resolver configuration.
@@ -664,4 +697,20 @@ mod test {
         assert!(conf.options.use_vc);
         assert_eq!(conf.ndots, 122);
     }
+
+    #[test]
+    fn parse_server_forms() {
+        assert_eq!(parse_server("8.8.8.8").unwrap(),
+                   SocketAddr::from_str("8.8.8.8:53").unwrap());
+        assert_eq!(parse_server("8.8.8.8:5353").unwrap(),
+                   SocketAddr::from_str("8.8.8.8:5353").unwrap());
+        assert_eq!(parse_server("[2001:4860:4860::8888]:5353").unwrap(),
+                   SocketAddr::from_str("[2001:4860:4860::8888]:5353")
+                              .unwrap());
+        assert_eq!(parse_server("2001:4860:4860::8888").unwrap(),
+                   SocketAddr::from_str("[2001:4860:4860::8888]:53")
+                              .unwrap());
+        assert!(parse_server("dns.google").is_err());
+        assert!(parse_server("8.8.8.8:").is_err());
+    }
 }
@@ -132,8 +132,203 @@ impl fmt::Display for Error {
 }
 
 
+//------------ ResolvError ---------------------------------------------------
+
+/// An error happened while processing a single query.
+///
+/// This type collects all the ways a query to a single server can fail,
+/// from sending the request to receiving and checking the response. It
+/// provides `From` impls for the underlying error types so that `?` can
+/// be used throughout.
+///
+/// An IO error of kind `TimedOut` is converted into the `Timeout`
+/// variant rather than the `Io` variant.
+#[derive(Debug)]
+pub enum ResolvError {
+    /// An IO error happened while talking to the server.
+    Io(io::Error),
+
+    /// No response was received in time.
+    Timeout,
+
+    /// The response could not be parsed.
+    Parse(ParseError),
+
+    /// The server responded with an error rcode.
+    Rcode(Rcode),
+
+    /// The response did not match the request.
+    Mismatch,
+}
+
+
+//--- Error
+
+impl error::Error for ResolvError {
+    fn description(&self) -> &str {
+        match *self {
+            ResolvError::Io(ref error) => error.description(),
+            ResolvError::Timeout => "query timed out",
+            ResolvError::Parse(ref error) => error.description(),
+            ResolvError::Rcode(_) => "error response",
+            ResolvError::Mismatch => "response did not match request",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ResolvError::Io(ref error) => Some(error),
+            ResolvError::Parse(ref error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+
+//--- From
+
+impl From<io::Error> for ResolvError {
+    fn from(error: io::Error) -> ResolvError {
+        if error.kind() == io::ErrorKind::TimedOut {
+            ResolvError::Timeout
+        }
+        else {
+            ResolvError::Io(error)
+        }
+    }
+}
+
+impl From<ParseError> for ResolvError {
+    fn from(error: ParseError) -> ResolvError {
+        ResolvError::Parse(error)
+    }
+}
+
+impl From<Rcode> for ResolvError {
+    fn from(rcode: Rcode) -> ResolvError {
+        ResolvError::Rcode(rcode)
+    }
+}
+
+impl From<Error> for ResolvError {
+    fn from(error: Error) -> ResolvError {
+        match error {
+            Error::Question(error) => {
+                ResolvError::Io(io::Error::new(io::ErrorKind::InvalidInput,
+                                               error))
+            }
+            Error::Timeout => ResolvError::Timeout,
+            Error::NoName => ResolvError::Rcode(Rcode::NXDomain),
+            Error::NoSecureAnswers | Error::AllBogusAnswers => {
+                ResolvError::Rcode(Rcode::ServFail)
+            }
+            Error::Io(error) => error.into(),
+        }
+    }
+}
+
+
+//--- From for Error
+
+impl From<ResolvError> for Error {
+    fn from(error: ResolvError) -> Error {
+        match error {
+            ResolvError::Io(error) => Error::Io(error),
+            ResolvError::Timeout => Error::Timeout,
+            ResolvError::Parse(error) => error.into(),
+            ResolvError::Rcode(rcode) => rcode.into(),
+            ResolvError::Mismatch => {
+                Error::Io(io::Error::new(io::ErrorKind::InvalidData,
+                                         "response did not match request"))
+            }
+        }
+    }
+}
+
+
+//--- Display
+
+impl fmt::Display for ResolvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolvError::Io(ref error) => error.fmt(f),
+            ResolvError::Timeout => "query timed out".fmt(f),
+            ResolvError::Parse(ref error) => {
+                write!(f, "malformed response: {}", error)
+            }
+            ResolvError::Rcode(rcode) => {
+                write!(f, "error response: {}", rcode)
+            }
+            ResolvError::Mismatch => {
+                "response did not match request".fmt(f)
+            }
+        }
+    }
+}
+
+
 //------------ Result --------------------------------------------------------
 
 /// The result type of a query.
 pub type Result<T> = result::Result<T, Error>;
 
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::io;
+    use ::bits::ParseError;
+    use ::iana::Rcode;
+    use super::*;
+
+    fn timed_out() -> Result<()> {
+        fn read() -> io::Result<()> {
+            Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"))
+        }
+
+        fn query() -> result::Result<(), ResolvError> {
+            read()?;
+            Ok(())
+        }
+
+        query()?;
+        Ok(())
+    }
+
+    #[test]
+    fn from_io() {
+        match ResolvError::from(io::Error::new(io::ErrorKind::TimedOut,
+                                               "timed out")) {
+            ResolvError::Timeout => { }
+            err => panic!("unexpected error {:?}", err)
+        }
+        match ResolvError::from(io::Error::new(io::ErrorKind::Other,
+                                               "other")) {
+            ResolvError::Io(_) => { }
+            err => panic!("unexpected error {:?}", err)
+        }
+        match timed_out() {
+            Err(Error::Timeout) => { }
+            res => panic!("unexpected result {:?}", res)
+        }
+    }
+
+    #[test]
+    fn mismatch() {
+        match Error::from(ResolvError::Mismatch) {
+            Error::Io(ref err)
+                if err.kind() == io::ErrorKind::InvalidData => { }
+            err => panic!("unexpected error {:?}", err)
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(ResolvError::from(Rcode::ServFail).to_string(),
+                   "error response: SERVFAIL");
+        assert_eq!(ResolvError::from(ParseError::FormErr).to_string(),
+                   format!("malformed response: {}", ParseError::FormErr));
+    }
+}
@@ -0,0 +1,337 @@
+//! Iterative resolution.
+//!
+//! Whereas the normal resolver asks recursive name servers that do all the
+//! work, the iterative resolver defined herein does this work itself. It
+//! starts at the root servers and follows the referrals it receives until
+//! it reaches a server authoritative for the name in question.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use futures::{Async, Future, Poll};
+use tokio_core::reactor;
+use ::bits::{DName, DNameBuf, DNameSlice, MessageBuf};
+use ::iana::{Class, Rcode, Rtype};
+use super::conf::{ResolvConf, ServerConf};
+use super::error::ResolvError;
+use super::public::{Query, Resolver};
+
+
+//------------ IterativeResolver ---------------------------------------------
+
+/// A resolver following referrals from the root servers.
+///
+/// The resolver is created from a list of root hints, i.e., the addresses
+/// of the root name servers. For each name to be resolved, it sends a
+/// non-recursive query to these servers. As long as it receives a
+/// referral, it sends the query again to the name servers given in the
+/// referral, using the glue addresses from its additional section. Once
+/// a server answers authoritatively, that response is the result.
+///
+/// To avoid being sent around in circles, each referral has to be for a
+/// zone strictly below the zone of the previous one and the number of
+/// referrals followed is limited. Name servers without glue aren’t
+/// resolved separately, so a referral only containing these fails.
+///
+/// All failures to resolve a name other than those reported by the
+/// servers themselves result in `ResolvError::Rcode(Rcode::ServFail)`.
+#[derive(Clone, Debug)]
+pub struct IterativeResolver {
+    /// A handle to the reactor to spawn the transports into.
+    reactor: reactor::Handle,
+
+    /// The addresses of the root servers.
+    root_hints: Vec<IpAddr>,
+
+    /// The port to send queries to.
+    port: u16,
+
+    /// Socket addresses to use instead for some server addresses.
+    server_addrs: HashMap<IpAddr, SocketAddr>,
+
+    /// The maximum number of referrals to follow.
+    max_referrals: usize,
+
+    /// The configuration used for the queries to each set of servers.
+    conf: ResolvConf,
+}
+
+impl IterativeResolver {
+    /// Creates a new iterative resolver starting from the given root hints.
+    pub fn new(reactor: &reactor::Handle, root_hints: Vec<IpAddr>) -> Self {
+        let mut conf = ResolvConf::new();
+        conf.options.recurse = false;
+        IterativeResolver {
+            reactor: reactor.clone(),
+            root_hints: root_hints,
+            port: 53,
+            server_addrs: HashMap::new(),
+            max_referrals: 16,
+            conf: conf
+        }
+    }
+
+    /// Sets the port queries are sent to.
+    ///
+    /// This port is used for the root hints as well as the glue addresses
+    /// and is 53 by default. Changing it is mostly useful for testing.
+    pub fn set_port(&mut self, port: u16) {
+        self.port = port
+    }
+
+    /// Sends queries for the server at `addr` to `sock` instead.
+    ///
+    /// This applies to root hints and glue addresses alike and takes
+    /// precedence over the port set via [`set_port()`]. It is mostly
+    /// useful for testing where all servers have to share one address.
+    ///
+    /// [`set_port()`]: #method.set_port
+    pub fn set_server_addr(&mut self, addr: IpAddr, sock: SocketAddr) {
+        self.server_addrs.insert(addr, sock);
+    }
+
+    /// Sets the maximum number of referrals followed for a query.
+    ///
+    /// The default is 16.
+    pub fn set_max_referrals(&mut self, max: usize) {
+        self.max_referrals = max
+    }
+
+    /// Starts resolving the given name and record type.
+    ///
+    /// The returned future resolves into the authoritative response for
+    /// the question.
+    pub fn resolve<N: DName>(&self, name: &N, rtype: Rtype) -> Resolve {
+        Resolve::new(self.clone(), name.to_cow().into_owned(), rtype)
+    }
+
+    /// Starts a query to the servers with the given addresses.
+    fn query(&self, addrs: &[IpAddr], name: &DNameSlice, rtype: Rtype)
+             -> Query {
+        let mut conf = self.conf.clone();
+        for addr in addrs {
+            let sock = match self.server_addrs.get(addr) {
+                Some(sock) => *sock,
+                None => SocketAddr::new(*addr, self.port)
+            };
+            conf.servers.push(ServerConf::new(sock));
+        }
+        conf.finalize();
+        Resolver::from_conf(&self.reactor, conf)
+                 .query((name, rtype, Class::In))
+    }
+}
+
+
+//------------ Resolve -------------------------------------------------------
+
+/// The future of an iterative resolution.
+///
+/// Values of this type are returned by [`IterativeResolver::resolve()`].
+///
+/// [`IterativeResolver::resolve()`]: struct.IterativeResolver.html#method.resolve
+pub struct Resolve {
+    /// The resolver we are working for.
+    resolver: IterativeResolver,
+
+    /// The name we are resolving.
+    qname: DNameBuf,
+
+    /// The record type we are resolving.
+    qtype: Rtype,
+
+    /// The zone of the servers currently being asked.
+    zone: DNameBuf,
+
+    /// The number of referrals followed so far.
+    referrals: usize,
+
+    /// The query currently in flight.
+    query: Query,
+}
+
+impl Resolve {
+    fn new(resolver: IterativeResolver, qname: DNameBuf, qtype: Rtype)
+           -> Self {
+        let query = resolver.query(&resolver.root_hints, &qname, qtype);
+        Resolve {
+            resolver: resolver,
+            qname: qname,
+            qtype: qtype,
+            zone: DNameBuf::root(),
+            referrals: 0,
+            query: query
+        }
+    }
+
+    /// Processes a response.
+    ///
+    /// Returns the glue addresses of the name servers if the response is
+    /// an acceptable referral and `None` if it is the final answer.
+    fn process(&mut self, response: &MessageBuf)
+               -> Result<Option<Vec<IpAddr>>, ResolvError> {
+        let delegation = match response.delegation() {
+            Some(delegation) => delegation,
+            None => {
+                if response.header().aa() {
+                    return Ok(None)
+                }
+                return Err(ResolvError::Rcode(Rcode::ServFail))
+            }
+        };
+        let zone = delegation.zone().to_cow().into_owned();
+        if zone == self.zone || !zone.ends_with(&self.zone)
+                || !self.qname.ends_with(&zone) {
+            return Err(ResolvError::Rcode(Rcode::ServFail))
+        }
+        self.referrals += 1;
+        if self.referrals > self.resolver.max_referrals {
+            return Err(ResolvError::Rcode(Rcode::ServFail))
+        }
+        let mut addrs = Vec::new();
+        for ns in delegation.nameservers() {
+            addrs.extend(delegation.glue_for(ns))
+        }
+        if addrs.is_empty() {
+            return Err(ResolvError::Rcode(Rcode::ServFail))
+        }
+        self.zone = zone;
+        Ok(Some(addrs))
+    }
+}
+
+
+//--- Future
+
+impl Future for Resolve {
+    type Item = MessageBuf;
+    type Error = ResolvError;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let response = try_ready!(self.query.poll());
+            match self.process(&response)? {
+                Some(addrs) => {
+                    self.query = self.resolver.query(&addrs, &self.qname,
+                                                     self.qtype);
+                }
+                None => return Ok(Async::Ready(response))
+            }
+        }
+    }
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+    use std::str::FromStr;
+    use std::thread;
+    use tokio_core::reactor::Core;
+    use ::bits::{ComposeMode, DName, DNameBuf, Message, MessageBuilder};
+    use ::iana::{Rcode, Rtype};
+    use ::rdata::owned::{A, Ns};
+    use ::rdata::AllRecordData;
+    use ::resolv::error::ResolvError;
+    use super::*;
+
+    fn name(s: &str) -> DNameBuf {
+        DNameBuf::from_str(s).unwrap()
+    }
+
+    /// Answers queries as one server of a tiny hierarchy.
+    ///
+    /// The root server at 198.51.100.1 refers to the com. server at
+    /// 198.51.100.2 which refers to the example.com. server at
+    /// 198.51.100.3. The com. server refers queries for loop.com. back to
+    /// com.
+    fn respond(server: u8, query: &Message) -> Vec<u8> {
+        assert!(!query.header().rd());
+        let question = query.first_question().unwrap();
+        let qname = question.qname().to_cow().into_owned();
+        let mut res = MessageBuilder::respond_to(query,
+                                                 ComposeMode::Limited(512))
+                                     .unwrap();
+        let (zone, ns, glue) = match server {
+            1 => ("com.", "ns.nic.com.", server_addr(2)),
+            2 if qname.ends_with(&name("loop.com.")) => {
+                ("com.", "ns.nic.com.", server_addr(2))
+            }
+            2 => ("example.com.", "ns.example.com.", server_addr(3)),
+            _ => {
+                res.header_mut().set_aa(true);
+                let mut res = res.answer();
+                if qname == name("www.example.com.") {
+                    res.push((qname.clone(), 3600,
+                              A::from_octets(192, 0, 2, 80))).unwrap();
+                }
+                return res.finish()
+            }
+        };
+        let mut res = res.authority();
+        res.push((name(zone), 3600, Ns::new(name(ns)))).unwrap();
+        let mut res = res.additional();
+        res.push((name(ns), 3600, A::new(glue))).unwrap();
+        res.finish().unwrap()
+    }
+
+    /// Returns the address the hierarchy uses for the given server.
+    fn server_addr(server: u8) -> Ipv4Addr {
+        Ipv4Addr::new(198, 51, 100, server)
+    }
+
+    /// Starts the three servers and points the resolver to them.
+    ///
+    /// All servers listen on 127.0.0.1 with a port of their own.
+    fn start_servers(resolver: &mut IterativeResolver) {
+        for index in 0..3 {
+            let sock = UdpSocket::bind("127.0.0.1:0").unwrap();
+            resolver.set_server_addr(IpAddr::V4(server_addr(index + 1)),
+                                     sock.local_addr().unwrap());
+            thread::spawn(move || {
+                let mut buf = [0u8; 512];
+                loop {
+                    let (len, addr) = sock.recv_from(&mut buf).unwrap();
+                    let query = Message::from_bytes(&buf[..len]).unwrap();
+                    sock.send_to(&respond(index + 1, query), addr)
+                        .unwrap();
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn resolve() {
+        let mut core = Core::new().unwrap();
+        let mut resolver = IterativeResolver::new(
+            &core.handle(), vec![IpAddr::V4(server_addr(1))]
+        );
+        start_servers(&mut resolver);
+
+        let response = core.run(resolver.resolve(&name("www.example.com."),
+                                                 Rtype::A)).unwrap();
+        assert!(response.header().aa());
+        let record = response.answer().unwrap()
+                             .limit_to::<AllRecordData>().next().unwrap()
+                             .unwrap();
+        match *record.data() {
+            AllRecordData::A(ref a) => {
+                assert_eq!(a.addr(), Ipv4Addr::new(192, 0, 2, 80))
+            }
+            _ => panic!("unexpected record {}", record)
+        }
+
+        match core.run(resolver.resolve(&name("www.loop.com."), Rtype::A)) {
+            Err(ResolvError::Rcode(Rcode::ServFail)) => { }
+            res => panic!("unexpected result {:?}", res.map(|_| ()))
+        }
+
+        resolver.set_max_referrals(1);
+        match core.run(resolver.resolve(&name("www.example.com."),
+                                        Rtype::A)) {
+            Err(ResolvError::Rcode(Rcode::ServFail)) => { }
+            res => panic!("unexpected result {:?}", res.map(|_| ()))
+        }
+    }
+}
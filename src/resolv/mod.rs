@@ -212,12 +212,15 @@
 
 //------------ Re-exports ----------------------------------------------------
 
-pub use self::conf::ResolvConf;
-pub use self::public::{Query, Resolver};
+pub use self::blocking::{lookup_host, lookup_host_with_conf};
+pub use self::conf::{ResolvConf, parse_server};
+pub use self::iterative::{IterativeResolver, Resolve};
+pub use self::public::{Query, QueryFastest, Resolver};
 
 
 //------------ Public Modules ------------------------------------------------
 
+pub mod cache;
 pub mod conf;
 pub mod error;
 pub mod lookup;
@@ -230,7 +233,9 @@ pub mod intro;
 
 //------------ Private Modules -----------------------------------------------
 
+mod blocking;
 mod channel;
+mod iterative;
 mod public;
 mod request;
 mod tcp;
@@ -106,6 +106,23 @@ impl Resolver {
         Query::new(self, question)
     }
 
+    /// Trades the resolver for a DNS query racing all servers.
+    ///
+    /// Unlike [`query()`] which asks one server after another, this sends
+    /// the question to all configured servers at once. The returned future
+    /// resolves into the first valid response. See [`QueryFastest`] for
+    /// the details.
+    ///
+    /// If you need to keep the resolver, clone it before calling this
+    /// method.
+    ///
+    /// [`query()`]: #method.query
+    /// [`QueryFastest`]: struct.QueryFastest.html
+    pub fn query_fastest<N, Q>(self, question: Q) -> QueryFastest
+                         where N: DName, Q: Into<Question<N>> {
+        QueryFastest::new(self, question)
+    }
+
     /// Returns a reference to the list of UDP service handles.
     fn udp(&self) -> &[TransportHandle] {
         &self.0.udp
@@ -255,6 +272,12 @@ struct QueryInner {
 impl QueryInner {
     fn new(resolver: Resolver, message: RequestMessage) -> Self {
         let dgram = !resolver.options().use_vc;
+        Self::with_track(resolver, message, dgram)
+    }
+
+    /// Creates a new query starting out on the given track.
+    fn with_track(resolver: Resolver, message: RequestMessage, dgram: bool)
+                  -> Self {
         let (index, request) = Self::start(&resolver, dgram, message);
         QueryInner {
             resolver: resolver,
@@ -367,3 +390,223 @@ impl Future for QueryInner {
     }
 }
 
+
+//------------ QueryFastest --------------------------------------------------
+
+/// A DNS query sent to all servers at once.
+///
+/// This future sends the question to all servers of the resolver at the
+/// same time and resolves into the first valid response. A response is
+/// valid if it matches the question and its rcode is either NoError or
+/// NXDomain. In the latter case, the future resolves into the same error
+/// as a normal [`Query`] would. All other requests are dropped, i.e.,
+/// cancelled, once a valid response has arrived. Responses with other
+/// rcodes, such as a server failure, are ignored in favour of the
+/// responses of the remaining servers.
+///
+/// If a truncated response arrives over the datagram track first, the
+/// query falls back to asking the servers over the stream track one by one
+/// like a normal query does.
+///
+/// If no server provides a valid response, the future resolves into an
+/// error.
+///
+/// You can start a query via the resolver’s [`query_fastest()`] method.
+///
+/// [`Query`]: struct.Query.html
+/// [`query_fastest()`]: struct.Resolver.html#method.query_fastest
+pub struct QueryFastest {
+    /// The resolver we are working with.
+    resolver: Resolver,
+
+    /// The outstanding requests, one for each server.
+    requests: Vec<QueryRequest>,
+
+    /// Are we on datagram track?
+    dgram: bool,
+
+    /// The query over the stream track after a truncated response.
+    fallback: Option<QueryInner>,
+
+    /// The error to return if all requests fail.
+    error: Option<Error>,
+}
+
+impl QueryFastest {
+    /// Starts a new query.
+    pub fn new<N, Q>(resolver: Resolver, question: Q) -> Self
+               where N: DName, Q: Into<Question<N>> {
+        let dgram = !resolver.options().use_vc;
+        let (requests, error) = match RequestMessage::new(question,
+                                                          resolver.conf()) {
+            Ok(message) => {
+                let track = if dgram { resolver.udp() }
+                            else { resolver.tcp() };
+                let requests = track.iter().map(|transport| {
+                    QueryRequest::new(message.clone(), transport)
+                }).collect();
+                (requests, None)
+            }
+            Err(err) => (Vec::new(), Some(err.into()))
+        };
+        QueryFastest {
+            resolver: resolver,
+            requests: requests,
+            dgram: dgram,
+            fallback: None,
+            error: error
+        }
+    }
+
+    /// Adds an error to the error to return if all requests fail.
+    fn add_error(&mut self, error: Error) {
+        self.error = Some(match self.error.take() {
+            Some(old) => old.merge(error),
+            None => error
+        })
+    }
+}
+
+
+//--- Future
+
+impl Future for QueryFastest {
+    type Item = MessageBuf;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        if let Some(ref mut fallback) = self.fallback {
+            return fallback.poll()
+        }
+        let mut i = 0;
+        while i < self.requests.len() {
+            let (response, message) = match self.requests[i].poll() {
+                Ok(Async::NotReady) => {
+                    i += 1;
+                    continue
+                }
+                Ok(Async::Ready(some)) => some,
+                Err((error, _)) => {
+                    self.requests.swap_remove(i);
+                    self.add_error(error);
+                    continue
+                }
+            };
+            self.requests.swap_remove(i);
+            if response.header().tc() && self.dgram
+                    && !self.resolver.options().ign_tc {
+                self.requests.clear();
+                self.fallback = Some(QueryInner::with_track(
+                    self.resolver.clone(), message, false
+                ));
+                return self.poll()
+            }
+            match response.header().rcode() {
+                Rcode::NoError => {
+                    self.requests.clear();
+                    return Ok(Async::Ready(response))
+                }
+                Rcode::NXDomain => {
+                    self.requests.clear();
+                    return Err(Rcode::NXDomain.into())
+                }
+                rcode => self.add_error(rcode.into())
+            }
+        }
+        if self.requests.is_empty() {
+            Err(self.error.take().unwrap_or(Error::Timeout))
+        }
+        else {
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+    use futures::{Future, Stream};
+    use ::bits::{ComposeMode, DNameBuf, Message, MessageBuf,
+                 MessageBuilder};
+    use ::iana::{Rcode, Rtype};
+    use super::super::conf::ResolvConf;
+    use super::super::error::Error;
+    use super::super::request::TransportHandle;
+    use super::{Resolver, ResolverInner};
+
+    /// Creates a mock server answering after `delay` with `rcode`.
+    fn mock_server(rcode: Rcode, delay: u64) -> TransportHandle {
+        let (handle, rx) = TransportHandle::channel(None);
+        thread::spawn(move || {
+            for request in rx.wait() {
+                let request = request.unwrap();
+                thread::sleep(Duration::from_millis(delay));
+                let response = {
+                    let mut msg = request.message();
+                    let query = Message::from_bytes(msg.dgram_bytes())
+                                       .unwrap();
+                    let mut response = MessageBuilder::new(
+                        ComposeMode::Unlimited, true
+                    ).unwrap();
+                    response.header_mut().set_id(query.header().id());
+                    response.header_mut().set_qr(true);
+                    response.header_mut().set_rcode(rcode);
+                    response.push(query.first_question().unwrap()).unwrap();
+                    MessageBuf::from_vec(response.finish()).unwrap()
+                };
+                request.response(response);
+            }
+        });
+        handle
+    }
+
+    fn mock_resolver(udp: Vec<TransportHandle>) -> Resolver {
+        Resolver(Arc::new(ResolverInner {
+            udp: udp,
+            tcp: Vec::new(),
+            conf: ResolvConf::new()
+        }))
+    }
+
+    fn question() -> (DNameBuf, Rtype) {
+        (DNameBuf::from_str("www.example.com.").unwrap(), Rtype::A)
+    }
+
+    #[test]
+    fn query_fastest() {
+        // The fast server wins.
+        let resolver = mock_resolver(vec![
+            mock_server(Rcode::ServFail, 200), mock_server(Rcode::NoError, 0)
+        ]);
+        let response = resolver.query_fastest(question()).wait().unwrap();
+        assert_eq!(response.header().rcode(), Rcode::NoError);
+
+        // A fast server failure is skipped.
+        let resolver = mock_resolver(vec![
+            mock_server(Rcode::ServFail, 0), mock_server(Rcode::NoError, 100)
+        ]);
+        let response = resolver.query_fastest(question()).wait().unwrap();
+        assert_eq!(response.header().rcode(), Rcode::NoError);
+
+        // Name errors are final.
+        let resolver = mock_resolver(vec![
+            mock_server(Rcode::NXDomain, 0), mock_server(Rcode::NoError, 100)
+        ]);
+        match resolver.query_fastest(question()).wait() {
+            Err(Error::NoName) => { }
+            _ => panic!("expected NoName")
+        }
+
+        // All servers failing is an error.
+        let resolver = mock_resolver(vec![
+            mock_server(Rcode::ServFail, 0), mock_server(Rcode::ServFail, 0)
+        ]);
+        assert!(resolver.query_fastest(question()).wait().is_err());
+    }
+}
@@ -38,6 +38,7 @@ use futures::{Async, Future, Poll};
 use futures::sync::{mpsc, oneshot, BiLock, BiLockGuard};
 use ::bits::{AdditionalBuilder, ComposeMode, ComposeResult, DName,
              Message, MessageBuf, MessageBuilder, Question};
+use ::iana::OptRcode;
 use super::conf::ResolvConf;
 use super::error::Error;
 
@@ -56,7 +57,14 @@ use super::error::Error;
 ///
 /// The only thing you can do with a request message is turn them into a
 /// transport message using the `into_service()` method.
-pub struct RequestMessage(AdditionalBuilder);
+#[derive(Clone)]
+pub struct RequestMessage {
+    /// The message builder positioned at the additional section.
+    builder: AdditionalBuilder,
+
+    /// Whether EDNS has been enabled by the resolver config.
+    edns: bool,
+}
 
 impl RequestMessage {
     /// Creates a new request message from a question and resolver config.
@@ -68,17 +76,47 @@ impl RequestMessage {
         let mut msg = MessageBuilder::new(ComposeMode::Stream, false)?;
         msg.header_mut().set_rd(conf.options.recurse);
         msg.push(question)?;
-        Ok(RequestMessage(msg.additional()))
+        Ok(RequestMessage {
+            builder: msg.additional(),
+            edns: conf.options.use_edns0
+        })
     }
 
     /// Converts the request message into a transport message.
     ///
+    /// The `edns` argument contains the UDP payload size to announce in
+    /// an OPT record or `None` if the transport’s server shouldn’t receive
+    /// one.
+    ///
     /// This method returns the transport message wrapped into a pair of
     /// bi-locks. See `TransportMessage` for a discussion as to why that
     /// is useful.
-    fn into_service(self) -> (BiLock<Option<TransportMessage>>,
-                              BiLock<Option<TransportMessage>>) {
-        BiLock::new(Some(TransportMessage(self.0)))
+    fn into_service(self, edns: Option<u16>)
+                    -> (BiLock<Option<TransportMessage>>,
+                        BiLock<Option<TransportMessage>>) {
+        BiLock::new(Some(self.into_transport(edns)))
+    }
+
+    /// Converts the request message into a transport message.
+    ///
+    /// An OPT record with `edns` as the payload size is added only if
+    /// EDNS is enabled both for the request and, by `edns` being `Some`,
+    /// for the transport.
+    fn into_transport(self, edns: Option<u16>) -> TransportMessage {
+        let builder = match edns {
+            Some(size) if self.edns => {
+                let opt = self.builder.clone().build_opt(size,
+                                                         OptRcode::NoError,
+                                                         false)
+                              .and_then(|opt| opt.complete());
+                match opt {
+                    Ok(builder) => builder,
+                    Err(_) => self.builder
+                }
+            }
+            _ => self.builder
+        };
+        TransportMessage { builder: builder, edns: self.edns }
     }
 }
 
@@ -92,8 +130,6 @@ impl RequestMessage {
 /// sending. Once done, the transport message can be returned into a
 /// request message by dropping all EDNS0 information thus making it ready
 /// for reuse by the next transport.
-/// 
-/// *Note:* EDNS0 is not yet implemented.
 ///
 /// Transport messages are always kept wrapped into a pair of bi-locks. One
 /// of those locks goes into the transport request for use by the transport,
@@ -111,35 +147,42 @@ impl RequestMessage {
 /// however, not `take()` out the message. By resolving the oneshot, access
 /// is transferred back to the query request. It then can `take()` out the
 /// message.
-pub struct TransportMessage(AdditionalBuilder);
+pub struct TransportMessage {
+    /// The message builder positioned at the additional section.
+    builder: AdditionalBuilder,
+
+    /// Whether EDNS has been enabled by the resolver config.
+    edns: bool,
+}
 
 impl TransportMessage {
     /// Sets the message ID to the given value.
     pub fn set_id(&mut self, id: u16) {
-        self.0.header_mut().set_id(id)
+        self.builder.header_mut().set_id(id)
     }
 
     /// Checks whether `answer` is an answer to this message.
     pub fn is_answer(&self, answer: &Message) -> bool {
-        answer.is_answer(&self.0)
+        answer.is_answer(&self.builder)
     }
 
     /// Trades in this transport message for a request message.
     ///
     /// This rewinds all additions made to the message since creation but
     /// leaves the ID in place.
-    pub fn rewind(self) -> RequestMessage {
-        RequestMessage(self.0)
+    pub fn rewind(mut self) -> RequestMessage {
+        self.builder.rewind();
+        RequestMessage { builder: self.builder, edns: self.edns }
     }
 
     /// Returns a bytes slice with the data to be sent over stream transports.
     pub fn stream_bytes(&mut self) -> &[u8] {
-        self.0.preview()
+        self.builder.preview()
     }
 
     /// Returns a bytes slice for sending over datagram transports.
     pub fn dgram_bytes(&mut self) -> &[u8] {
-        &self.0.preview()[2..]
+        &self.builder.preview()[2..]
     }
 }
 
@@ -205,7 +248,7 @@ impl QueryRequest {
     /// referenced by `transport`.
     pub fn new(message: RequestMessage, transport: &TransportHandle) -> Self {
         let (tx, rx) = oneshot::channel();
-        let (smsg, qmsg) = message.into_service();
+        let (smsg, qmsg) = message.into_service(transport.edns);
         let sreq = TransportRequest::new(smsg, tx);
         let rx = transport.send(sreq).ok().map(|_| rx);
         QueryRequest {
@@ -390,19 +433,29 @@ impl TransportRequest {
 pub struct TransportHandle {
     /// The sending end of the transport’s request queue
     tx: mpsc::UnboundedSender<TransportRequest>,
+
+    /// The EDNS payload size for the transport’s server.
+    ///
+    /// If this is `None`, EDNS is disabled for the server.
+    edns: Option<u16>,
 }
 
 impl TransportHandle {
     /// Creates a new request channel, returning both ends.
-    pub fn channel() -> (TransportHandle, RequestReceiver) {
+    ///
+    /// The `edns` argument provides the EDNS payload size to use for
+    /// requests or `None` if EDNS is to be disabled for this transport.
+    pub fn channel(edns: Option<u16>) -> (TransportHandle, RequestReceiver) {
         let (tx, rx) = mpsc::unbounded();
-        (TransportHandle::from_sender(tx), rx)
+        (TransportHandle::from_sender(tx, edns), rx)
     } 
 
     /// Creates a new handle from the sender side of an MPCS channel.
-    pub fn from_sender(tx: mpsc::UnboundedSender<TransportRequest>) -> Self {
+    pub fn from_sender(tx: mpsc::UnboundedSender<TransportRequest>,
+                       edns: Option<u16>) -> Self {
         TransportHandle {
-            tx: tx
+            tx: tx,
+            edns: edns
         }
     }
 
@@ -431,3 +484,49 @@ impl fmt::Debug for TransportHandle {
 pub type RequestReceiver = mpsc::UnboundedReceiver<TransportRequest>;
 
 
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use ::bits::{DNameBuf, Message};
+    use ::bits::opt::Opt;
+    use ::iana::Rtype;
+    use super::super::conf::ResolvConf;
+    use super::*;
+
+    fn has_opt(msg: &mut TransportMessage) -> bool {
+        let msg = Message::from_bytes(msg.dgram_bytes()).unwrap();
+        msg.additional().unwrap().limit_to::<Opt>().next().is_some()
+    }
+
+    fn request(use_edns0: bool) -> RequestMessage {
+        let mut conf = ResolvConf::new();
+        conf.options.use_edns0 = use_edns0;
+        RequestMessage::new((DNameBuf::from_str("example.com.").unwrap(),
+                             Rtype::A), &conf).unwrap()
+    }
+
+    #[test]
+    fn edns_enabled() {
+        let mut msg = request(true).into_transport(Some(4096));
+        assert!(has_opt(&mut msg));
+
+        // Rewinding drops the OPT record again.
+        let mut msg = msg.rewind().into_transport(None);
+        assert!(!has_opt(&mut msg));
+    }
+
+    #[test]
+    fn edns_disabled() {
+        // Disabled for the server.
+        let mut msg = request(true).into_transport(None);
+        assert!(!has_opt(&mut msg));
+        assert_eq!(Message::from_bytes(msg.dgram_bytes()).unwrap()
+                           .counts().arcount(), 0);
+
+        // Disabled globally.
+        let mut msg = request(false).into_transport(Some(4096));
+        assert!(!has_opt(&mut msg));
+    }
+}
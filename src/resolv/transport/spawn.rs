@@ -1,5 +1,6 @@
 //! The `spawn_transport()` function for starting transport and what it needs.
 
+use std::cmp;
 use tokio_core::reactor;
 use super::super::channel::Channel;
 use super::super::conf::ServerConf;
@@ -23,7 +24,11 @@ pub fn spawn_transport<C>(reactor: &reactor::Handle, channel: C,
                           mode: TransportMode, conf: &ServerConf)
                           -> TransportHandle
                        where C : Channel + 'static {
-    let (tx, rx) = TransportHandle::channel();
+    let edns = if conf.edns {
+        Some(cmp::min(conf.recv_size, 0xFFFF) as u16)
+    }
+    else { None };
+    let (tx, rx) = TransportHandle::channel(edns);
     match mode {
         TransportMode::SingleRequest
             => single::Transport::spawn(rx, channel, reactor, conf),
@@ -0,0 +1,293 @@
+//! Decoding and encoding of Base 64.
+//!
+//! The Base 64 encoding is defined in [RFC 4648]. It is used in master
+//! files for record data that contains binary blobs such as keys,
+//! signatures, or certificates.
+//!
+//! [RFC 4648]: https://tools.ietf.org/html/rfc4648
+
+use std::{error, fmt};
+
+
+//------------ Convenience Functions -----------------------------------------
+
+/// Decodes a string with Base 64 encoded data.
+///
+/// The function attempts to decode the entire string and returns the
+/// result as a bytes vec.
+pub fn decode(s: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut decoder = Decoder::new();
+    for ch in s.bytes() {
+        try!(decoder.push(ch));
+    }
+    decoder.finalize()
+}
+
+/// Formats binary data as Base 64 into a formatter.
+///
+/// The data is written as one long string without any line breaks or
+/// spaces.
+pub fn display<B: AsRef<[u8]>>(bytes: B, f: &mut fmt::Formatter)
+                               -> fmt::Result {
+    use std::fmt::Write;
+
+    for chunk in bytes.as_ref().chunks(3) {
+        match chunk.len() {
+            1 => {
+                try!(f.write_char(ENCODE_ALPHABET[(chunk[0] >> 2) as usize]));
+                try!(f.write_char(
+                    ENCODE_ALPHABET[((chunk[0] & 0x03) << 4) as usize]
+                ));
+                try!(f.write_str("=="));
+            }
+            2 => {
+                try!(f.write_char(ENCODE_ALPHABET[(chunk[0] >> 2) as usize]));
+                try!(f.write_char(
+                    ENCODE_ALPHABET[((chunk[0] & 0x03) << 4 | chunk[1] >> 4)
+                                    as usize]
+                ));
+                try!(f.write_char(
+                    ENCODE_ALPHABET[((chunk[1] & 0x0F) << 2) as usize]
+                ));
+                try!(f.write_char('='));
+            }
+            _ => {
+                try!(f.write_char(ENCODE_ALPHABET[(chunk[0] >> 2) as usize]));
+                try!(f.write_char(
+                    ENCODE_ALPHABET[((chunk[0] & 0x03) << 4 | chunk[1] >> 4)
+                                    as usize]
+                ));
+                try!(f.write_char(
+                    ENCODE_ALPHABET[((chunk[1] & 0x0F) << 2 | chunk[2] >> 6)
+                                    as usize]
+                ));
+                try!(f.write_char(
+                    ENCODE_ALPHABET[(chunk[2] & 0x3F) as usize]
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+
+//------------ Decoder -------------------------------------------------------
+
+/// A Base 64 decoder.
+///
+/// The decoder is fed with the encoded data one character at a time via
+/// the `push()` method. This allows decoding data that has been split into
+/// several pieces such as the words of a master file entry. Once all data
+/// has been pushed, `finalize()` checks that the input was complete and
+/// returns the decoded data.
+#[derive(Clone, Debug, Default)]
+pub struct Decoder {
+    /// A buffer for up to four characters.
+    ///
+    /// We only keep `u8`s here because only the lower six bits are used.
+    /// The value `PAD_MARKER` marks a padding character.
+    buf: [u8; 4],
+
+    /// The index in `buf` where we place the next character.
+    next: usize,
+
+    /// Whether we have seen padding and therefore the end of the data.
+    finished: bool,
+
+    /// The target for decoded output.
+    target: Vec<u8>,
+}
+
+impl Decoder {
+    /// Creates a new, empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes the next character of the encoded data into the decoder.
+    pub fn push(&mut self, ch: u8) -> Result<(), DecodeError> {
+        if self.finished {
+            return Err(DecodeError::TrailingInput)
+        }
+        let value = if ch == PAD {
+            // Padding is only allowed in the third and fourth position.
+            if self.next < 2 {
+                return Err(DecodeError::IllegalChar(ch))
+            }
+            PAD_MARKER
+        }
+        else {
+            // Once there was padding, there may only be more padding.
+            if self.next > 0 && self.buf[self.next - 1] == PAD_MARKER {
+                return Err(DecodeError::IllegalChar(ch))
+            }
+            match DECODE_ALPHABET.get(ch as usize) {
+                Some(&value) if value < 0x40 => value,
+                _ => return Err(DecodeError::IllegalChar(ch))
+            }
+        };
+        self.buf[self.next] = value;
+        self.next += 1;
+        if self.next == 4 {
+            self.target.push(self.buf[0] << 2 | self.buf[1] >> 4);
+            if self.buf[2] == PAD_MARKER {
+                if self.buf[3] != PAD_MARKER {
+                    return Err(DecodeError::IllegalChar(PAD))
+                }
+                self.finished = true;
+            }
+            else {
+                self.target.push(self.buf[1] << 4 | self.buf[2] >> 2);
+                if self.buf[3] == PAD_MARKER {
+                    self.finished = true;
+                }
+                else {
+                    self.target.push(self.buf[2] << 6 | self.buf[3]);
+                }
+            }
+            self.next = 0;
+        }
+        Ok(())
+    }
+
+    /// Finishes decoding and returns the decoded data.
+    ///
+    /// This fails if the encoded data ended in the middle of a group of
+    /// four characters.
+    pub fn finalize(self) -> Result<Vec<u8>, DecodeError> {
+        if self.next != 0 {
+            Err(DecodeError::ShortInput)
+        }
+        else {
+            Ok(self.target)
+        }
+    }
+}
+
+
+//------------ DecodeError ---------------------------------------------------
+
+/// An error happened while decoding Base 64 data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecodeError {
+    /// A character was encountered that is not allowed at this place.
+    IllegalChar(u8),
+
+    /// There was more data after the padding.
+    TrailingInput,
+
+    /// The data ended before a group of four characters was complete.
+    ShortInput,
+}
+
+impl error::Error for DecodeError {
+    fn description(&self) -> &str {
+        match *self {
+            DecodeError::IllegalChar(_) => "illegal character",
+            DecodeError::TrailingInput => "trailing input",
+            DecodeError::ShortInput => "incomplete input",
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::IllegalChar(ch) => {
+                write!(f, "illegal character '{}'", ch as char)
+            }
+            DecodeError::TrailingInput => f.write_str("trailing input"),
+            DecodeError::ShortInput => f.write_str("incomplete input"),
+        }
+    }
+}
+
+
+//------------ Constants -----------------------------------------------------
+
+/// The alphabet used for decoding.
+///
+/// This maps encoding characters into their values. A value of 0xFF stands
+/// in for illegal characters. We only provide the first 128 characters
+/// since the alphabet will only use ASCII characters.
+const DECODE_ALPHABET: [u8; 128] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,  // 0x00 .. 0x07
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,  // 0x08 .. 0x0F
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,  // 0x10 .. 0x17
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,  // 0x18 .. 0x1F
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,  // 0x20 .. 0x27
+    0xFF, 0xFF, 0xFF, 0x3E, 0xFF, 0xFF, 0xFF, 0x3F,  // 0x28 .. 0x2F
+    0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3A, 0x3B,  // 0x30 .. 0x37
+    0x3C, 0x3D, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,  // 0x38 .. 0x3F
+    0xFF, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,  // 0x40 .. 0x47
+    0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,  // 0x48 .. 0x4F
+    0x0F, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16,  // 0x50 .. 0x57
+    0x17, 0x18, 0x19, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,  // 0x58 .. 0x5F
+    0xFF, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E, 0x1F, 0x20,  // 0x60 .. 0x67
+    0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28,  // 0x68 .. 0x6F
+    0x29, 0x2A, 0x2B, 0x2C, 0x2D, 0x2E, 0x2F, 0x30,  // 0x70 .. 0x77
+    0x31, 0x32, 0x33, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,  // 0x78 .. 0x7F
+];
+
+/// The alphabet used for encoding.
+const ENCODE_ALPHABET: [char; 64] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H',
+    'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P',
+    'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X',
+    'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f',
+    'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n',
+    'o', 'p', 'q', 'r', 's', 't', 'u', 'v',
+    'w', 'x', 'y', 'z', '0', '1', '2', '3',
+    '4', '5', '6', '7', '8', '9', '+', '/',
+];
+
+/// The padding character.
+const PAD: u8 = b'=';
+
+/// The marker for a padding character in the decoder’s buffer.
+const PAD_MARKER: u8 = 0x80;
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use std::fmt;
+    use super::*;
+
+    struct Base64<'a>(&'a [u8]);
+
+    impl<'a> fmt::Display for Base64<'a> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            display(self.0, f)
+        }
+    }
+
+    #[test]
+    fn decode_str() {
+        assert_eq!(decode("").unwrap(), b"");
+        assert_eq!(decode("Zg==").unwrap(), b"f");
+        assert_eq!(decode("Zm8=").unwrap(), b"fo");
+        assert_eq!(decode("Zm9v").unwrap(), b"foo");
+        assert_eq!(decode("Zm9vYg==").unwrap(), b"foob");
+        assert_eq!(decode("Zm9vYmE=").unwrap(), b"fooba");
+        assert_eq!(decode("Zm9vYmFy").unwrap(), b"foobar");
+
+        assert_eq!(decode("Zm9vYmE=Zm9v"), Err(DecodeError::TrailingInput));
+        assert_eq!(decode("Zm9vYmE"), Err(DecodeError::ShortInput));
+        assert_eq!(decode("Z==="), Err(DecodeError::IllegalChar(b'=')));
+        assert_eq!(decode("Zg=v"), Err(DecodeError::IllegalChar(b'v')));
+        assert_eq!(decode("Zm9v-mFy"), Err(DecodeError::IllegalChar(b'-')));
+    }
+
+    #[test]
+    fn display_bytes() {
+        assert_eq!(format!("{}", Base64(b"")), "");
+        assert_eq!(format!("{}", Base64(b"f")), "Zg==");
+        assert_eq!(format!("{}", Base64(b"fo")), "Zm8=");
+        assert_eq!(format!("{}", Base64(b"foo")), "Zm9v");
+        assert_eq!(format!("{}", Base64(b"foob")), "Zm9vYg==");
+        assert_eq!(format!("{}", Base64(b"fooba")), "Zm9vYmE=");
+        assert_eq!(format!("{}", Base64(b"foobar")), "Zm9vYmFy");
+    }
+}
@@ -0,0 +1,55 @@
+//! Access to the current time.
+//!
+//! Checking whether signatures are within their validity period requires
+//! knowing the current time. In order to make this testable, the check
+//! doesn’t ask the system directly but rather goes through the `Clock`
+//! trait defined here. Normally, you will want to use `SystemClock` which
+//! provides the system time. `FixedClock` always returns the same time and
+//! is mostly useful for testing.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+
+//------------ Clock ---------------------------------------------------------
+
+/// A source for the current time.
+pub trait Clock {
+    /// Returns the current time as seconds since the Unix epoch.
+    fn unix_time(&self) -> u64;
+}
+
+impl<'a, C: Clock + ?Sized> Clock for &'a C {
+    fn unix_time(&self) -> u64 {
+        (**self).unix_time()
+    }
+}
+
+
+//------------ SystemClock ---------------------------------------------------
+
+/// A clock providing the system time.
+///
+/// If the system time is before the Unix epoch, the clock returns zero.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn unix_time(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH)
+                         .map(|duration| duration.as_secs())
+                         .unwrap_or(0)
+    }
+}
+
+
+//------------ FixedClock ----------------------------------------------------
+
+/// A clock that is stuck at a given time.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn unix_time(&self) -> u64 {
+        self.0
+    }
+}
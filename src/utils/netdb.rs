@@ -3,10 +3,10 @@
 //! This module provides a placeholder implementation for some of the
 //! functions included in POSIX’s (?) network database.
 //!
-//! For the moment, implementations here are functional in the sense that
-//! they simulate an empty database. Since we are only using this for
-//! parsing WKS records for the moment, this seems to be a reasonably
-//! shortcut.
+//! For the moment, the database isn’t read from the system but consists
+//! of a small built-in table of the most common protocols and well-known
+//! services. Since we are only using this for parsing WKS records for the
+//! moment, this seems to be a reasonably shortcut.
 //!
 //! Furthermore, if this gets implemented for real, it should be considered
 //! whether giving out clones of the entities is really necessary.
@@ -22,33 +22,41 @@ pub struct ProtoEnt {
 
 impl ProtoEnt {
     pub fn by_name(name: &str) -> Option<Self> {
-        let _ = name;
-        None
+        Self::iter().find(|ent| ent.name.eq_ignore_ascii_case(name))
     }
 
     pub fn by_number(number: u8) -> Option<Self> {
-        let _ = number;
-        None
+        Self::iter().find(|ent| ent.proto == number)
     }
 
     pub fn iter() -> ProtoIter {
-        ProtoIter
+        ProtoIter(0)
+    }
+
+    fn from_entry(entry: &(&str, u8)) -> Self {
+        ProtoEnt {
+            name: entry.0.into(),
+            aliases: Vec::new(),
+            proto: entry.1
+        }
     }
 }
 
 
 //------------ ProtoIter ----------------------------------------------------
 
-pub struct ProtoIter;
+pub struct ProtoIter(usize);
 
 impl Iterator for ProtoIter {
     type Item = ProtoEnt;
 
     fn next(&mut self) -> Option<ProtoEnt> {
-        None
+        let res = PROTOCOLS.get(self.0).map(ProtoEnt::from_entry);
+        self.0 += 1;
+        res
     }
 }
-    
+
 
 //------------ ServEnt ------------------------------------------------------
 
@@ -61,30 +69,76 @@ pub struct ServEnt {
 
 impl ServEnt {
     pub fn by_name(name: &str) -> Option<Self> {
-        let _ = name;
-        None
+        Self::iter().find(|ent| ent.name.eq_ignore_ascii_case(name))
     }
 
     pub fn by_port(port: u16) -> Option<Self> {
-        let _ = port;
-        None
+        Self::iter().find(|ent| ent.port == port)
     }
 
     pub fn iter() -> ServIter {
-        ServIter
+        ServIter(0)
+    }
+
+    fn from_entry(entry: &(&str, u16)) -> Self {
+        ServEnt {
+            name: entry.0.into(),
+            aliases: Vec::new(),
+            port: entry.1,
+            proto: "tcp".into()
+        }
     }
 }
 
 
 //------------ ServIter ------------------------------------------------------
 
-pub struct ServIter;
+pub struct ServIter(usize);
 
 impl Iterator for ServIter {
     type Item = ServEnt;
 
     fn next(&mut self) -> Option<ServEnt> {
-        None
+        let res = SERVICES.get(self.0).map(ServEnt::from_entry);
+        self.0 += 1;
+        res
     }
 }
 
+
+//------------ The Tables ----------------------------------------------------
+
+/// The built-in protocols as pairs of name and protocol number.
+const PROTOCOLS: &[(&str, u8)] = &[
+    ("ip", 0), ("icmp", 1), ("igmp", 2), ("tcp", 6), ("udp", 17),
+    ("ipv6", 41), ("gre", 47), ("esp", 50), ("ah", 51), ("ipv6-icmp", 58),
+    ("sctp", 132),
+];
+
+/// The built-in well-known services as pairs of name and port.
+const SERVICES: &[(&str, u16)] = &[
+    ("echo", 7), ("discard", 9), ("daytime", 13), ("ftp-data", 20),
+    ("ftp", 21), ("ssh", 22), ("telnet", 23), ("smtp", 25), ("time", 37),
+    ("whois", 43), ("domain", 53), ("gopher", 70), ("finger", 79),
+    ("http", 80), ("kerberos", 88), ("pop3", 110), ("sunrpc", 111),
+    ("auth", 113), ("nntp", 119), ("ntp", 123), ("imap", 143),
+    ("snmp", 161), ("ldap", 389), ("https", 443), ("submission", 587),
+];
+
+
+//============ Testing =======================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(ProtoEnt::by_name("TCP").unwrap().proto, 6);
+        assert_eq!(ProtoEnt::by_number(17).unwrap().name, "udp");
+        assert!(ProtoEnt::by_name("bogus").is_none());
+        assert_eq!(ServEnt::by_name("smtp").unwrap().port, 25);
+        assert_eq!(ServEnt::by_port(80).unwrap().name, "http");
+        assert!(ServEnt::by_port(1).is_none());
+    }
+}
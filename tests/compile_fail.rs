@@ -0,0 +1,12 @@
+//! Compile-fail tests.
+//!
+//! These make sure that the rcode enums can’t be matched exhaustively
+//! outside of this crate.
+
+extern crate trybuild;
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,18 @@
+extern crate domain;
+
+use domain::iana::OptRcode;
+
+fn is_badvers(rcode: OptRcode) -> bool {
+    match rcode {
+        OptRcode::NoError | OptRcode::FormErr | OptRcode::ServFail
+        | OptRcode::NXDomain | OptRcode::NotImp | OptRcode::Refused
+        | OptRcode::YXDomain | OptRcode::YXRRSet | OptRcode::NXRRSet
+        | OptRcode::NotAuth | OptRcode::NotZone | OptRcode::DsoTypeNi
+        | OptRcode::BadCookie | OptRcode::Int(_) => false,
+        OptRcode::BadVers => true,
+    }
+}
+
+fn main() {
+    is_badvers(OptRcode::BadVers);
+}
@@ -0,0 +1,17 @@
+extern crate domain;
+
+use domain::iana::Rcode;
+
+fn is_nxdomain(rcode: Rcode) -> bool {
+    match rcode {
+        Rcode::NoError | Rcode::FormErr | Rcode::ServFail
+        | Rcode::NotImp | Rcode::Refused | Rcode::YXDomain
+        | Rcode::YXRRSet | Rcode::NXRRSet | Rcode::NotAuth
+        | Rcode::NotZone | Rcode::DsoTypeNi | Rcode::Int(_) => false,
+        Rcode::NXDomain => true,
+    }
+}
+
+fn main() {
+    is_nxdomain(Rcode::NXDomain);
+}
@@ -0,0 +1,20 @@
+extern crate domain;
+
+use domain::iana::TsigRcode;
+
+fn is_badtime(rcode: TsigRcode) -> bool {
+    match rcode {
+        TsigRcode::NoError | TsigRcode::FormErr | TsigRcode::ServFail
+        | TsigRcode::NXDomain | TsigRcode::NotImp | TsigRcode::Refused
+        | TsigRcode::YXDomain | TsigRcode::YXRRSet | TsigRcode::NXRRSet
+        | TsigRcode::NotAuth | TsigRcode::NotZone | TsigRcode::DsoTypeNi
+        | TsigRcode::BadSig | TsigRcode::BadKey | TsigRcode::BadMode
+        | TsigRcode::BadName | TsigRcode::BadAlg | TsigRcode::BadTrunc
+        | TsigRcode::BadCookie | TsigRcode::Int(_) => false,
+        TsigRcode::BadTime => true,
+    }
+}
+
+fn main() {
+    is_badtime(TsigRcode::BadTime);
+}